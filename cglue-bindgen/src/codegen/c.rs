@@ -33,7 +33,8 @@
 //! `MUT_SLICE`, `REF_SLICE`, `STR` macros allow to build slices out of strings or buffers,
 //! `COLLECT_CB` and its derivatives deal with buffer callbacks, `COUNT_CB` creates a callback that
 //! simply counts elements, `BUF_ITER` and its derivatives deal with constructing iterators from
-//! buffers.
+//! buffers. `CALLBACK_RET` builds callbacks with return values, while `BOX_CALLBACK_*` and
+//! `ONCE_CALLBACK_CALL` invoke and drop owned callbacks.
 //!
 
 use crate::config::*;
@@ -530,6 +531,24 @@ static inline bool cb_count_{typename}(size_t *cnt, {typename} info) {{
     size_t name##_count = 0; \
     Callback_c_void__##ty name = CALLBACK(ty, &name##_count, cb_count_##ty)
 
+// Constructs a callback with a return value
+#define CALLBACK_RET(ty, ret, ctx, func) \
+    (struct CallbackRet_c_void__##ty##__##ret){(ctx), (ret (*)(void *, ty))(func)}
+
+// Calls an owned callback (`CBoxCallback`)
+#define BOX_CALLBACK_CALL(cb, arg) ((cb).func((cb).context.instance, (arg)))
+
+// Drops an owned callback (`CBoxCallback`), or a one-shot callback (`COnceCallback`) that was
+// not called
+#define BOX_CALLBACK_DROP(cb) \
+    do { \
+        if ((cb).context.drop_fn) \
+            (cb).context.drop_fn((cb).context.instance, (cb).context.alloc); \
+    } while (0)
+
+// Calls a one-shot callback (`COnceCallback`). The callback is consumed and must not be dropped
+#define ONCE_CALLBACK_CALL(cb, arg) ((cb).func((cb).context, (arg)))
+
 #define BUF_ITER_SPEC(ty, ty2, name, buf, len) \
     struct BufferIterator name##_base = (struct BufferIterator){(const void *)(const ty2 *)buf, len, 0, sizeof(ty2)}; \
     CIterator_##ty name = (CIterator_##ty){ &name##_base, (int32_t (*)(void *, ty2 *))buf_iter_next }
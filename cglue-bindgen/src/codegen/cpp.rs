@@ -36,6 +36,9 @@
 //!
 //! Containers must be passed by pointer, while lambdas are taken in as const references.
 //!
+//! `CallbackRet`, `CBoxCallback`, and `COnceCallback` can be built with lambdas as well. Owned
+//! callbacks allocate the lambda on the heap, and provide `drop` and `forget` methods.
+//!
 //! ## Create vtable impl types.
 //!
 //! Basically, this is a quick way to build a vtable for an object, if you have a struct defined in
@@ -248,7 +251,9 @@ struct (?P<class>CSlice(Ref|Mut)) \{
         r"(?P<definition_start>template<typename T>
 struct CIterator \{
     void \*iter;
-    int32_t \(\*func\)\(void\*, T \*out\);)
+    int32_t \(\*func\)\(void\*, T \*out\);
+    int32_t \(\*next_back\)\(void\*, T \*out\);
+    uintptr_t \(\*size_hint\)\(const void\*, COption<uintptr_t> \*upper\);)
 \};",
     )?
     .replace(
@@ -330,6 +335,8 @@ struct CPPIterator {
     {
         iter.iter = &iter - offsetof(CPPIterator<Container>, iter);
         iter.func = &CPPIterator::next;
+        iter.next_back = nullptr;
+        iter.size_hint = nullptr;
     }
 
     CPPIterator(CPPIterator &&o) {
@@ -392,6 +399,123 @@ struct Callback \{
 };",
     );
 
+    let header = Regex::new(
+        r"(?P<definition>template<typename T, typename A, typename R>
+struct CallbackRet \{
+    T \*context;
+    R \(\*func\)\(T\*, A\);)
+\};",
+    )?
+    .replace(
+        &header,
+        r"$definition
+
+    template<typename Function>
+    static R functional(Function *function, A data) {
+        return (*function)(data);
+    }
+
+    CallbackRet() = default;
+
+    template<typename Function, typename = decltype(std::declval<Function>()(std::declval<A>()))>
+    CallbackRet(const Function &function) :
+        context((T *)&function),
+        func((decltype(func))(&CallbackRet::functional<Function>)) {}
+
+    inline R operator()(A data) {
+        return func(context, data);
+    }
+
+    constexpr operator CallbackRet<void, A, R> &() {
+        return *((CallbackRet<void, A, R> *)this);
+    }
+};",
+    );
+
+    let header = Regex::new(
+        r"(?P<definition>template<typename A, typename R>
+struct CBoxCallback \{
+    CBox<void> context;
+    R \(\*func\)\(void\*, A\);)
+\};",
+    )?
+    .replace(
+        &header,
+        r"$definition
+
+    template<typename Function>
+    static R functional(Function *function, A data) {
+        return (*function)(data);
+    }
+
+    CBoxCallback() = default;
+
+    template<typename Function, typename = decltype(std::declval<Function>()(std::declval<A>()))>
+    CBoxCallback(Function function) :
+        context(CBox<Function>(new Function(function), &CBox<Function>::delete_fn)),
+        func((decltype(func))(&CBoxCallback::functional<Function>)) {}
+
+    inline R operator()(A data) {
+        return func(context.instance, data);
+    }
+
+    inline void drop() && noexcept {
+        std::move(context).drop();
+        func = nullptr;
+    }
+
+    inline void forget() noexcept {
+        context.forget();
+        func = nullptr;
+    }
+};",
+    );
+
+    let header = Regex::new(
+        r"(?P<definition>template<typename A, typename R>
+struct COnceCallback \{
+    CBox<void> context;
+    R \(\*func\)\(CBox<void>, A\);)
+\};",
+    )?
+    .replace(
+        &header,
+        r"$definition
+
+    template<typename Function>
+    static R functional(CBox<Function> function, A data) {
+        struct Guard {
+            CBox<Function> &function;
+            ~Guard() { std::move(function).drop(); }
+        } guard{function};
+        return (*function.instance)(data);
+    }
+
+    COnceCallback() = default;
+
+    template<typename Function, typename = decltype(std::declval<Function>()(std::declval<A>()))>
+    COnceCallback(Function function) :
+        context(CBox<Function>(new Function(function), &CBox<Function>::delete_fn)),
+        func((decltype(func))(&COnceCallback::functional<Function>)) {}
+
+    inline R operator()(A data) && {
+        CBox<void> ctx = context;
+        context.forget();
+        return func(ctx, data);
+    }
+
+    inline void drop() && noexcept {
+        std::move(context).drop();
+        func = nullptr;
+    }
+
+    inline void forget() noexcept {
+        context.forget();
+        func = nullptr;
+    }
+};",
+    );
+
     let header_regex = Regex::new(
         r"(?P<start>(/\*[^*]*\*+(?:[^/*][^*]*\*+)*/
 )?template<typename)",
@@ -468,7 +592,8 @@ $start",
         r"(?P<definition>template<typename T>
 struct CBox \{
     T \*instance;
-    void \(\*drop_fn\)\(T\*\);)
+    void \(\*drop_fn\)\(T\*, const CAllocator\*\);
+    const CAllocator \*alloc;)
 \};",
     )?
     .replace(
@@ -476,19 +601,20 @@ struct CBox \{
         r"${definition}
 
     CBox() = default;
-    CBox(T *instance) : instance(instance), drop_fn(nullptr) {}
-    CBox(T *instance, void (*drop_fn)(T *)) : instance(instance), drop_fn(drop_fn) {}
+    CBox(T *instance) : instance(instance), drop_fn(nullptr), alloc(nullptr) {}
+    CBox(T *instance, void (*drop_fn)(T *, const CAllocator *)) : instance(instance), drop_fn(drop_fn), alloc(nullptr) {}
     template<typename U = T, class = typename std::enable_if<std::is_same<U, T>::value>::type, class = typename std::enable_if<!std::is_same<U, void>::value>::type>
-    CBox(U &&instance) : instance(new U(instance)), drop_fn(&CBox::delete_fn) {}
+    CBox(U &&instance) : instance(new U(instance)), drop_fn(&CBox::delete_fn), alloc(nullptr) {}
 
-    static void delete_fn(T *v) {
+    static void delete_fn(T *v, const CAllocator *) {
         delete v;
     }
 
     inline operator CBox<void> () const {
         CBox<void> ret;
         ret.instance = (void*)instance;
-        ret.drop_fn = (void(*)(void *))drop_fn;
+        ret.drop_fn = (void(*)(void *, const CAllocator *))drop_fn;
+        ret.alloc = alloc;
         return ret;
     }
 
@@ -496,18 +622,20 @@ struct CBox \{
         CBox ret;
         ret.instance = new T;
         ret.drop_fn = &CBox::delete_fn;
+        ret.alloc = nullptr;
         return ret;
     }
 
     inline void drop() && noexcept {
         if (drop_fn && instance)
-            drop_fn(instance);
+            drop_fn(instance, alloc);
         forget();
     }
 
     inline void forget() noexcept {
         instance = nullptr;
         drop_fn = nullptr;
+        alloc = nullptr;
     }
 
     inline T *operator->() {
@@ -526,7 +654,8 @@ struct CBox \{
 struct CArc \{
     const T \*instance;
     const T \*\(\*clone_fn\)\(const T\*\);
-    void \(\*drop_fn\)\(const T\*\);)
+    void \(\*drop_fn\)\(const T\*\);
+    const CArcVtbl<T> \*vtbl;)
 \};",
     )?
     .replace(
@@ -538,6 +667,7 @@ struct CArc \{
         ret.instance = clone_fn(instance);
         ret.clone_fn = clone_fn;
         ret.drop_fn = drop_fn;
+        ret.vtbl = vtbl;
         return ret;
     }
 
@@ -551,6 +681,7 @@ struct CArc \{
         instance = nullptr;
         clone_fn = nullptr;
         drop_fn = nullptr;
+        vtbl = nullptr;
     }
 };",
     );
@@ -999,7 +1130,7 @@ fn groups_regex(vtbls: &[Vtable], explicit_group: Option<&str>) -> Result<Regex>
         Itertools::intersperse(vtbls.iter().map(|v| v.name.as_str()), "|").collect::<String>();
 
     Regex::new(
-        &format!(r"(?P<definition_start> \* `as_ref_`, and `as_mut_` functions obtain references to safe objects, but do not
+        &format!(r"(?P<definition_start> \* `as_ref!`, and `as_mut!` macros obtain references to safe objects, but do not
  \* perform any memory transformations either. They are the safest to use, because
  \* there is no risk of accidentally consuming the whole object.
  \*/
@@ -21,7 +21,7 @@ impl<'a> ContainerType<'a> {
                     ty_prefix: "Box",
                     cpp_type: "CBox<void>",
                     drop_impl: Some(
-                        "if (self->drop_fn && self->instance) self->drop_fn(self->instance);",
+                        "if (self->drop_fn && self->instance) self->drop_fn(self->instance, self->alloc);",
                     ),
                 },
             ),
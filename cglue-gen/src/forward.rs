@@ -63,6 +63,20 @@ pub fn gen_forward(tr: ItemTrait, ext_path: Option<TokenStream>) -> TokenStream
         need_mut = nm || need_mut;
     }
 
+    // Associated functions returning `Self` are not forwarded, thus no boxing is needed.
+    let static_funcs =
+        super::traits::parse_static_funcs(&tr, &crate_path, (&quote!(Self), &quote!(ret)));
+
+    for func in static_funcs {
+        func.forward_wrapped_trait_impl(&mut impls);
+    }
+
+    for super::traits::ParsedConst { ident, ty, .. } in
+        super::traits::parse_consts(&tr, &crate_path)
+    {
+        impls.extend(quote!(const #ident: #ty = <CGlueT as #ext_path #trait_name<#life_use #gen_use>>::#ident;));
+    }
+
     let mut required_mutability = TokenStream::new();
 
     required_mutability.extend(quote!(::core::ops::Deref<Target = CGlueT>));
@@ -89,7 +103,7 @@ pub fn gen_forward(tr: ItemTrait, ext_path: Option<TokenStream>) -> TokenStream
     quote! {
         #tr_impl
 
-        impl<#life_declare CGlueO: #required_mutability #send_bound, CGlueT, #gen_declare> #ext_path #trait_name<#life_use #gen_use> for #crate_path::forward::Fwd<CGlueO> where CGlueT: #ext_path #trait_name<#life_use #gen_use>, #gen_where_bounds {
+        impl<#life_declare CGlueO: #required_mutability #send_bound, CGlueT: ?Sized, #gen_declare> #ext_path #trait_name<#life_use #gen_use> for #crate_path::forward::Fwd<CGlueO> where CGlueT: #ext_path #trait_name<#life_use #gen_use>, #gen_where_bounds {
             #wrapped_types
             #impls
         }
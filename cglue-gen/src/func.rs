@@ -254,6 +254,351 @@ fn do_wrap_type<'a>(
     }
 }
 
+/// Lowering of closure and iterator arguments to their FFI-safe counterparts.
+enum ArgLowering {
+    /// `FnMut(T) -> bool`, or `FnMut(T)`, lowered to `OpaqueCallback<T>`.
+    Callback {
+        arg: Box<Type>,
+        returns_bool: bool,
+        c_ty: TokenStream,
+    },
+    /// `FnMut(T) -> R`, lowered to `OpaqueCallbackRet<T, R>`.
+    CallbackRet { c_ty: TokenStream },
+    /// `Iterator<Item = T>`, lowered to `CIterator<T>`.
+    Iterator { c_ty: TokenStream },
+}
+
+impl ArgLowering {
+    fn new(
+        bounds: &Punctuated<TypeParamBound, Token![+]>,
+        crate_path: &TokenStream,
+    ) -> Option<Self> {
+        let mut traits = bounds.iter().filter_map(|b| match b {
+            TypeParamBound::Trait(t) => Some(t),
+            _ => None,
+        });
+
+        let seg = match (traits.next(), traits.next()) {
+            (Some(t), None) => t.path.segments.last()?,
+            _ => return None,
+        };
+
+        match (seg.ident.to_string().as_str(), &seg.arguments) {
+            ("FnMut", PathArguments::Parenthesized(a)) if a.inputs.len() == 1 => {
+                let arg = Box::new(a.inputs.first()?.clone());
+                match &a.output {
+                    ReturnType::Type(_, r) if !is_bool(r) => Some(Self::CallbackRet {
+                        c_ty: quote!(#crate_path::callback::OpaqueCallbackRet<#arg, #r>),
+                    }),
+                    ReturnType::Type(_, _) => Some(Self::Callback {
+                        c_ty: quote!(#crate_path::callback::OpaqueCallback<#arg>),
+                        arg,
+                        returns_bool: true,
+                    }),
+                    ReturnType::Default => Some(Self::Callback {
+                        c_ty: quote!(#crate_path::callback::OpaqueCallback<#arg>),
+                        arg,
+                        returns_bool: false,
+                    }),
+                }
+            }
+            ("Iterator", PathArguments::AngleBracketed(a)) if a.args.len() == 1 => {
+                match a.args.first()? {
+                    GenericArgument::Binding(b) if b.ident == "Item" => {
+                        let item = &b.ty;
+                        Some(Self::Iterator {
+                            c_ty: quote!(#crate_path::iter::CIterator<#item>),
+                        })
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Build argument conversion.
+    ///
+    /// `is_ref` signifies that the argument is a `&mut dyn Trait`, as opposed to `impl Trait`.
+    fn conv(
+        &self,
+        name: &TokenStream,
+        is_ref: bool,
+    ) -> (
+        TokenStream,
+        TokenStream,
+        TokenStream,
+        TokenStream,
+        TokenStream,
+    ) {
+        let reborrow = if is_ref { quote!(&mut) } else { quote!() };
+
+        let (c_ty, to_c_args, to_trait_arg) = match self {
+            Self::Callback {
+                arg,
+                returns_bool: true,
+                c_ty,
+            } => (
+                c_ty,
+                quote!(let mut #name = #name;),
+                quote!(#reborrow { let mut #name = #name; move |v: #arg| #name.call(v) },),
+            ),
+            Self::Callback {
+                arg,
+                returns_bool: false,
+                c_ty,
+            } => (
+                c_ty,
+                quote! {
+                    let mut #name = #name;
+                    let mut #name = move |v: #arg| {
+                        #name(v);
+                        true
+                    };
+                },
+                quote! {
+                    #reborrow {
+                        let mut #name = #name;
+                        move |v: #arg| {
+                            let _ = #name.call(v);
+                        }
+                    },
+                },
+            ),
+            Self::CallbackRet { c_ty } => (
+                c_ty,
+                quote!(let mut #name = #name;),
+                quote!(#reborrow { let mut #name = #name; move |v| #name.call(v) },),
+            ),
+            Self::Iterator { c_ty } => (
+                c_ty,
+                quote!(let mut #name = #name;),
+                if is_ref {
+                    quote!(&mut { #name },)
+                } else {
+                    quote!(#name,)
+                },
+            ),
+        };
+
+        (
+            to_c_args,
+            quote!((&mut #name).into(),),
+            quote!(#name: #c_ty,),
+            quote!(#name: #c_ty,),
+            to_trait_arg,
+        )
+    }
+}
+
+fn is_bool(ty: &Type) -> bool {
+    matches!(ty, Type::Path(p) if p.path.is_ident("bool"))
+}
+
+/// Attribute that marks `impl Trait`, `&dyn Trait` and `&mut dyn Trait` arguments of CGlue
+/// traits, which get converted to trait objects.
+const OBJ_ARG_ATTR: &str = "wrap_with_obj";
+
+fn is_obj_arg(attr: &Attribute) -> bool {
+    attr.path.is_ident(OBJ_ARG_ATTR)
+}
+
+/// Remove the [`OBJ_ARG_ATTR`] attribute from the argument.
+///
+/// The attribute is only understood by CGlue, thus it must not be emitted in the final code.
+pub fn strip_obj_arg_attr(arg: &mut FnArg) {
+    if let FnArg::Typed(t) = arg {
+        t.attrs.retain(|a| !is_obj_arg(a));
+    }
+}
+
+/// Build argument conversion for `impl Trait`, `&dyn Trait` and `&mut dyn Trait` of CGlue traits.
+///
+/// Only arguments marked with `#[wrap_with_obj]` are converted. The caller side converts the
+/// argument to an opaque trait object through `trait_obj!`, and the callee receives the object.
+///
+/// `reference` is `None` for `impl Trait`, and signifies mutability for trait object references.
+/// Owned arguments are moved into a `CUnsendBox`, because `impl Trait` is not bound by `Send`,
+/// thus the callee takes ownership of them.
+/// Trait object references are wrapped in `Fwd`, thus the trait needs `#[cglue_forward]`.
+fn obj_arg_conv(
+    bounds: &Punctuated<TypeParamBound, Token![+]>,
+    name: &TokenStream,
+    reference: Option<bool>,
+    crate_path: &TokenStream,
+) -> Option<(
+    TokenStream,
+    TokenStream,
+    TokenStream,
+    TokenStream,
+    TokenStream,
+)> {
+    let mut traits = bounds.iter().filter_map(|b| match b {
+        TypeParamBound::Trait(t) => Some(t),
+        _ => None,
+    });
+
+    let tr = match (traits.next(), traits.next()) {
+        (Some(t), None) if matches!(t.modifier, TraitBoundModifier::None) => t,
+        _ => return None,
+    };
+
+    let trait_path = &tr.path;
+
+    let last = trait_path.segments.last()?;
+
+    let gen_args = match &last.arguments {
+        PathArguments::None => quote!(),
+        PathArguments::AngleBracketed(a)
+            if a.args
+                .iter()
+                .all(|a| matches!(a, GenericArgument::Type(_) | GenericArgument::Lifetime(_))) =>
+        {
+            let args = &a.args;
+            quote!(#args)
+        }
+        _ => return None,
+    };
+
+    let mut obj_path = trait_path.clone();
+    let obj_seg = obj_path.segments.last_mut()?;
+    obj_seg.arguments = PathArguments::None;
+
+    let (to_c_args, to_trait_arg) = match reference {
+        None => {
+            obj_seg.ident = format_ident!("{}UnsendBox", obj_seg.ident);
+            (
+                quote! {
+                    let #name = #crate_path::boxed::CUnsendBox::from(#name);
+                    let #name = #crate_path::trait_obj!(#name as #trait_path);
+                },
+                quote!(#name,),
+            )
+        }
+        Some(true) => {
+            obj_seg.ident = format_ident!("{}Mut", obj_seg.ident);
+            (
+                quote! {
+                    let mut #name = #crate_path::forward::Fwd(#name);
+                    let #name = #crate_path::trait_obj!(&mut #name as #trait_path);
+                },
+                quote!(&mut { #name },),
+            )
+        }
+        Some(false) => {
+            obj_seg.ident = format_ident!("{}Ref", obj_seg.ident);
+            (
+                quote! {
+                    let #name = #crate_path::forward::Fwd(#name);
+                    let #name = #crate_path::trait_obj!(&#name as #trait_path);
+                },
+                quote!(&#name,),
+            )
+        }
+    };
+
+    let c_ty = quote!(#obj_path<'_, #gen_args>);
+
+    Some((
+        to_c_args,
+        quote!(#name,),
+        quote!(#name: #c_ty,),
+        quote!(#name: #c_ty,),
+        to_trait_arg,
+    ))
+}
+
+/// Map an owned standard library container to its FFI-safe counterpart.
+///
+/// `Vec<T>`, `String`, `Box<T>`, `Box<[T]>` and `Arc<T>` are mapped to `CVec<T>`, `ReprCString`,
+/// `CBox<T>`, `CSliceBox<T>` and `CArcSome<T>` respectively. Boxes are given lifetime `lt`.
+///
+/// Returns the C type, the method that converts it back to the std type, and whether that method
+/// is unsafe. Boxes can only be converted back, because the generated code always creates them
+/// with their allocator recorded.
+fn std_container(
+    ty: &Type,
+    lt: &Lifetime,
+    crate_path: &TokenStream,
+) -> Option<(Type, Ident, bool)> {
+    let seg = match ty {
+        Type::Path(p) if p.qself.is_none() => p.path.segments.last()?,
+        _ => return None,
+    };
+
+    let arg = match &seg.arguments {
+        PathArguments::AngleBracketed(a) if a.args.len() == 1 => match a.args.first()? {
+            GenericArgument::Type(t) => Some(t),
+            _ => return None,
+        },
+        PathArguments::None => None,
+        _ => return None,
+    };
+
+    let into = format_ident!("into");
+
+    let into_box = format_ident!("into_box");
+
+    let (new_ty, conv, is_unsafe) = match (seg.ident.to_string().as_str(), arg) {
+        ("Vec", Some(t)) => (quote!(#crate_path::vec::CVec<#t>), into, false),
+        ("String", None) => (quote!(#crate_path::repr_cstring::ReprCString), into, false),
+        ("Box", Some(Type::Slice(s))) => {
+            let t = &s.elem;
+            (
+                quote!(#crate_path::boxed::CSliceBox<#lt, #t>),
+                into_box,
+                true,
+            )
+        }
+        ("Box", Some(Type::Path(p))) if p.path.is_ident("str") => return None,
+        // `Box` is fundamental, thus `CBox` can not implement `Into<Box<T>>`.
+        ("Box", Some(t @ Type::Path(_)))
+        | ("Box", Some(t @ Type::Tuple(_)))
+        | ("Box", Some(t @ Type::Array(_))) => {
+            (quote!(#crate_path::boxed::CBox<#lt, #t>), into_box, true)
+        }
+        ("Arc", Some(t)) => (
+            quote!(#crate_path::arc::CArcSome<#t>),
+            format_ident!("into_arc_or_clone"),
+            false,
+        ),
+        _ => return None,
+    };
+
+    parse2(new_ty).ok().map(|ty| (ty, conv, is_unsafe))
+}
+
+/// Conversion of a receiver that consumes the object.
+///
+/// `this` is the expression that evaluates to the object by value.
+fn owned_receiver_conv(
+    this: TokenStream,
+    crate_path: &TokenStream,
+) -> (
+    TokenStream,
+    TokenStream,
+    TokenStream,
+    TokenStream,
+    TokenStream,
+) {
+    (
+        quote! {
+            let cont = #this.into_ccont();
+            // Guard against failure cases where context drops the library.
+            // Only happens where self gets consumed.
+            // TODO: make a breaking change in cobj_owned so this is not needed
+            // separately
+            // TODO 2: figure out how to test this.
+            let __ctx = #crate_path::trait_group::CGlueObjBase::cobj_base_ref(&cont).1.clone();
+            let cont = #crate_path::trait_group::OpaqueHelper::new(cont);
+        },
+        quote!(cont,),
+        quote!(cont: #crate_path::trait_group::OpaqueHelper<CGlueC>,),
+        quote!(cont: #crate_path::trait_group::OpaqueHelper<CGlueC>,),
+        quote!(),
+    )
+}
+
 impl TraitArgConv {
     fn new(
         arg: &FnArg,
@@ -269,22 +614,7 @@ impl TraitArgConv {
                 let lifetime_cast = inject_lifetime_cast.or_else(|| r.lifetime());
 
                 if r.reference.is_none() {
-                    (
-                        quote! {
-                            let cont = self.into_ccont();
-                            // Guard against failure cases where context drops the library.
-                            // Only happens where self gets consumed.
-                            // TODO: make a breaking change in cobj_owned so this is not needed
-                            // separately
-                            // TODO 2: figure out how to test this.
-                            let __ctx = #crate_path::trait_group::CGlueObjBase::cobj_base_ref(&cont).1.clone();
-                            let cont = #crate_path::trait_group::OpaqueHelper::new(cont);
-                        },
-                        quote!(cont,),
-                        quote!(cont: #crate_path::trait_group::OpaqueHelper<CGlueC>,),
-                        quote!(cont: #crate_path::trait_group::OpaqueHelper<CGlueC>,),
-                        quote!(),
-                    )
+                    owned_receiver_conv(quote!(self), crate_path)
                 } else if r.mutability.is_some() {
                     (
                         quote!(let cont = self.ccont_mut();),
@@ -303,6 +633,19 @@ impl TraitArgConv {
                     )
                 }
             }
+            t if recv_box(t) => owned_receiver_conv(quote!((*self)), crate_path),
+            t if recv_arc(t) => {
+                let lifetime = inject_lifetime;
+                let lifetime_cast = inject_lifetime_cast;
+
+                (
+                    quote!(let cont = self.ccont_ref();),
+                    quote!(cont,),
+                    quote!(cont: &#lifetime CGlueC,),
+                    quote!(cont: &#lifetime_cast CGlueC,),
+                    quote!(),
+                )
+            }
             t if recv_pin(t) => {
                 let lifetime = inject_lifetime.or_else(|| recv_lifetime(t));
                 let lifetime_cast = inject_lifetime_cast.or_else(|| recv_lifetime(t));
@@ -329,6 +672,8 @@ impl TraitArgConv {
                 let mut t = t.clone();
                 let _old = do_wrap_type(&mut t.ty, targets);
 
+                let is_obj = t.attrs.iter().any(is_obj_arg);
+
                 let name = &*t.pat;
 
                 // TODO: handle more edge cases here
@@ -381,6 +726,16 @@ impl TraitArgConv {
                                 None
                             }
                             .map(|v| (v, true)),
+                            Type::TraitObject(t) => {
+                                ret = ArgLowering::new(&t.bounds, crate_path)
+                                    .filter(|_| is_mut)
+                                    .map(|l| l.conv(&name, true))
+                                    .or_else(|| {
+                                        obj_arg_conv(&t.bounds, &name, Some(is_mut), crate_path)
+                                            .filter(|_| is_obj)
+                                    });
+                                None
+                            }
                             _ => None,
                         };
 
@@ -398,8 +753,25 @@ impl TraitArgConv {
                             ))
                         }
                     }
-                    // TODO: Warn if Box is being used.
                     Type::Path(p) => {
+                        // Owned std containers get converted to their C counterparts.
+                        let elided = Lifetime::new("'_", proc_macro2::Span::call_site());
+                        if let Some((c_ty, conv, is_unsafe)) =
+                            std_container(&ty, &elided, crate_path)
+                        {
+                            ret = Some((
+                                quote!(let #name = #name.into();),
+                                quote!(#name,),
+                                quote!(#name: #c_ty,),
+                                quote!(#name: #c_ty,),
+                                if is_unsafe {
+                                    quote!(unsafe { #name.#conv() },)
+                                } else {
+                                    quote!(#name.#conv(),)
+                                },
+                            ));
+                        }
+
                         // Here we check for any Option types, and wrap them to COption if they can
                         // not be NPOd.
                         let last = p.path.segments.last();
@@ -450,6 +822,14 @@ impl TraitArgConv {
                         }
                     }
                     Type::ImplTrait(t) => {
+                        // Convert `impl FnMut(T)` and `impl Iterator<Item = T>`, or `impl Trait` of
+                        // a CGlue trait.
+                        ret = ArgLowering::new(&t.bounds, crate_path)
+                            .map(|l| l.conv(&name, false))
+                            .or_else(|| {
+                                obj_arg_conv(&t.bounds, &name, None, crate_path).filter(|_| is_obj)
+                            });
+
                         // Convert `impl Into<T>` to `T`.
                         if t.bounds.len() == 1 {
                             if let TypeParamBound::Trait(t) = t.bounds.first().unwrap() {
@@ -574,6 +954,46 @@ fn recv_mutable(recv: &FnArg) -> bool {
     }
 }
 
+/// Extract the smart pointer wrapping `Self`, such as `Box` in `self: Box<Self>`.
+fn extract_self_ptr(t: &Type) -> Option<&Ident> {
+    if let Type::Path(v) = t {
+        if let Some(seg) = v.path.segments.last() {
+            if let PathArguments::AngleBracketed(a) = &seg.arguments {
+                if let (1, Some(GenericArgument::Type(Type::Path(p)))) =
+                    (a.args.len(), a.args.first())
+                {
+                    if p.path.is_ident("Self") {
+                        return Some(&seg.ident);
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+fn recv_ptr(recv: &FnArg, ptr: &str) -> bool {
+    match recv {
+        FnArg::Receiver(_) => false,
+        FnArg::Typed(t) => extract_self_ptr(&t.ty).map(|i| i == ptr) == Some(true),
+    }
+}
+
+/// `self: Box<Self>` receiver. These consume the object, just like `self`.
+fn recv_box(recv: &FnArg) -> bool {
+    recv_ptr(recv, "Box")
+}
+
+/// `self: Arc<Self>` receiver. These borrow the object, and clone the arc inside.
+fn recv_arc(recv: &FnArg) -> bool {
+    recv_ptr(recv, "Arc")
+}
+
+/// Whether the receiver consumes the object.
+fn recv_owned(recv: &FnArg) -> bool {
+    !recv_reference(recv) && !recv_arc(recv)
+}
+
 impl ParsedFunc {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -615,6 +1035,32 @@ impl ParsedFunc {
 
         let receiver = receiver?;
 
+        if let FnArg::Typed(t) = &receiver {
+            if recv_ptr(&receiver, "CArc") || recv_ptr(&receiver, "CArcSome") {
+                panic!(
+                    "Unsupported receiver type `{}` in `{}`! It requires the unstable `arbitrary_self_types` feature, use `self: Arc<Self>` instead, which is passed as `CArc` across FFI.",
+                    t.ty.to_token_stream(),
+                    name
+                );
+            }
+
+            if recv_ptr(&receiver, "Rc") {
+                panic!(
+                    "Unsupported receiver type `{}` in `{}`! There is no FFI-safe `Rc` to pass across FFI, use `self: Arc<Self>` instead, which also accepts types that are not `Send`.",
+                    t.ty.to_token_stream(),
+                    name
+                );
+            }
+
+            if !recv_pin(&receiver) && !recv_box(&receiver) && !recv_arc(&receiver) {
+                panic!(
+                    "Unsupported receiver type `{}` in `{}`! Only `Pin<&Self>`, `Pin<&mut Self>`, `Box<Self>` and `Arc<Self>` are supported.",
+                    t.ty.to_token_stream(),
+                    name
+                );
+            }
+        }
+
         let out = ParsedReturnType::new(
             (sig.output, custom_impl.as_ref().map(|i| &i.c_ret_ty)),
             wrap_types,
@@ -635,6 +1081,7 @@ impl ParsedFunc {
         }) = custom_impl
         {
             orig_args.extend(sig.inputs.into_iter());
+            orig_args.iter_mut().for_each(strip_obj_arg_attr);
             // But first, we need to process the receiver (self) type, as it is implicit.
             for arg in orig_args
                 .iter()
@@ -668,7 +1115,7 @@ impl ParsedFunc {
                 impl_func_ret,
             }
         } else {
-            for input in sig.inputs.into_iter() {
+            for mut input in sig.inputs.into_iter() {
                 let func = TraitArgConv::new(
                     &input,
                     wrap_types,
@@ -679,6 +1126,7 @@ impl ParsedFunc {
                 );
 
                 args.push(func);
+                strip_obj_arg_attr(&mut input);
                 orig_args.push(input);
             }
 
@@ -895,6 +1343,17 @@ impl ParsedFunc {
         ret
     }
 
+    /// Lifetime of the receiver that needs to be bound in the function pointer.
+    ///
+    /// This is the case when the receiver borrows for a lifetime declared on the trait, and the
+    /// return type does not already bind it, such as `fn get(&'a mut self) -> Lent<'a>`.
+    fn recv_hrtb(&self) -> TokenStream {
+        match recv_lifetime(&self.receiver) {
+            Some(lt) if lt.ident != "cglue_a" && lt.ident != "static" => quote!(#lt),
+            _ => quote!(),
+        }
+    }
+
     /// Create a VTable definition for this function
     pub fn vtbl_def(&self, stream: &mut TokenStream) {
         let name = &self.name;
@@ -926,7 +1385,7 @@ impl ParsedFunc {
         ) {
             (_, Some(lifetime), false) => (quote!(#lifetime), self.c_impl_args_cast(), c_cast_out),
             (Some(lifetime), _, _) => (quote!(#lifetime), args, c_out),
-            _ => (quote!(), args, c_out),
+            _ => (self.recv_hrtb(), args, c_out),
         };
 
         let sig_life_declare = merge_lifetime_declarations(&sig_life_declare, &parse_quote!(#hrtb));
@@ -976,7 +1435,7 @@ impl ParsedFunc {
         ) {
             (_, Some(lifetime), false) => (quote!(#lifetime), self.c_impl_args_cast(), c_cast_out),
             (Some(lifetime), _, _) => (quote!(#lifetime), args, c_out),
-            _ => (quote!(), args, c_out),
+            _ => (self.recv_hrtb(), args, c_out),
         };
 
         let sig_life_declare = merge_lifetime_declarations(&sig_life_declare, &parse_quote!(#hrtb));
@@ -1090,13 +1549,15 @@ impl ParsedFunc {
 
         let sig_life_declare = remap_lifetime_defs(sig_life_declare, lifetime_map);
 
-        let tmp_lifetime = if *use_hrtb && !life_use.is_empty() {
+        let use_hrtb = *use_hrtb || !self.recv_hrtb().is_empty();
+
+        let tmp_lifetime = if use_hrtb && !life_use.is_empty() {
             quote!('cglue_b, )
         } else {
             quote!(#life_use)
         };
 
-        let tmp_lifetime_anon = if *use_hrtb && !life_use.is_empty() {
+        let tmp_lifetime_anon = if use_hrtb && !life_use.is_empty() {
             quote!('_, )
         } else {
             quote!(#life_use)
@@ -1114,14 +1575,34 @@ impl ParsedFunc {
 
         let mut container_bound = quote!();
 
-        let (c_pre_call, cglue_c_into_inner) = if !recv_reference(&self.receiver) {
+        let (c_pre_call, cglue_c_into_inner) = if recv_arc(&self.receiver) {
+            container_bound.extend(quote!(#trg_path::CGlueObjInst<Context = CGlueCtx> + ));
+
+            (
+                quote! {
+                    let (this, cglue_ctx) = cont.cobj_inst();
+                    let this = unsafe { #trg_path::CloneArc::clone_arc(this) };
+                    #c_pre_call
+                },
+                Some(quote!(
+                    CGlueC::InstType: #trg_path::CloneArc<ArcTarget = CGlueC::ObjType>,
+                )),
+            )
+        } else if !recv_reference(&self.receiver) {
             container_bound.extend(quote!(#trg_path::CGlueObjBase<Context = CGlueCtx> + ));
 
+            let into_box = if recv_box(&self.receiver) {
+                quote!(let this = ::core::convert::From::from(this);)
+            } else {
+                quote!()
+            };
+
             (
                 quote! {
                     let cont = cont.take_raw();
                     let (this, cglue_ctx) = cont.cobj_base_owned();
                     let this = unsafe { #trg_path::IntoInner::into_inner(this) };
+                    #into_box
                     #c_pre_call
                 },
                 Some(quote!(
@@ -1207,7 +1688,7 @@ impl ParsedFunc {
         }
     }
 
-    pub fn trait_impl(&self, tokens: &mut TokenStream) -> (bool, bool, bool) {
+    pub fn trait_impl(&self, tokens: &mut TokenStream) -> (bool, bool, bool, bool) {
         if !self.only_c_side {
             let name = &self.name;
             let args = self.trait_args();
@@ -1264,7 +1745,8 @@ impl ParsedFunc {
 
         (
             recv_mutable(&self.receiver),
-            !recv_reference(&self.receiver),
+            recv_owned(&self.receiver),
+            recv_arc(&self.receiver),
             self.out.return_self,
         )
     }
@@ -1374,6 +1856,521 @@ impl ParsedFunc {
     }
 }
 
+/// Check whether the type refers to `Self` anywhere.
+fn contains_self(ty: &Type) -> bool {
+    fn tokens_contain_self(tokens: TokenStream) -> bool {
+        tokens.into_iter().any(|t| match t {
+            proc_macro2::TokenTree::Ident(i) => i == "Self",
+            proc_macro2::TokenTree::Group(g) => tokens_contain_self(g.stream()),
+            _ => false,
+        })
+    }
+
+    tokens_contain_self(ty.to_token_stream())
+}
+
+/// Return value lowering of an associated function.
+struct StaticRet {
+    /// Return type on the C side.
+    c_ty: TokenStream,
+    /// Return type of the factory function.
+    ty: TokenStream,
+    /// Conversion of `ret` from the trait function's return type into `c_ty`.
+    to_c: TokenStream,
+    /// Conversion of `ret` from `c_ty` into `ty`.
+    from_c: TokenStream,
+    /// Whether the types are passed through as-is.
+    trivial: bool,
+    /// Whether `Self` is being returned.
+    returns_self: bool,
+}
+
+impl StaticRet {
+    fn passthrough(ty: &Type) -> Self {
+        Self {
+            c_ty: quote!(#ty),
+            ty: quote!(#ty),
+            to_c: quote!(ret),
+            from_c: quote!(ret),
+            trivial: true,
+            returns_self: false,
+        }
+    }
+
+    /// Lower the return type.
+    ///
+    /// `Self` gets converted into the opaque boxed trait object, and it may be wrapped in `Option`,
+    /// or `Result`. These two get converted to their C counterparts, if needed.
+    ///
+    /// Returns `None` if `Self` is used in any other way.
+    fn new(
+        ty: &Type,
+        box_ty: &TokenStream,
+        box_conv: &TokenStream,
+        crate_path: &TokenStream,
+    ) -> Option<Self> {
+        let p = match ty {
+            Type::Path(p) if p.qself.is_none() => p,
+            Type::Paren(p) => return Self::new(&p.elem, box_ty, box_conv, crate_path),
+            t if contains_self(t) => return None,
+            t => return Some(Self::passthrough(t)),
+        };
+
+        if p.path.is_ident("Self") {
+            return Some(Self {
+                c_ty: box_ty.clone(),
+                ty: box_ty.clone(),
+                to_c: box_conv.clone(),
+                from_c: quote!(ret),
+                trivial: false,
+                returns_self: true,
+            });
+        }
+
+        let last = p.path.segments.last()?;
+
+        let mut args = match &last.arguments {
+            PathArguments::AngleBracketed(a) => a.args.iter(),
+            _ if contains_self(ty) => return None,
+            _ => return Some(Self::passthrough(ty)),
+        };
+
+        match (
+            last.ident.to_string().as_str(),
+            args.next(),
+            args.next(),
+            args.next(),
+        ) {
+            ("Option", Some(GenericArgument::Type(a)), None, None) => {
+                let inner = Self::new(a, box_ty, box_conv, crate_path)?;
+
+                if inner.trivial && crate::util::is_null_pointer_optimizable(a, &[]) {
+                    return Some(Self::passthrough(ty));
+                }
+
+                let StaticRet {
+                    c_ty,
+                    ty,
+                    to_c,
+                    from_c,
+                    returns_self,
+                    ..
+                } = inner;
+
+                Some(Self {
+                    c_ty: quote!(#crate_path::option::COption<#c_ty>),
+                    ty: quote!(Option<#ty>),
+                    to_c: quote!(ret.map(|ret| #to_c).into()),
+                    from_c: quote!(Option::from(ret).map(|ret| #from_c)),
+                    trivial: false,
+                    returns_self,
+                })
+            }
+            ("Result", Some(GenericArgument::Type(a)), Some(GenericArgument::Type(e)), None)
+                if !contains_self(e) =>
+            {
+                let StaticRet {
+                    c_ty,
+                    ty,
+                    to_c,
+                    from_c,
+                    returns_self,
+                    ..
+                } = Self::new(a, box_ty, box_conv, crate_path)?;
+
+                Some(Self {
+                    c_ty: quote!(#crate_path::result::CResult<#c_ty, #e>),
+                    ty: quote!(Result<#ty, #e>),
+                    to_c: quote!(ret.map(|ret| #to_c).into()),
+                    from_c: quote!(Result::from(ret).map(|ret| #from_c)),
+                    trivial: false,
+                    returns_self,
+                })
+            }
+            _ if contains_self(ty) => None,
+            _ => Some(Self::passthrough(ty)),
+        }
+    }
+}
+
+/// Associated function of a trait, that does not take a receiver.
+///
+/// These functions can not be placed in the object vtable, instead, they get exposed through the
+/// trait's factory table. `Self` is returned as the opaque boxed trait object.
+pub struct ParsedStaticFunc {
+    name: Ident,
+    trait_name: Ident,
+    safe: bool,
+    abi: FuncAbi,
+    unwind_safe: bool,
+    has_default: bool,
+    orig_args: Vec<PatType>,
+    args: Vec<TraitArgConv>,
+    out: ReturnType,
+    ret: StaticRet,
+    generics: ParsedGenerics,
+    sig_generics: ParsedGenerics,
+}
+
+impl ParsedStaticFunc {
+    /// Parse an associated function.
+    ///
+    /// `box_ty` is the opaque boxed trait object type, and `box_conv` converts `ret` of type
+    /// `CGlueT` into it.
+    ///
+    /// Returns `None` if the function can not be exposed through FFI, that is, if it is generic
+    /// over types, takes `Self` as an argument, or returns `Self` nested in anything other than
+    /// `Option` or `Result`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        sig: Signature,
+        trait_name: Ident,
+        generics: &ParsedGenerics,
+        crate_path: &TokenStream,
+        (box_ty, box_conv): (&TokenStream, &TokenStream),
+        has_default: bool,
+        unwind_safe: bool,
+    ) -> Option<Self> {
+        if sig
+            .generics
+            .params
+            .iter()
+            .any(|p| !matches!(p, GenericParam::Lifetime(_)))
+        {
+            return None;
+        }
+
+        let mut orig_args = vec![];
+        let mut args = vec![];
+
+        for input in sig.inputs.iter() {
+            match input {
+                FnArg::Typed(t) if !contains_self(&t.ty) => {
+                    args.push(TraitArgConv::new(
+                        input,
+                        &Default::default(),
+                        crate_path,
+                        None,
+                        None,
+                        &Default::default(),
+                    ));
+                    orig_args.push(t.clone());
+                }
+                _ => return None,
+            }
+        }
+
+        let ret = match &sig.output {
+            ReturnType::Default => StaticRet::passthrough(&parse_quote!(())),
+            ReturnType::Type(_, ty) => StaticRet::new(ty, box_ty, box_conv, crate_path)?,
+        };
+
+        Some(Self {
+            name: sig.ident,
+            trait_name,
+            safe: sig.unsafety.is_none(),
+            abi: From::from(sig.abi),
+            unwind_safe,
+            has_default,
+            orig_args,
+            args,
+            out: sig.output,
+            ret,
+            generics: generics.clone(),
+            sig_generics: ParsedGenerics::from(&sig.generics),
+        })
+    }
+
+    pub fn returns_self(&self) -> bool {
+        self.ret.returns_self
+    }
+
+    fn get_safety(&self) -> TokenStream {
+        if self.safe {
+            quote!()
+        } else {
+            quote!(unsafe)
+        }
+    }
+
+    fn extern_abi(&self) -> TokenStream {
+        if self.unwind_safe {
+            quote!("C-unwind")
+        } else {
+            quote!("C")
+        }
+    }
+
+    fn c_out(&self) -> TokenStream {
+        match &self.out {
+            ReturnType::Default => quote!(),
+            _ => {
+                let c_ty = &self.ret.c_ty;
+                quote!(-> #c_ty)
+            }
+        }
+    }
+
+    fn factory_out(&self) -> TokenStream {
+        match &self.out {
+            ReturnType::Default => quote!(),
+            _ => {
+                let ty = &self.ret.ty;
+                quote!(-> #ty)
+            }
+        }
+    }
+
+    fn trait_args(&self) -> TokenStream {
+        let mut ret = TokenStream::new();
+
+        for PatType { pat, ty, .. } in &self.orig_args {
+            match &**pat {
+                Pat::Ident(PatIdent { ident, .. }) => quote!(#ident: #ty,),
+                pat => quote!(#pat: #ty,),
+            }
+            .to_tokens(&mut ret);
+        }
+
+        ret
+    }
+
+    fn trait_passthrough_args(&self) -> TokenStream {
+        let mut ret = TokenStream::new();
+
+        for PatType { pat, .. } in &self.orig_args {
+            quote!(#pat,).to_tokens(&mut ret);
+        }
+
+        ret
+    }
+
+    /// Create a factory table definition for this function.
+    pub fn factory_def(&self, stream: &mut TokenStream) {
+        let name = &self.name;
+        let unsafety = self.get_safety();
+        let extern_abi = self.extern_abi();
+        let sig_life_declare = &self.sig_generics.life_declare;
+        let c_out = self.c_out();
+
+        let mut args = TokenStream::new();
+
+        for arg in &self.args {
+            arg.c_impl_args.to_tokens(&mut args);
+        }
+
+        // ABI-Stable does not support C-unwind ABI, so hack around that.
+        if cfg!(feature = "layout_checks") && self.unwind_safe {
+            let ty = quote!(for<#sig_life_declare> #unsafety extern "C" fn(#args) #c_out);
+            #[cfg(not(feature = "abi_stable11"))]
+            let ty = ty.to_string();
+            stream.extend(quote!(#[sabi(unsafe_change_type = #ty)]));
+        }
+
+        stream.extend(quote! {
+            #name: for<#sig_life_declare> #unsafety extern #extern_abi fn(#args) #c_out,
+        });
+    }
+
+    /// Create a factory function that calls into the table.
+    pub fn factory_func_def(&self, stream: &mut TokenStream) {
+        let name = &self.name;
+        let safety = self.get_safety();
+        let args = self.trait_args();
+        let out = self.factory_out();
+        let from_c = &self.ret.from_c;
+        let sig_life_declare = &self.sig_generics.life_declare;
+
+        let mut def_args = TokenStream::new();
+        let mut call_args = TokenStream::new();
+
+        for arg in &self.args {
+            arg.to_c_args.to_tokens(&mut def_args);
+            arg.call_c_args.to_tokens(&mut call_args);
+        }
+
+        let doc_text = format!(
+            " Call `{}::{}` of the underlying type.",
+            self.trait_name, name
+        );
+
+        stream.extend(quote! {
+            #[doc = #doc_text]
+            #[inline(always)]
+            pub #safety fn #name<#sig_life_declare>(&self, #args) #out {
+                #def_args
+                let ret = (self.#name)(#call_args);
+                #from_c
+            }
+        });
+    }
+
+    /// Create a wrapper implementation body for this function.
+    ///
+    /// `box_bound` is the bound needed by `box_conv` to create the boxed trait object.
+    pub fn cfunc_def(
+        &self,
+        tokens: &mut TokenStream,
+        assocs: &ParsedGenerics,
+        assoc_equality: &TokenStream,
+        box_bound: &TokenStream,
+    ) {
+        let name = &self.name;
+        let trname = &self.trait_name;
+        let fnname = format_ident!("{}{}", FN_PREFIX, name);
+        let safety = self.get_safety();
+        let extern_abi = self.extern_abi();
+        let c_out = self.c_out();
+        let to_c = &self.ret.to_c;
+
+        let ParsedGenerics {
+            life_declare,
+            life_use,
+            gen_declare,
+            gen_use,
+            gen_where_bounds,
+            ..
+        } = &self.generics;
+
+        let assoc_declare = &assocs.gen_declare;
+
+        let sig_life_declare = &self.sig_generics.life_declare;
+
+        let life_anon = life_use
+            .iter()
+            .map(|_| quote!('_,))
+            .collect::<TokenStream>();
+
+        let box_bound = if self.returns_self() {
+            box_bound.clone()
+        } else {
+            quote!()
+        };
+
+        let mut args = TokenStream::new();
+        let mut call_args = TokenStream::new();
+
+        for arg in &self.args {
+            arg.c_impl_args.to_tokens(&mut args);
+            arg.to_trait_arg.to_tokens(&mut call_args);
+        }
+
+        tokens.extend(quote! {
+            #safety extern #extern_abi fn #fnname<#sig_life_declare 'cglue_a, CGlueT, #gen_declare #assoc_declare>(#args) #c_out
+            where
+                #gen_where_bounds
+                CGlueT: 'cglue_a + for<#life_declare> #trname<#life_use #gen_use #assoc_equality>,
+                #box_bound
+            {
+                let ret = <CGlueT as #trname<#life_anon #gen_use>>::#name(#call_args);
+                #to_c
+            }
+        });
+    }
+
+    pub fn factory_default_def(&self, tokens: &mut TokenStream, assocs: &ParsedGenerics) {
+        let name = &self.name;
+        let fnname = format_ident!("{}{}", FN_PREFIX, name);
+        let gen_use = &self.generics.gen_use;
+        let assoc_use = &assocs.gen_use;
+        tokens.extend(quote!(#name: #fnname::<CGlueT, #gen_use #assoc_use>,));
+    }
+
+    fn stub_impl(&self, tokens: &mut TokenStream) {
+        let name = &self.name;
+        let out = &self.out;
+        let safety = self.get_safety();
+        let abi = self.abi.prefix();
+        let sig_life_declare = &self.sig_generics.life_declare;
+
+        let mut args = TokenStream::new();
+
+        for PatType { ty, .. } in &self.orig_args {
+            quote!(_: #ty,).to_tokens(&mut args);
+        }
+
+        let msg = format!(
+            "`{}::{}` can not be called on a CGlue object, use `{}Factory` instead",
+            self.trait_name, name, self.trait_name
+        );
+
+        let crate_path = crate::util::crate_path();
+
+        // The constant only gets evaluated once the function is instantiated, thus the error is
+        // raised at compile time for the callers alone.
+        tokens.extend(quote! {
+            #[inline(always)]
+            #safety #abi fn #name<#sig_life_declare>(#args) #out {
+                struct CGlueStub<T: ?Sized>(::core::marker::PhantomData<T>);
+
+                impl<T: ?Sized> CGlueStub<T> {
+                    const CALL: () = #crate_path::__cglue_const_panic!(#msg);
+                }
+
+                #[allow(clippy::let_unit_value)]
+                let () = CGlueStub::<Self>::CALL;
+                unreachable!()
+            }
+        });
+    }
+
+    /// Implement the function for CGlue objects.
+    ///
+    /// There is no underlying type to call the function on, thus, if there is no default
+    /// implementation, calling it fails to compile.
+    pub fn trait_impl(&self, tokens: &mut TokenStream) {
+        if !self.has_default {
+            self.stub_impl(tokens);
+        }
+    }
+
+    /// Implement the function for `Fwd`, which calls it on the underlying type.
+    ///
+    /// Functions returning `Self` can not be forwarded, thus, if there is no default
+    /// implementation, calling them fails to compile.
+    pub fn forward_wrapped_trait_impl(&self, tokens: &mut TokenStream) {
+        if self.returns_self() {
+            if !self.has_default {
+                self.stub_impl(tokens);
+            }
+            return;
+        }
+
+        let name = &self.name;
+        let args = self.trait_args();
+        let passthrough_args = self.trait_passthrough_args();
+        let out = &self.out;
+        let safety = self.get_safety();
+        let abi = self.abi.prefix();
+        let sig_life_declare = &self.sig_generics.life_declare;
+
+        tokens.extend(quote! {
+            #[inline(always)]
+            #safety #abi fn #name<#sig_life_declare>(#args) #out {
+                CGlueT::#name(#passthrough_args)
+            }
+        });
+    }
+
+    /// Implement the function by calling into the external trait `ext_trait`.
+    pub fn int_trait_impl(&self, ext_trait: &TokenStream, tokens: &mut TokenStream) {
+        let name = &self.name;
+        let args = self.trait_args();
+        let passthrough_args = self.trait_passthrough_args();
+        let out = &self.out;
+        let safety = self.get_safety();
+        let abi = self.abi.prefix();
+        let sig_life_declare = &self.sig_generics.life_declare;
+
+        tokens.extend(quote! {
+            #[inline(always)]
+            #safety #abi fn #name<#sig_life_declare>(#args) #out {
+                <Self as #ext_trait>::#name(#passthrough_args)
+            }
+        });
+    }
+}
+
 #[derive(Debug, Eq, PartialEq)]
 enum FuncAbi {
     ReprC,
@@ -1648,7 +2645,7 @@ impl ParsedReturnType {
                     _ => (None, quote!()),
                 };
 
-                let c_pre_call = if recv_reference(receiver) {
+                let c_pre_call = if !recv_owned(receiver) {
                     quote!(#c_pre_call let cglue_ctx = cglue_ctx.clone();)
                 } else {
                     c_pre_call
@@ -1690,6 +2687,31 @@ impl ParsedReturnType {
                 ret.c_out = quote!(-> #ty);
             }
 
+            // Owned std containers get converted to their C counterparts.
+            let static_lt = Lifetime::new("'static", proc_macro2::Span::call_site());
+            if let Some((new_ty, conv, is_unsafe)) = std_container(ty, &static_lt, crate_path) {
+                if let Some(ty_cast) = ty_cast.as_deref_mut() {
+                    if let Some((new_ty_cast, _, _)) =
+                        std_container(ty_cast, &static_lt, crate_path)
+                    {
+                        *ty_cast = new_ty_cast;
+                    }
+                }
+                **ty = new_ty;
+
+                ret.c_out = quote!(-> #ty);
+                ret.c_cast_out = quote!(-> #ty_cast);
+                ret.c_ret.extend(quote!(.into()));
+                if is_unsafe {
+                    let old_ret = ret.impl_func_ret;
+                    ret.impl_func_ret = quote! {
+                        unsafe { #old_ret .#conv() }
+                    }
+                } else {
+                    ret.impl_func_ret.extend(quote!(.#conv()));
+                }
+            }
+
             match &mut **ty {
                 Type::Reference(r) => {
                     let is_mut = r.mutability.is_some();
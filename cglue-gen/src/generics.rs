@@ -1,4 +1,4 @@
-use crate::util::{parse_punctuated, recurse_type_to_path};
+use crate::util::recurse_type_to_path;
 use proc_macro2::TokenStream;
 use quote::*;
 use std::collections::{HashMap, HashSet};
@@ -43,13 +43,15 @@ pub struct ParsedGenerics {
     ///
     /// For instance: `'a: 'b,` becomes just `'a,`.
     pub life_use: Punctuated<Lifetime, Comma>,
-    /// Type declarations on the left side of the type/trait.
+    /// Type and const declarations on the left side of the type/trait.
     ///
-    /// This may include any trait bounds it contains, for instance: `T: Clone,`.
-    pub gen_declare: Punctuated<TypeParam, Comma>,
+    /// This may include any trait bounds it contains, for instance: `T: Clone,`, or the type of a
+    /// const generic, for instance: `const N: usize,`.
+    pub gen_declare: Punctuated<GenericParam, Comma>,
     /// Declarations that "use" the traits i.e. has bounds stripped.
     ///
-    /// For instance: `T: Clone,` becomes just `T,`.
+    /// For instance: `T: Clone,` becomes just `T,`. Const generics are stored as paths (`N`), or
+    /// verbatim expressions, if they are concrete (`4`).
     pub gen_use: Punctuated<Type, Comma>,
     /// All where predicates, without the `where` keyword.
     pub gen_where_bounds: Punctuated<WherePredicate, Comma>,
@@ -60,11 +62,152 @@ pub struct ParsedGenerics {
     pub gen_remaps: HashMap<Ident, Type>,
 }
 
+/// Identifier of a type, or const generic parameter.
+pub fn param_ident(param: &GenericParam) -> &Ident {
+    match param {
+        GenericParam::Type(ty) => &ty.ident,
+        GenericParam::Const(cn) => &cn.ident,
+        GenericParam::Lifetime(lt) => &lt.lifetime.ident,
+    }
+}
+
+fn type_param(ident: Ident, bounds: Punctuated<TypeParamBound, Token![+]>) -> GenericParam {
+    GenericParam::Type(TypeParam {
+        attrs: vec![],
+        ident,
+        colon_token: None,
+        bounds,
+        eq_token: None,
+        default: None,
+    })
+}
+
+/// Parse a generic argument, or a const generic declaration (`const N: usize`).
+///
+/// Const declarations are returned as plain `N` arguments, alongside the declaration itself.
+fn parse_generic_argument(input: ParseStream) -> Result<(GenericArgument, Option<ConstParam>)> {
+    if input.peek(Token![const]) {
+        let param: ConstParam = input.parse()?;
+        let arg = GenericArgument::Type(ident_path(param.ident.clone()));
+        Ok((arg, Some(param)))
+    } else {
+        Ok((input.parse()?, None))
+    }
+}
+
+/// Parse a type that may declare const generics in place of its generic arguments.
+///
+/// For instance, `Buf<T, const N: usize>` is parsed as `Buf<T, N>`, and `const N: usize` is
+/// returned separately. Parsing stops at the first top-level comma, `where` clause, or braces.
+pub fn parse_type_with_consts(input: ParseStream) -> Result<(Type, Vec<ConstParam>)> {
+    use proc_macro2::TokenTree;
+
+    let mut tokens = TokenStream::new();
+    let mut consts = vec![];
+    let mut depth = 0usize;
+
+    while !input.is_empty() {
+        if depth > 0 && input.peek(Token![const]) {
+            let param: ConstParam = input.parse()?;
+            param.ident.to_tokens(&mut tokens);
+            consts.push(param);
+            continue;
+        }
+
+        let tt: TokenTree = input.fork().parse()?;
+
+        match &tt {
+            TokenTree::Punct(p) if p.as_char() == ',' && depth == 0 => break,
+            TokenTree::Ident(i) if i == "where" && depth == 0 => break,
+            TokenTree::Group(g) if g.delimiter() == proc_macro2::Delimiter::Brace && depth == 0 => {
+                break
+            }
+            TokenTree::Punct(p) if p.as_char() == '<' => depth += 1,
+            // Skip `->` arrows.
+            TokenTree::Punct(p) if p.as_char() == '>' && !tokens_end_with_dash(&tokens) => {
+                depth = depth.saturating_sub(1)
+            }
+            _ => {}
+        }
+
+        input.parse::<TokenTree>()?;
+        tokens.extend(Some(tt));
+    }
+
+    Ok((parse2(tokens)?, consts))
+}
+
+fn tokens_end_with_dash(tokens: &TokenStream) -> bool {
+    matches!(
+        tokens.clone().into_iter().last(),
+        Some(proc_macro2::TokenTree::Punct(p)) if p.as_char() == '-'
+    )
+}
+
 impl ParsedGenerics {
-    pub fn declare_without_nonstatic_bounds(&self) -> Punctuated<TypeParam, Comma> {
+    /// Turn type declarations with the given identifiers into const generic declarations.
+    pub fn apply_consts<'a>(&mut self, consts: impl IntoIterator<Item = &'a ConstParam>) {
+        for cn in consts {
+            for p in self.gen_declare.iter_mut() {
+                if param_ident(p) == &cn.ident {
+                    *p = GenericParam::Const(cn.clone());
+                }
+            }
+        }
+    }
+
+    /// Check whether the used generic is a const generic.
+    pub fn is_const_use(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Verbatim(_) => true,
+            ty => ty_ident(ty)
+                .map(|ident| {
+                    self.gen_declare
+                        .iter()
+                        .any(|p| matches!(p, GenericParam::Const(cn) if &cn.ident == ident))
+                })
+                .unwrap_or(false),
+        }
+    }
+
+    /// Used generic types, with const generics filtered out.
+    pub fn type_use(&self) -> impl Iterator<Item = &Type> {
+        self.gen_use.iter().filter(move |ty| !self.is_const_use(ty))
+    }
+
+    /// Declarations for type aliases.
+    ///
+    /// Types are declared without any bounds, while const generics keep their types. For instance:
+    /// `T: Clone, const N: usize,` becomes `T, const N: usize,`.
+    pub fn alias_declare(&self) -> TokenStream {
+        let mut ts = TokenStream::new();
+
+        for ty in &self.gen_use {
+            let cn = ty_ident(ty).and_then(|ident| {
+                self.gen_declare.iter().find_map(|p| match p {
+                    GenericParam::Const(cn) if &cn.ident == ident => Some(cn),
+                    _ => None,
+                })
+            });
+
+            match cn {
+                Some(ConstParam {
+                    ident, ty: cn_ty, ..
+                }) => ts.extend(quote!(const #ident: #cn_ty,)),
+                None => ts.extend(quote!(#ty,)),
+            }
+        }
+
+        ts
+    }
+
+    pub fn declare_without_nonstatic_bounds(&self) -> Punctuated<GenericParam, Comma> {
         let mut ret = self.gen_declare.clone();
 
-        for p in ret.iter_mut() {
+        for p in ret.iter_mut().filter_map(|p| match p {
+            GenericParam::Type(ty) => Some(ty),
+            _ => None,
+        }) {
             p.bounds = std::mem::take(&mut p.bounds)
                 .into_iter()
                 .filter(|b| {
@@ -84,7 +227,7 @@ impl ParsedGenerics {
     pub fn declare_lt_for_all(&self, lt: &TokenStream) -> TokenStream {
         let mut ts = TokenStream::new();
 
-        for p in &self.gen_use {
+        for p in self.type_use() {
             ts.extend(quote!(#p: #lt,));
         }
 
@@ -99,7 +242,7 @@ impl ParsedGenerics {
     pub fn declare_sabi_for_all(&self, crate_path: &TokenStream) -> TokenStream {
         let mut ts = TokenStream::new();
 
-        for p in &self.gen_use {
+        for p in self.type_use() {
             ts.extend(quote!(#p: #crate_path::trait_group::GenericTypeBounds,));
         }
 
@@ -157,7 +300,7 @@ impl ParsedGenerics {
 
                 gen_declare.push_value(decl.clone());
                 gen_declare.push_punct(Default::default());
-                gen_use.push_value(ident_path(decl.ident.clone()));
+                gen_use.push_value(ident_path(param_ident(decl).clone()));
                 gen_use.push_punct(Default::default());
 
                 applied_typenames.insert(ident);
@@ -204,13 +347,13 @@ impl ParsedGenerics {
         for val in old_gen_declare.into_pairs() {
             match val {
                 punctuated::Pair::Punctuated(p, punc) => {
-                    if !self.gen_remaps.contains_key(&p.ident) {
+                    if !self.gen_remaps.contains_key(param_ident(&p)) {
                         self.gen_declare.push_value(p);
                         self.gen_declare.push_punct(punc);
                     }
                 }
                 punctuated::Pair::End(p) => {
-                    if !self.gen_remaps.contains_key(&p.ident) {
+                    if !self.gen_remaps.contains_key(param_ident(&p)) {
                         self.gen_declare.push_value(p);
                     }
                 }
@@ -244,7 +387,11 @@ impl ParsedGenerics {
     pub fn phantom_data_definitions(&self) -> TokenStream {
         let mut stream = TokenStream::new();
 
-        for ty in self.gen_declare.iter() {
+        // Const generics are allowed to be unused, thus they do not need phantom data.
+        for ty in self.gen_declare.iter().filter_map(|p| match p {
+            GenericParam::Type(ty) => Some(ty),
+            _ => None,
+        }) {
             let ty_ident = format_ident!("_ty_{}", ty.ident.to_string().to_lowercase());
             let ty = &ty.ident;
             stream.extend(quote!(#ty_ident: ::core::marker::PhantomData<#ty>,));
@@ -257,7 +404,10 @@ impl ParsedGenerics {
     pub fn phantom_data_init(&self) -> TokenStream {
         let mut stream = TokenStream::new();
 
-        for ty in self.gen_declare.iter() {
+        for ty in self.gen_declare.iter().filter_map(|p| match p {
+            GenericParam::Type(ty) => Some(ty),
+            _ => None,
+        }) {
             let ty_ident = format_ident!("_ty_{}", ty.ident.to_string().to_lowercase());
             stream.extend(quote!(#ty_ident: ::core::marker::PhantomData{},));
         }
@@ -363,14 +513,14 @@ impl<'a> std::iter::FromIterator<&'a ParsedGenerics> for ParsedGenerics {
 
             for gen in val.gen_declare.pairs() {
                 let (val, punct) = gen.into_tuple();
-                if gen_declared.contains(&val.ident) {
+                if gen_declared.contains(param_ident(val)) {
                     continue;
                 }
                 gen_declare.push_value(val.clone());
                 if let Some(punct) = punct {
                     gen_declare.push_punct(*punct);
                 }
-                gen_declared.insert(&val.ident);
+                gen_declared.insert(param_ident(val));
             }
 
             gen_where_bounds.extend(val.gen_where_bounds.clone());
@@ -420,21 +570,15 @@ impl From<&Punctuated<GenericArgument, Comma>> for ParsedGenerics {
             match param {
                 GenericArgument::Type(ty) => {
                     if let Some(ident) = ty_ident(ty).cloned() {
-                        gen_declare.push_value(TypeParam {
-                            attrs: vec![],
-                            ident,
-                            colon_token: None,
-                            bounds: Punctuated::new(),
-                            eq_token: None,
-                            default: None,
-                        });
+                        gen_declare.push_value(type_param(ident, Punctuated::new()));
                         gen_declare.push_punct(Default::default());
                     }
                     gen_use.push_value(ty.clone());
                     gen_use.push_punct(Default::default());
                 }
-                GenericArgument::Const(_cn) => {
-                    // TODO
+                GenericArgument::Const(cn) => {
+                    gen_use.push_value(Type::Verbatim(cn.to_token_stream()));
+                    gen_use.push_punct(Default::default());
                 }
                 GenericArgument::Lifetime(lifetime) => {
                     life_use.push_value(lifetime.clone());
@@ -450,14 +594,10 @@ impl From<&Punctuated<GenericArgument, Comma>> for ParsedGenerics {
                 GenericArgument::Constraint(constraint) => {
                     gen_use.push_value(ident_path(constraint.ident.clone()));
                     gen_use.push_punct(Default::default());
-                    gen_declare.push_value(TypeParam {
-                        attrs: vec![],
-                        ident: constraint.ident.clone(),
-                        colon_token: None,
-                        bounds: constraint.bounds.clone(),
-                        eq_token: None,
-                        default: None,
-                    });
+                    gen_declare.push_value(type_param(
+                        constraint.ident.clone(),
+                        constraint.bounds.clone(),
+                    ));
                     gen_declare.push_punct(Default::default());
                 }
                 GenericArgument::Binding(bind) => {
@@ -494,11 +634,19 @@ impl From<&Generics> for ParsedGenerics {
                 GenericParam::Type(ty) => {
                     gen_use.push_value(ident_path(ty.ident.clone()));
                     gen_use.push_punct(Default::default());
-                    gen_declare.push_value(ty.clone());
+                    gen_declare.push_value(param.clone());
                     gen_declare.push_punct(Default::default());
                 }
-                GenericParam::Const(_cn) => {
-                    // TODO
+                GenericParam::Const(cn) => {
+                    gen_use.push_value(ident_path(cn.ident.clone()));
+                    gen_use.push_punct(Default::default());
+                    // Defaults are only allowed on type definitions.
+                    gen_declare.push_value(GenericParam::Const(ConstParam {
+                        eq_token: None,
+                        default: None,
+                        ..cn.clone()
+                    }));
+                    gen_declare.push_punct(Default::default());
                 }
                 GenericParam::Lifetime(lt) => {
                     let lifetime = &lt.lifetime;
@@ -521,22 +669,39 @@ impl From<&Generics> for ParsedGenerics {
     }
 }
 
-fn parse_generic_arguments(input: ParseStream) -> Punctuated<GenericArgument, Comma> {
-    parse_punctuated(input)
+fn parse_generic_arguments(
+    input: ParseStream,
+) -> (Punctuated<GenericArgument, Comma>, Vec<ConstParam>) {
+    let mut punct = Punctuated::new();
+    let mut consts = vec![];
+
+    while let Ok((arg, cn)) = parse_generic_argument(input) {
+        punct.push_value(arg);
+        consts.extend(cn);
+
+        if let Ok(comma) = input.parse::<Comma>() {
+            punct.push_punct(comma);
+        } else {
+            break;
+        }
+    }
+
+    (punct, consts)
 }
 
 impl Parse for ParsedGenerics {
     fn parse(input: ParseStream) -> Result<Self> {
-        let gens = match input.parse::<Lt>() {
+        let (gens, consts) = match input.parse::<Lt>() {
             Ok(_) => {
-                let punct = parse_generic_arguments(input);
+                let (punct, consts) = parse_generic_arguments(input);
                 input.parse::<Gt>()?;
-                Some(punct)
+                (Some(punct), consts)
             }
-            _ => None,
+            _ => (None, vec![]),
         };
 
-        let ret = Self::from(gens.as_ref());
+        let mut ret = Self::from(gens.as_ref());
+        ret.apply_consts(&consts);
 
         if let Ok(mut clause) = input.parse::<WhereClause>() {
             if !clause.predicates.trailing_punct() {
@@ -1,4 +1,6 @@
 use super::ext::*;
+#[cfg(not(feature = "unstable"))]
+use super::generics::parse_type_with_consts;
 use super::generics::ParsedGenerics;
 use crate::util::*;
 use itertools::*;
@@ -9,6 +11,7 @@ use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::*;
 
+#[derive(Clone)]
 pub struct AliasPath {
     path: Path,
     alias: Option<Ident>,
@@ -28,7 +31,23 @@ impl Parse for AliasPath {
     }
 }
 
+impl ToTokens for AliasPath {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.path.to_tokens(tokens);
+        if let Some(alias) = &self.alias {
+            tokens.extend(quote!(= #alias));
+        }
+    }
+}
+
 impl AliasPath {
+    /// Name the trait is referred to by within the group.
+    fn name(&self) -> Option<&Ident> {
+        self.alias
+            .as_ref()
+            .or_else(|| self.path.segments.last().map(|s| &s.ident))
+    }
+
     fn prelude_remap(self) -> Self {
         Self {
             path: prelude_remap(self.path),
@@ -52,6 +71,8 @@ pub struct TraitInfo {
     generics: ParsedGenerics,
     assocs: ParsedGenerics,
     vtbl_name: Ident,
+    /// Constant of the vtable filler trait, holding the optional vtable.
+    vtbl_const_name: Ident,
     ret_tmp_typename: Ident,
     ret_tmp_name: Ident,
     enable_vtbl_name: Ident,
@@ -115,6 +136,7 @@ impl From<AliasPath> for TraitInfo {
 
         Self {
             vtbl_name: format_ident!("vtbl_{}", lc_ident),
+            vtbl_const_name: format_ident!("CGLUE_VTBL_{}", lc_ident.to_uppercase()),
             lc_name: format_ident!("{}", lc_ident),
             vtbl_typename: format_ident!("{}Vtbl", raw_ident),
             vtbl_get_ident: format_ident!("{}VtblGet", raw_ident),
@@ -131,6 +153,62 @@ impl From<AliasPath> for TraitInfo {
     }
 }
 
+/// Traits of a base group, as passed by its callback macro.
+///
+/// Input is in the form of `#[vtbl_table] { mandatory }, { optional }, { ext_trait_defs }`, where
+/// the attribute is only present if the base group stores its vtables in a shared table.
+struct GroupBaseInfo {
+    vtbl_table: bool,
+    mandatory: Vec<AliasPath>,
+    optional: Vec<AliasPath>,
+    ext_trait_defs: Vec<ItemTrait>,
+}
+
+impl Parse for GroupBaseInfo {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let vtbl_table = parse_group_attrs(input)?;
+        let mandatory = parse_maybe_braced(input)?;
+        input.parse::<Token![,]>()?;
+        let optional = parse_maybe_braced(input)?;
+        input.parse::<Token![,]>()?;
+        let ext_trait_defs = parse_maybe_braced(input)?;
+
+        Ok(Self {
+            vtbl_table,
+            mandatory,
+            optional,
+            ext_trait_defs,
+        })
+    }
+}
+
+impl ToTokens for GroupBaseInfo {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Self {
+            vtbl_table,
+            mandatory,
+            optional,
+            ext_trait_defs,
+        } = self;
+
+        if *vtbl_table {
+            tokens.extend(quote!(#[vtbl_table]));
+        }
+
+        tokens.extend(quote!({ #(#mandatory),* }, { #(#optional),* }, { #(#ext_trait_defs)* }));
+    }
+}
+
+/// Describes the group a derived trait group is based on.
+struct GroupBase {
+    /// Path to the base group, without generic arguments.
+    path: Path,
+    /// Whether the base group stores its vtables in a shared table.
+    vtbl_table: bool,
+    mandatory_vtbl: Vec<TraitInfo>,
+    optional_vtbl: Vec<TraitInfo>,
+}
+
 /// Describes parse trait group, allows to generate code for it.
 #[cfg_attr(feature = "unstable", allow(unused))]
 pub struct TraitGroup {
@@ -141,29 +219,275 @@ pub struct TraitGroup {
     optional_vtbl: Vec<TraitInfo>,
     ext_traits: HashMap<Ident, (Path, ItemTrait)>,
     extra_filler_traits: bool,
+    /// Whether the object points to a shared table of vtables, instead of storing them inline.
+    vtbl_table: bool,
+    /// Traits and definitions, as they were given to the macro.
+    ///
+    /// These are forwarded to groups derived from this one.
+    base_info: GroupBaseInfo,
+    base: Option<GroupBase>,
+    /// Macro input of a derived group, for which base group information has not been retrieved yet.
+    unresolved: Option<(Path, TokenStream)>,
+}
+
+/// Pick traits of the base group that the derived group lists.
+///
+/// Returns the traits that are newly made mandatory, and the ones that stay optional.
+fn derive_traits(
+    base: &Path,
+    info: &GroupBaseInfo,
+    mandatory: Vec<AliasPath>,
+    optional: Vec<AliasPath>,
+) -> Result<(Vec<AliasPath>, Vec<AliasPath>)> {
+    let find =
+        |list: &[AliasPath], tr: &AliasPath| list.iter().find(|b| b.name() == tr.name()).cloned();
+
+    let not_found = |tr: &AliasPath| {
+        Error::new_spanned(
+            &tr.path,
+            format!(
+                "Trait `{}` is not part of the base group `{}`",
+                tr.to_token_stream(),
+                base.to_token_stream()
+            ),
+        )
+    };
+
+    let mut promoted = vec![];
+
+    for tr in mandatory {
+        if let Some(tr) = find(&info.optional, &tr) {
+            promoted.push(tr);
+        } else if find(&info.mandatory, &tr).is_none() {
+            return Err(not_found(&tr));
+        }
+    }
+
+    let mut kept = vec![];
+
+    for tr in optional {
+        if let Some(tr) = find(&info.optional, &tr) {
+            kept.push(tr);
+        } else if find(&info.mandatory, &tr).is_some() {
+            return Err(Error::new_spanned(
+                &tr.path,
+                format!(
+                    "Trait `{}` is mandatory in the base group `{}`",
+                    tr.to_token_stream(),
+                    base.to_token_stream()
+                ),
+            ));
+        } else {
+            return Err(not_found(&tr));
+        }
+    }
+
+    Ok((promoted, kept))
+}
+
+/// Parse outer attributes of a trait group.
+///
+/// Returns whether the group should store its vtables in a shared table.
+fn parse_group_attrs(input: ParseStream) -> Result<bool> {
+    let mut vtbl_table = false;
+
+    for attr in input.call(Attribute::parse_outer)? {
+        if attr.path.is_ident("vtbl_table") {
+            // Tables are built by `cglue_impl_group!`, which does nothing with `unstable`.
+            if cfg!(feature = "unstable") {
+                return Err(Error::new_spanned(
+                    attr,
+                    "`#[vtbl_table]` is not supported with the `unstable` feature",
+                ));
+            }
+            vtbl_table = true;
+        } else {
+            return Err(Error::new_spanned(
+                attr,
+                "Unsupported trait group attribute",
+            ));
+        }
+    }
+
+    Ok(vtbl_table)
 }
 
 impl Parse for TraitGroup {
     fn parse(input: ParseStream) -> Result<Self> {
+        let macro_input: TokenStream = input.fork().parse()?;
+
+        // Derived groups are expanded twice. First time around the macro calls back into the base
+        // group's macro, which then invokes us again with `@cglue_base(...)` prepended.
+        let base_info = if input.parse::<Token![@]>().is_ok() {
+            let kw: Ident = input.parse()?;
+
+            if kw != "cglue_base" {
+                return Err(Error::new(kw.span(), "Expected `cglue_base`"));
+            }
+
+            let content;
+            parenthesized!(content in input);
+            Some(content.parse::<GroupBaseInfo>()?)
+        } else {
+            None
+        };
+
+        let vtbl_table = parse_group_attrs(input)?;
+
         let name = input.parse()?;
 
-        let generics = input.parse()?;
+        let mut generics: ParsedGenerics = input.parse()?;
+
+        let base_path = if input.parse::<Token![:]>().is_ok() {
+            let path: Path = input.parse()?;
+
+            if let Ok(mut clause) = input.parse::<WhereClause>() {
+                if !clause.predicates.trailing_punct() {
+                    clause.predicates.push_punct(Default::default());
+                }
+                generics
+                    .gen_where_bounds
+                    .extend(clause.predicates.into_pairs());
+            }
+
+            Some(path)
+        } else {
+            None
+        };
 
         // TODO: parse associated type defs here
         parse_brace_content(input).ok();
 
+        let base_info = match (base_path, base_info) {
+            (Some(path), None) => {
+                // Consume the rest of the input, it will be processed once the base group passes
+                // information back to us.
+                input.parse::<TokenStream>()?;
+
+                return Ok(Self {
+                    cont_name: format_ident!("{}Container", name),
+                    name,
+                    generics,
+                    mandatory_vtbl: vec![],
+                    optional_vtbl: vec![],
+                    ext_traits: HashMap::new(),
+                    extra_filler_traits: false,
+                    vtbl_table,
+                    base_info: GroupBaseInfo {
+                        vtbl_table,
+                        mandatory: vec![],
+                        optional: vec![],
+                        ext_trait_defs: vec![],
+                    },
+                    base: None,
+                    unresolved: Some((path, macro_input)),
+                });
+            }
+            (Some(path), Some(info)) => Some((path, info)),
+            (None, Some(_)) => {
+                return Err(Error::new(
+                    name.span(),
+                    "Base group information given to a group without a base",
+                ))
+            }
+            (None, None) => None,
+        };
+
         input.parse::<Token![,]>()?;
         let mandatory_traits = parse_maybe_braced::<AliasPath>(input)?;
 
         input.parse::<Token![,]>()?;
         let optional_traits = parse_maybe_braced::<AliasPath>(input)?;
 
-        let ext_trait_defs = if input.parse::<Token![,]>().is_ok() {
+        let mut ext_trait_defs = if input.parse::<Token![,]>().is_ok() {
             parse_maybe_braced::<ItemTrait>(input)?
         } else {
             vec![]
         };
 
+        let (base, mandatory_traits, optional_traits) = match base_info {
+            Some((path, info)) => {
+                let (mut path, base_ident, gens) = split_path_ident(&path)?;
+
+                // Containers are shared, thus so must be the generic parameters.
+                let base_gens = gens
+                    .iter()
+                    .flatten()
+                    .map(|g| g.to_token_stream().to_string())
+                    .collect::<Vec<_>>();
+                let gens = generics
+                    .gen_use
+                    .iter()
+                    .map(|g| g.to_token_stream().to_string())
+                    .collect::<Vec<_>>();
+
+                if base_gens != gens {
+                    return Err(Error::new(
+                        base_ident.span(),
+                        "Derived groups must pass their generic parameters to the base group as is",
+                    ));
+                }
+
+                path.segments.push(base_ident.into());
+
+                let (promoted, kept) =
+                    derive_traits(&path, &info, mandatory_traits, optional_traits)?;
+
+                // Generated code lives 2 modules deeper than the macro invocation.
+                let path = match path.segments.first() {
+                    Some(seg) if seg.ident == "self" || seg.ident == "super" => {
+                        let mut segments: Punctuated<PathSegment, Token![::]> =
+                            parse_quote!(super::super);
+                        segments.extend(
+                            path.segments
+                                .into_iter()
+                                .skip_while(|seg| seg.ident == "self"),
+                        );
+                        Path {
+                            leading_colon: None,
+                            segments,
+                        }
+                    }
+                    _ => path,
+                };
+
+                let base_vtbls = |list: &[AliasPath]| {
+                    let mut list: Vec<TraitInfo> = list
+                        .iter()
+                        .cloned()
+                        .map(AliasPath::prelude_remap)
+                        .map(TraitInfo::from)
+                        .collect();
+                    list.sort();
+                    list
+                };
+
+                let base = GroupBase {
+                    vtbl_table: info.vtbl_table,
+                    mandatory_vtbl: base_vtbls(&info.mandatory),
+                    optional_vtbl: base_vtbls(&info.optional),
+                    path,
+                };
+
+                let mut mandatory = info.mandatory;
+                mandatory.extend(promoted);
+
+                let mut defs = info.ext_trait_defs;
+                defs.extend(ext_trait_defs);
+                ext_trait_defs = defs;
+
+                (Some(base), mandatory, kept)
+            }
+            None => (None, mandatory_traits, optional_traits),
+        };
+
+        let base_info = GroupBaseInfo {
+            vtbl_table,
+            mandatory: mandatory_traits.clone(),
+            optional: optional_traits.clone(),
+            ext_trait_defs: ext_trait_defs.clone(),
+        };
+
         let mut ext_traits = HashMap::new();
 
         let mut mandatory_vtbl: Vec<TraitInfo> = mandatory_traits
@@ -180,6 +504,16 @@ impl Parse for TraitGroup {
             .collect();
         optional_vtbl.sort();
 
+        if vtbl_table && optional_vtbl.len() > MAX_TABLE_TRAITS {
+            return Err(Error::new(
+                name.span(),
+                format!(
+                    "`#[vtbl_table]` groups can have at most {} optional traits",
+                    MAX_TABLE_TRAITS
+                ),
+            ));
+        }
+
         let store_exports = get_exports();
         let store_traits = get_store();
 
@@ -279,10 +613,62 @@ impl Parse for TraitGroup {
             optional_vtbl,
             ext_traits,
             extra_filler_traits,
+            vtbl_table,
+            base_info,
+            base,
+            unresolved: None,
         })
     }
 }
 
+/// Maximum number of optional traits of a group marked with `#[vtbl_table]`.
+///
+/// Availability of the optional vtables is stored in a `u64` mask of the table.
+const MAX_TABLE_TRAITS: usize = 64;
+
+/// Maximum number of optional traits `cglue_impl_group!` is able to detect automatically.
+///
+/// The number of optional traits is not known when implementing the group, thus larger groups
+/// are rejected at compile time.
+#[cfg(not(feature = "unstable"))]
+const MAX_AUTO_TRAITS: usize = 16;
+
+/// Optional traits implemented by a type.
+#[cfg(not(feature = "unstable"))]
+enum ImplementedVtbls {
+    /// Traits are listed explicitly.
+    Listed(Vec<TraitInfo>),
+    /// Traits are detected automatically, using the group's probe.
+    Auto,
+}
+
+#[cfg(not(feature = "unstable"))]
+impl Parse for ImplementedVtbls {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let fork = input.fork();
+
+        if let Ok(ident) = fork.parse::<Ident>() {
+            if ident == "auto" && (fork.is_empty() || fork.peek(Token![,])) {
+                input.parse::<Ident>()?;
+                return Ok(Self::Auto);
+            }
+        }
+
+        let implemented_traits = parse_maybe_braced::<AliasPath>(input)?;
+
+        let mut implemented_vtbl: Vec<TraitInfo> = implemented_traits
+            .into_iter()
+            .map(AliasPath::prelude_remap)
+            .map(AliasPath::ext_abs_remap)
+            .map(From::from)
+            .collect();
+
+        implemented_vtbl.sort();
+
+        Ok(Self::Listed(implemented_vtbl))
+    }
+}
+
 /// Describes trait group to be implemented on a type.
 #[cfg(not(feature = "unstable"))]
 pub struct TraitGroupImpl {
@@ -291,28 +677,62 @@ pub struct TraitGroupImpl {
     generics: ParsedGenerics,
     group_path: Path,
     group: Ident,
-    implemented_vtbl: Vec<TraitInfo>,
-    fwd_implemented_vtbl: Option<Vec<TraitInfo>>,
+    implemented_vtbl: ImplementedVtbls,
+    fwd_implemented_vtbl: Option<ImplementedVtbls>,
 }
 
 #[cfg(not(feature = "unstable"))]
 impl Parse for TraitGroupImpl {
     fn parse(input: ParseStream) -> Result<Self> {
-        let mut ty: Type = input.parse()?;
+        // Blanket implementation for a generic type, such as `T: Bounds`.
+        let (mut ty, ty_consts, mut ty_generics) =
+            if input.peek(Ident) && input.peek2(Token![:]) && !input.peek2(Token![::]) {
+                let ident = input.parse::<Ident>()?;
+                input.parse::<Token![:]>()?;
+                let bounds =
+                    Punctuated::<TypeParamBound, Token![+]>::parse_separated_nonempty(input)?;
+
+                let ty = Type::Path(TypePath {
+                    qself: None,
+                    path: ident.clone().into(),
+                });
+
+                let mut gens = Punctuated::new();
+                gens.push_value(GenericArgument::Constraint(Constraint {
+                    ident,
+                    colon_token: Default::default(),
+                    bounds,
+                }));
+
+                (ty, vec![], ParsedGenerics::from(&gens))
+            } else {
+                let (mut ty, ty_consts) = parse_type_with_consts(input)?;
+
+                // Parse generic arguments from the type.
+                // Here we assume the last instance of AngleBracketed are generic arguments.
+                let ty_gens = extract_generics(&mut ty);
 
-        // Parse generic arguments from the type.
-        // Here we assume the last instance of AngleBracketed are generic arguments.
-        let ty_gens = extract_generics(&mut ty);
+                let ty_generics = ParsedGenerics::from(ty_gens.as_ref());
 
-        let mut ty_generics = ParsedGenerics::from(ty_gens.as_ref());
+                (ty, ty_consts, ty_generics)
+            };
 
         input.parse::<Token![,]>()?;
 
-        let group = input.parse()?;
+        let (group, group_consts) = parse_type_with_consts(input)?;
+
+        let group = match group {
+            Type::Path(TypePath { path, .. }) => path,
+            ty => return Err(Error::new_spanned(ty, "Expected a trait group path")),
+        };
 
         let (group_path, group, gens) = split_path_ident(&group)?;
 
-        let generics = ParsedGenerics::from(gens.as_ref());
+        let mut generics = ParsedGenerics::from(gens.as_ref());
+
+        // Const generics may be declared either on the type, or on the group.
+        generics.apply_consts(ty_consts.iter().chain(&group_consts));
+        ty_generics.apply_consts(ty_consts.iter().chain(&group_consts));
 
         let mut generics = match input.parse::<ParsedGenerics>() {
             Ok(ParsedGenerics {
@@ -330,35 +750,13 @@ impl Parse for TraitGroupImpl {
         generics.merge_and_remap(&mut ty_generics);
 
         let implemented_vtbl = if input.parse::<Token![,]>().is_ok() {
-            let implemented_traits = parse_maybe_braced::<AliasPath>(input)?;
-
-            let mut implemented_vtbl: Vec<TraitInfo> = implemented_traits
-                .into_iter()
-                .map(AliasPath::prelude_remap)
-                .map(AliasPath::ext_abs_remap)
-                .map(From::from)
-                .collect();
-
-            implemented_vtbl.sort();
-
-            implemented_vtbl
+            input.parse()?
         } else {
-            vec![]
+            ImplementedVtbls::Listed(vec![])
         };
 
         let fwd_implemented_vtbl = if input.parse::<Token![,]>().is_ok() {
-            let implemented_traits = parse_maybe_braced::<AliasPath>(input)?;
-
-            let mut implemented_vtbl: Vec<TraitInfo> = implemented_traits
-                .into_iter()
-                .map(AliasPath::prelude_remap)
-                .map(AliasPath::ext_abs_remap)
-                .map(From::from)
-                .collect();
-
-            implemented_vtbl.sort();
-
-            Some(implemented_vtbl)
+            Some(input.parse()?)
         } else {
             None
         };
@@ -391,15 +789,12 @@ impl TraitGroupImpl {
     #[cfg(not(feature = "unstable"))]
     pub fn implement_group(&self) -> TokenStream {
         let crate_path = crate_path();
-        let trg_path: TokenStream = quote!(#crate_path::trait_group);
 
         let ctx_bound = super::traits::ctx_bound();
 
         let ty = &self.ty;
 
         let group = &self.group;
-        let group_path = &self.group_path;
-        let ParsedGenerics { gen_use, .. } = &self.generics;
 
         let ParsedGenerics {
             gen_declare,
@@ -449,64 +844,208 @@ impl TraitGroupImpl {
         };
 
         let filler_trait = format_ident!("{}VtableFiller", group);
-        let vtable_type = format_ident!("{}Vtables", group);
-        let cont_name = format_ident!("{}Container", group);
 
-        let implemented_tables = TraitGroup::enable_opt_vtbls(self.implemented_vtbl.iter());
-        let vtbl_where_bounds = TraitGroup::vtbl_where_bounds(
-            self.implemented_vtbl.iter(),
-            &cont_name,
-            quote!(CGlueInst),
-            quote!(CGlueCtx),
-            &self.generics,
-            Some(quote!(Self)).as_ref(),
-            first_life,
-        );
+        let impl_generics = quote!(#life_declare CGlueCtx: #ctx_bound, #gen_declare);
 
-        let gen = quote! {
-            impl<#life_declare CGlueInst: #trg_path::InstanceBounds<InstanceObjType = #ty>, CGlueCtx: #ctx_bound, #gen_declare>
-                #group_path #filler_trait<#life_use CGlueInst, CGlueCtx, #gen_use> for #ty
-            where #gen_where_bounds #vtbl_where_bounds {
-                fn fill_table(table: #group_path #vtable_type<#life_use CGlueInst, CGlueCtx, #gen_use>) -> #group_path #vtable_type<#life_use CGlueInst, CGlueCtx, #gen_use> {
-                    table #implemented_tables
-                }
-            }
-        };
+        let gen = self.filler_impls(
+            &self.implemented_vtbl,
+            &filler_trait,
+            &quote!(#ty),
+            &impl_generics,
+            &life_use,
+            &gen_where_bounds,
+        );
 
         if let Some(fwd_vtbl) = &self.fwd_implemented_vtbl {
             let fwd_filler_trait = format_ident!("{}FwdVtableFiller", group);
 
             let fwd_ty = quote!(#crate_path::forward::Fwd<&#first_life mut #ty>);
 
-            let implemented_tables = TraitGroup::enable_opt_vtbls(fwd_vtbl.iter());
-            let vtbl_where_bounds = TraitGroup::vtbl_where_bounds(
-                fwd_vtbl.iter(),
-                &cont_name,
-                quote!(CGlueInst),
-                quote!(CGlueCtx),
-                &self.generics,
-                Some(quote!(Self)).as_ref(),
-                first_life,
+            let fwd_gen = self.filler_impls(
+                fwd_vtbl,
+                &fwd_filler_trait,
+                &fwd_ty,
+                &impl_generics,
+                &life_use,
+                &gen_where_bounds,
             );
 
             quote! {
                 #gen
 
-                impl<#life_declare CGlueInst: #trg_path::InstanceBounds<InstanceObjType = #fwd_ty>, CGlueCtx: #ctx_bound, #gen_declare>
-                    #group_path #fwd_filler_trait<#life_use CGlueInst, CGlueCtx, #gen_use> for #ty
-                where
-                    #cont_name<CGlueInst, CGlueCtx, #gen_use>: #crate_path::trait_group::CGlueObjBase,
-                    #gen_where_bounds #vtbl_where_bounds
-                {
-                    fn fill_fwd_table(table: #group_path #vtable_type<#life_use CGlueInst, CGlueCtx, #gen_use>) -> #group_path #vtable_type<#life_use CGlueInst, CGlueCtx, #gen_use> {
-                        table #implemented_tables
-                    }
-                }
+                #fwd_gen
             }
         } else {
             gen
         }
     }
+
+    /// Vtable filler implementations for the type.
+    ///
+    /// Listed traits are enabled in a single implementation, generic over the instance type.
+    ///
+    /// Automatically detected traits need to know the instance type, because whether a vtable can
+    /// be built depends on it, for instance, `&mut self` functions can not be called through a
+    /// shared reference. Thus, every instance type provided by CGlue gets its own implementation,
+    /// and optional traits are probed for through inherent constants of the group's probe.
+    ///
+    /// # Arguments
+    ///
+    /// * `vtbls` - optional traits to enable.
+    /// * `filler_trait` - filler trait to implement.
+    /// * `obj_ty` - type of the object, as stored in the instance.
+    /// * `impl_generics` - generic parameters of the implementation, excluding `CGlueInst`.
+    /// * `life_use` - lifetimes passed to the filler trait.
+    /// * `where_bounds` - where bounds of the implementation.
+    #[allow(clippy::too_many_arguments)]
+    fn filler_impls(
+        &self,
+        vtbls: &ImplementedVtbls,
+        filler_trait: &Ident,
+        obj_ty: &TokenStream,
+        impl_generics: &TokenStream,
+        life_use: &Punctuated<Lifetime, token::Comma>,
+        where_bounds: &TokenStream,
+    ) -> TokenStream {
+        let crate_path = crate_path();
+        let trg_path: TokenStream = quote!(#crate_path::trait_group);
+
+        let ty = &self.ty;
+        let group = &self.group;
+        let group_path = &self.group_path;
+        let ParsedGenerics { gen_use, .. } = &self.generics;
+
+        let vtable_type = format_ident!("{}Vtables", group);
+        let auto_vtables = format_ident!("{}AutoVtables", group);
+        let cont_name = format_ident!("{}Container", group);
+
+        // Lifetime should always exist based on previous code
+        let first_life = life_use.first().unwrap();
+
+        let vtbls = match vtbls {
+            ImplementedVtbls::Listed(vtbls) => vtbls,
+            ImplementedVtbls::Auto => {
+                let auto_probe = format_ident!("{}AutoProbe", group);
+
+                // Sendable instances need a higher ranked bound, so that the impl is accepted
+                // even if the type is known not to be `Send`.
+                let send_bound = quote!(for<'cglue_s> #obj_ty: Send,);
+                let sync_bound = quote!(for<'cglue_s> #obj_ty: Send + Sync,);
+                let no_bound = quote!();
+
+                let instances = [
+                    (
+                        quote!(),
+                        quote!(#crate_path::boxed::CBox<#first_life, #obj_ty>),
+                        &send_bound,
+                    ),
+                    (
+                        quote!(),
+                        quote!(#crate_path::boxed::CUnsendBox<#first_life, #obj_ty>),
+                        &no_bound,
+                    ),
+                    (
+                        quote!(),
+                        quote!(#crate_path::boxed::CSyncBox<#first_life, #obj_ty>),
+                        &sync_bound,
+                    ),
+                    (
+                        quote!(const CGLUE_N: usize,),
+                        quote!(#crate_path::inline::CInline<#first_life, #obj_ty, CGLUE_N>),
+                        &send_bound,
+                    ),
+                    (quote!(), quote!(&#first_life #obj_ty), &no_bound),
+                    (quote!(), quote!(&#first_life mut #obj_ty), &no_bound),
+                ];
+
+                let limit_msg = format!(
+                    "`auto` can only detect up to {} optional traits of `{}`, list the implemented traits explicitly",
+                    MAX_AUTO_TRAITS, group
+                );
+
+                let mut ret = quote! {
+                    const _: () = if #group_path #auto_probe::<(), (), 0>::CGLUE_OPTIONAL_TRAITS
+                        > #MAX_AUTO_TRAITS
+                    {
+                        #crate_path::__cglue_const_panic!(#limit_msg);
+                    };
+                };
+
+                // Container bounds are left out of the where clause, because they would take
+                // precedence over the actual implementations, leaving the context and opaque
+                // types unresolved.
+                for (inst_generics, inst, inst_bound) in &instances {
+                    let table_ty =
+                        quote!(#group_path #vtable_type<#life_use #inst, CGlueCtx, #gen_use>);
+
+                    // The probe only has an inherent vtable constant if the trait is implemented,
+                    // otherwise the constant of `AutoSkip` is used.
+                    let probes = (0..MAX_AUTO_TRAITS).map(|i| {
+                        let idx = proc_macro2::Literal::usize_unsuffixed(i);
+                        quote!(#group_path #auto_probe::<#obj_ty, #table_ty, #idx>::CGLUE_VTBL,)
+                    });
+
+                    ret.extend(quote! {
+                        impl<#impl_generics #inst_generics>
+                            #group_path #filler_trait<#life_use #inst, CGlueCtx, #gen_use> for #ty
+                        where
+                            #inst_bound
+                            #where_bounds
+                        {
+                            const CGLUE_AUTO: #group_path #auto_vtables<#life_use #inst, CGlueCtx, #gen_use> = {
+                                use #trg_path::AutoSkip as _;
+
+                                (#(#probes)*)
+                            };
+                        }
+                    });
+                }
+
+                return ret;
+            }
+        };
+
+        let vtbl_consts = vtbls.iter().map(|info| {
+            let TraitInfo {
+                path,
+                vtbl_typename,
+                vtbl_const_name,
+                generics: ParsedGenerics { gen_use: tr_gen_use, .. },
+                assocs: ParsedGenerics {
+                    gen_use: assoc_use, ..
+                },
+                ..
+            } = info;
+
+            quote! {
+                const #vtbl_const_name: ::core::option::Option<
+                    &#first_life #path #vtbl_typename<#first_life, #group_path #cont_name<CGlueInst, CGlueCtx, #gen_use>, #tr_gen_use #assoc_use>
+                > = ::core::option::Option::Some(#trg_path::StaticVtbl::CGLUE_VTBL);
+            }
+        });
+
+        let vtbl_where_bounds = TraitGroup::vtbl_where_bounds(
+            vtbls.iter(),
+            &cont_name,
+            quote!(CGlueInst),
+            quote!(CGlueCtx),
+            &self.generics,
+            Some(quote!(Self)).as_ref(),
+            first_life,
+        );
+
+        quote! {
+            impl<#impl_generics CGlueInst: #trg_path::InstanceBounds<InstanceObjType = #obj_ty>>
+                #group_path #filler_trait<#life_use CGlueInst, CGlueCtx, #gen_use> for #ty
+            where
+                #group_path #cont_name<CGlueInst, CGlueCtx, #gen_use>: #first_life + #trg_path::CGlueObjBase,
+                #where_bounds
+                #vtbl_where_bounds
+            {
+                #(#vtbl_consts)*
+            }
+        }
+    }
 }
 
 pub struct TraitCastGroup {
@@ -519,6 +1058,7 @@ pub enum CastType {
     AsRef,
     AsMut,
     Into,
+    TryInto,
     OnlyCheck,
 }
 
@@ -558,19 +1098,25 @@ impl TraitCastGroup {
     ///
     /// The type will have specified vtables implemented as a conversion function.
     pub fn cast_group(&self, cast: CastType) -> TokenStream {
-        let prefix = match cast {
-            CastType::Cast => "cast",
-            CastType::AsRef => "as_ref",
-            CastType::AsMut => "as_mut",
-            CastType::Into => "into",
-            CastType::OnlyCheck => "check",
+        let func_name = match cast {
+            CastType::Cast => "cglue_cast",
+            CastType::AsRef => "cglue_as_ref",
+            CastType::AsMut => "cglue_as_mut",
+            CastType::Into => "cglue_into",
+            CastType::TryInto => "cglue_try_into",
+            CastType::OnlyCheck => "cglue_check",
         };
 
+        let crate_path = crate::util::crate_path();
         let name = &self.name;
-        let func_name = TraitGroup::optional_func_name(prefix, self.needed_vtbls.iter());
+        let func_name = format_ident!("{}", func_name);
+        let cast_id = TraitGroup::optional_cast_id(self.needed_vtbls.iter());
 
         quote! {
-            (#name).#func_name()
+            {
+                use #crate_path::trait_group::CastGroupExt as _;
+                (#name).#func_name::<#cast_id>()
+            }
         }
     }
 }
@@ -597,41 +1143,27 @@ impl TraitGroup {
         format_ident!("{}{}With{}", name, postfix, all_traits)
     }
 
-    /// Get the name of the function for trait conversion.
+    /// Get the identifier of a set of traits to cast the group to.
+    ///
+    /// This is the `CastGroup` parameter, computed as 64-bit FNV-1a hash of the lowercase trait
+    /// names.
     ///
     /// # Arguments
     ///
-    /// * `prefix` - function name prefix.
-    /// * `lc_names` - lowercase identifiers of the traits the function implements.
-    pub fn optional_func_name<'a>(
-        prefix: &str,
+    /// * `lc_names` - lowercase identifiers of the traits to cast to. Must be sorted!
+    pub fn optional_cast_id<'a>(
         lc_names: impl Iterator<Item = &'a TraitInfo>,
-    ) -> Ident {
-        let mut ident = format_ident!("{}_impl", prefix);
+    ) -> proc_macro2::Literal {
+        let mut hash = 0xcbf29ce484222325u64;
 
         for TraitInfo { lc_name, .. } in lc_names {
-            ident = format_ident!("{}_{}", ident, lc_name);
-        }
-
-        ident
-    }
-
-    /// Generate function calls that enable individual functional vtables.
-    ///
-    /// # Arguments
-    ///
-    /// * `iter` - iterator of optional traits to enable
-    pub fn enable_opt_vtbls<'a>(iter: impl Iterator<Item = &'a TraitInfo>) -> TokenStream {
-        let mut ret = TokenStream::new();
-
-        for TraitInfo {
-            enable_vtbl_name, ..
-        } in iter
-        {
-            ret.extend(quote!(.#enable_vtbl_name()));
+            for b in lc_name.to_string().bytes().chain(Some(b'_')) {
+                hash ^= b as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
         }
 
-        ret
+        proc_macro2::Literal::u64_unsuffixed(hash)
     }
 
     /// Generate full code for the trait group.
@@ -639,6 +1171,15 @@ impl TraitGroup {
     /// This trait group will have all variants generated for converting, building, and
     /// converting it.
     pub fn create_group(&self) -> TokenStream {
+        // Retrieve the traits of the base group, which will invoke us again.
+        if let Some((base, input)) = &self.unresolved {
+            let mut base = base.clone();
+            if let Some(seg) = base.segments.last_mut() {
+                seg.arguments = PathArguments::None;
+            }
+            return quote!(#base! { #input });
+        }
+
         // Path to trait group import.
         let crate_path = crate::util::crate_path();
 
@@ -685,7 +1226,20 @@ impl TraitGroup {
             quote!(#crate_path::trait_group::NoContext),
         );
 
+        let group_vtbl_defs = self.group_vtbl_defs();
+        let group_vtbl_init = self.group_vtbl_init();
+        let self_vtbl_bind = self.group_vtbl_bind(quote!(self));
+        let vtbl_table_impl = self.vtbl_table_impl(&trg_path, &crate_path);
         let mand_vtbl_default = self.mandatory_vtbl_defaults();
+        let mand_vtbl_let_default = self
+            .mandatory_vtbl
+            .iter()
+            .map(|info| {
+                let vtbl_name = &info.vtbl_name;
+                let vtbl_type = self.vtbl_type(info);
+                quote!(let #vtbl_name: &'cglue_a #vtbl_type = #trg_path::StaticVtbl::CGLUE_VTBL;)
+            })
+            .collect::<TokenStream>();
         let mand_ret_tmp_default = self.mandatory_ret_tmp_defaults();
         let full_opt_ret_tmp_default = Self::ret_tmp_defaults(self.optional_vtbl.iter());
         let default_opt_vtbl_list = self.default_opt_vtbl_list();
@@ -693,13 +1247,14 @@ impl TraitGroup {
         let full_opt_vtbl_list = self.vtbl_list(self.optional_vtbl.iter());
         let mandatory_as_ref_impls = self.mandatory_as_ref_impls(&trg_path);
 
-        let get_container_impl = self.get_container_impl(name, &trg_path, &self.generics);
+        let group_obj_impl = self.group_obj_impl(name, &trg_path, &self.generics, false);
 
         let mandatory_internal_trait_impls = self.internal_trait_impls(
             name,
             self.mandatory_vtbl.iter(),
             &self.generics,
             &crate_path,
+            false,
         );
         let vtbl_where_bounds = Self::vtbl_where_bounds(
             self.mandatory_vtbl.iter(),
@@ -749,6 +1304,15 @@ impl TraitGroup {
 
         let all_gen_use = &gen_use;
 
+        let gen_alias_declare = self.generics.alias_declare();
+
+        // Generic uses with a trailing comma, for appending the inline buffer size.
+        let gen_use_inline = if gen_use.empty_or_trailing() {
+            quote!(#gen_use)
+        } else {
+            quote!(#gen_use,)
+        };
+
         // Work around needless_update lint
         let fill_rest = if self.optional_vtbl.len() + self.mandatory_vtbl.len() > 1 {
             quote!(..self)
@@ -768,25 +1332,47 @@ impl TraitGroup {
             ..
         } in &self.optional_vtbl
         {
-            for (funcs, fill_rest) in &mut [
-                (&mut enable_funcs, &quote!(..self)),
-                (&mut enable_funcs_vtbl, &fill_rest),
-            ] {
-                funcs.extend(quote! {
+            let vtbl_bound = quote! {
+                &'cglue_a #path #vtbl_typename<'cglue_a, #cont_name<CGlueInst, CGlueCtx, #all_gen_use>, #gen_use #assoc_use>: #trg_path::StaticVtbl
+            };
+
+            // Shared tables are built at compile time, thus they can not be modified.
+            if !self.vtbl_table {
+                enable_funcs.extend(quote! {
                     pub fn #enable_vtbl_name (self) -> Self
-                        where &'cglue_a #path #vtbl_typename<'cglue_a, #cont_name<CGlueInst, CGlueCtx, #all_gen_use>, #gen_use #assoc_use>: Default {
+                        where #vtbl_bound {
                             Self {
-                                #vtbl_name: Some(Default::default()),#fill_rest
+                                #vtbl_name: Some(#trg_path::StaticVtbl::CGLUE_VTBL),..self
                             }
                     }
                 });
             }
+
+            enable_funcs_vtbl.extend(quote! {
+                pub fn #enable_vtbl_name (self) -> Self
+                    where #vtbl_bound {
+                        Self {
+                            #vtbl_name: Some(#trg_path::StaticVtbl::CGLUE_VTBL),#fill_rest
+                        }
+                }
+            });
         }
 
-        let mut trait_funcs = TokenStream::new();
+        let variant_name = format_ident!("{}Variant", name);
+        let variant_impl = self.variant_impl(&trg_path, &crate_path);
 
         let mut opt_structs = TokenStream::new();
-        let mut opt_struct_imports = TokenStream::new();
+        let mut opt_struct_imports = if self.optional_vtbl.is_empty() {
+            quote!()
+        } else {
+            quote!(#variant_name,)
+        };
+
+        if self.vtbl_table {
+            let table_name = self.table_name();
+            let table_filler = self.table_filler_name();
+            opt_struct_imports.extend(quote!(#table_name, #table_filler,));
+        }
 
         let impl_traits =
             self.impl_traits(self.mandatory_vtbl.iter().chain(self.optional_vtbl.iter()));
@@ -808,6 +1394,15 @@ impl TraitGroup {
         let base_name_boxed = format_ident!("{}BaseBox", name);
         let base_name_arc_box = format_ident!("{}BaseArcBox", name);
         let base_name_ctx_box = format_ident!("{}BaseCtxBox", name);
+        let base_name_unsend_box = format_ident!("{}BaseUnsendBox", name);
+        let base_name_arc_unsend_box = format_ident!("{}BaseArcUnsendBox", name);
+        let base_name_ctx_unsend_box = format_ident!("{}BaseCtxUnsendBox", name);
+        let base_name_sync_box = format_ident!("{}BaseSyncBox", name);
+        let base_name_arc_sync_box = format_ident!("{}BaseArcSyncBox", name);
+        let base_name_ctx_sync_box = format_ident!("{}BaseCtxSyncBox", name);
+        let base_name_inline = format_ident!("{}BaseInline", name);
+        let base_name_arc_inline = format_ident!("{}BaseArcInline", name);
+        let base_name_ctx_inline = format_ident!("{}BaseCtxInline", name);
         let opaque_name_ref = format_ident!("{}Ref", name);
         let opaque_name_ctx_ref = format_ident!("{}CtxRef", name);
         let opaque_name_arc_ref = format_ident!("{}ArcRef", name);
@@ -817,6 +1412,15 @@ impl TraitGroup {
         let opaque_name_boxed = format_ident!("{}Box", name);
         let opaque_name_arc_box = format_ident!("{}ArcBox", name);
         let opaque_name_ctx_box = format_ident!("{}CtxBox", name);
+        let opaque_name_unsend_box = format_ident!("{}UnsendBox", name);
+        let opaque_name_arc_unsend_box = format_ident!("{}ArcUnsendBox", name);
+        let opaque_name_ctx_unsend_box = format_ident!("{}CtxUnsendBox", name);
+        let opaque_name_sync_box = format_ident!("{}SyncBox", name);
+        let opaque_name_arc_sync_box = format_ident!("{}ArcSyncBox", name);
+        let opaque_name_ctx_sync_box = format_ident!("{}CtxSyncBox", name);
+        let opaque_name_inline = format_ident!("{}Inline", name);
+        let opaque_name_arc_inline = format_ident!("{}ArcInline", name);
+        let opaque_name_ctx_inline = format_ident!("{}CtxInline", name);
 
         #[cfg(not(feature = "unstable"))]
         let filler_trait = format_ident!("{}VtableFiller", name);
@@ -830,74 +1434,33 @@ impl TraitGroup {
             .powerset()
             .filter(|v| !v.is_empty())
         {
-            let func_name = Self::optional_func_name("cast", traits.iter().copied());
-            let func_name_final = Self::optional_func_name("into", traits.iter().copied());
-            let func_name_check = Self::optional_func_name("check", traits.iter().copied());
-            let func_name_mut = Self::optional_func_name("as_mut", traits.iter().copied());
-            let func_name_ref = Self::optional_func_name("as_ref", traits.iter().copied());
+            let cast_id = Self::optional_cast_id(traits.iter().copied());
             let opt_final_name = Self::optional_group_ident(name, "Final", traits.iter().copied());
             let opt_name = Self::optional_group_ident(name, "", traits.iter().copied());
-            let opt_vtbl_defs = self.mandatory_vtbl_defs(traits.iter().copied());
-            let opt_mixed_vtbl_defs = self.mixed_opt_vtbl_defs(traits.iter().copied());
-
-            let opt_vtbl_list = self.vtbl_list(traits.iter().copied());
-            let opt_vtbl_unwrap = self.vtbl_unwrap_list(traits.iter().copied());
-            let opt_vtbl_unwrap_validate = self.vtbl_unwrap_validate(traits.iter().copied());
-
-            let mixed_opt_vtbl_unwrap = self.mixed_opt_vtbl_unwrap_list(traits.iter().copied());
-
-            let get_container_impl = self.get_container_impl(&opt_name, &trg_path, &self.generics);
-
-            let opt_as_ref_impls = self.as_ref_impls(
-                &opt_name,
-                self.mandatory_vtbl.iter().chain(traits.iter().copied()),
-                &self.generics,
-                &trg_path,
-            );
-
-            let opt_internal_trait_impls = self.internal_trait_impls(
-                &opt_name,
-                self.mandatory_vtbl.iter().chain(traits.iter().copied()),
-                &self.generics,
-                &crate_path,
-            );
-
-            let get_container_impl_final =
-                self.get_container_impl(&opt_final_name, &trg_path, &self.generics);
-
-            let opt_final_as_ref_impls = self.as_ref_impls(
-                &opt_final_name,
-                self.mandatory_vtbl.iter().chain(traits.iter().copied()),
-                &self.generics,
-                &trg_path,
-            );
-
-            let opt_final_internal_trait_impls = self.internal_trait_impls(
-                &opt_final_name,
-                self.mandatory_vtbl.iter().chain(traits.iter().copied()),
-                &self.generics,
-                &crate_path,
-            );
+            let opt_final_slots = self.variant_slot_list(traits.iter().copied(), true);
+            let opt_slots = self.variant_slot_list(traits.iter().copied(), false);
 
             let impl_traits =
                 self.impl_traits(self.mandatory_vtbl.iter().chain(traits.iter().copied()));
+            let cast_traits = self.impl_traits(traits.iter().copied());
 
             let opt_final_doc = format!(
                 " Final {} variant with `{}` implemented.",
                 name, &impl_traits
             );
             let opt_final_doc2 = format!(
-                " Retrieve this type using [`{}`]({}::{}) function.",
-                func_name_final, name, func_name_final
+                " Retrieve this type using `into!(obj impl {})`, or `try_into!(obj impl {})` macros.",
+                cast_traits, cast_traits
             );
 
             let opt_doc = format!(
                 " Concrete {} variant with `{}` implemented.",
                 name, &impl_traits
             );
-            let opt_doc2 = format!(" Retrieve this type using one of [`{}`]({}::{}), [`{}`]({}::{}), or [`{}`]({}::{}) functions.", func_name, name, func_name, func_name_mut, name, func_name_mut, func_name_ref, name, func_name_ref);
-
-            // TODO: remove unused generics to remove need for phantom data
+            let opt_doc2 = format!(
+                " Retrieve this type using one of `cast!(obj impl {})`, `as_mut!(obj impl {})`, or `as_ref!(obj impl {})` macros.",
+                cast_traits, cast_traits, cast_traits
+            );
 
             opt_struct_imports.extend(quote! {
                 #opt_final_name,
@@ -905,232 +1468,56 @@ impl TraitGroup {
             });
 
             opt_structs.extend(quote! {
-
-                // Final implementation - more compact layout.
-
                 #[doc = #opt_final_doc]
                 ///
                 #[doc = #opt_final_doc2]
-                #[repr(C)]
-                #derive_layouts
-                pub struct #opt_final_name<'cglue_a, CGlueInst: 'cglue_a, CGlueCtx: #ctx_bound, #gen_declare>
-                where
-                    #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
-                    #gen_where_bounds_base
-                {
-                    #mandatory_vtbl_defs
-                    #opt_vtbl_defs
-                    container: #cont_name<CGlueInst, CGlueCtx, #gen_use>,
-                }
-
-                #get_container_impl_final
-
-                #opt_final_as_ref_impls
-
-                #opt_final_internal_trait_impls
-
-                // Non-final implementation. Has the same layout as the base struct.
+                pub type #opt_final_name<'cglue_a, CGlueInst, CGlueCtx, #gen_alias_declare> =
+                    #variant_name<'cglue_a, CGlueInst, CGlueCtx, #opt_final_slots #gen_use>;
 
                 #[doc = #opt_doc]
                 ///
                 #[doc = #opt_doc2]
-                #[repr(C)]
-                #derive_layouts
-                pub struct #opt_name<'cglue_a, CGlueInst: 'cglue_a, CGlueCtx: #ctx_bound, #gen_declare>
-                where
-                    #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
-                    #gen_where_bounds_base
-                {
-                    #mandatory_vtbl_defs
-                    #opt_mixed_vtbl_defs
-                    container: #cont_name<CGlueInst, CGlueCtx, #gen_use>,
-                }
-
-                unsafe impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #gen_declare>
-                    #trg_path::Opaquable for #opt_name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>
-                where
-                    #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
-                    #gen_where_bounds
-                {
-                    type OpaqueTarget = #name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>;
-                }
+                pub type #opt_name<'cglue_a, CGlueInst, CGlueCtx, #gen_alias_declare> =
+                    #variant_name<'cglue_a, CGlueInst, CGlueCtx, #opt_slots #gen_use>;
 
                 impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #gen_declare>
-                    From<#opt_name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>> for #name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>
+                    #trg_path::CastGroup<#cast_id> for #name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>
                 where
-                    #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
-                    #gen_where_bounds
-                {
-                    fn from(input: #opt_name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>) -> Self {
-                        #trg_path::Opaquable::into_opaque(input)
-                    }
-                }
-
-                impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #gen_declare> #opt_name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>
-                    where Self: #trg_path::Opaquable,
-                    #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
+                    #cont_name<CGlueInst, CGlueCtx, #gen_use>: 'cglue_a + #trg_path::CGlueObjBase,
                     #gen_where_bounds
                 {
-                    /// Cast back into the original group
-                    pub fn upcast(self) -> <Self as #trg_path::Opaquable>::OpaqueTarget {
-                        #trg_path::Opaquable::into_opaque(self)
-                    }
-                }
-
-                #get_container_impl
-
-                #opt_as_ref_impls
-
-                #opt_internal_trait_impls
-            });
-
-            let func_final_doc1 = format!(
-                " Retrieve a final {} variant that implements `{}`.",
-                name, impl_traits
-            );
-            let func_final_doc2 = format!(
-                " This consumes the `{}`, and outputs `Some(impl {})`, if all types are present.",
-                name, impl_traits
-            );
-
-            let func_doc1 = format!(
-                " Retrieve a concrete {} variant that implements `{}`.",
-                name, impl_traits
-            );
-            let func_doc2 = format!(" This consumes the `{}`, and outputs `Some(impl {})`, if all types are present. It is possible to cast this type back with the `From` implementation.", name, impl_traits);
-
-            let func_check_doc1 = format!(" Check whether {} implements `{}`.", name, impl_traits);
-            let func_check_doc2 =
-                " If this check returns true, it is safe to run consuming conversion operations."
-                    .to_string();
-
-            let func_mut_doc1 = format!(
-                " Retrieve mutable reference to a concrete {} variant that implements `{}`.",
-                name, impl_traits
-            );
-            let func_ref_doc1 = format!(
-                " Retrieve immutable reference to a concrete {} variant that implements `{}`.",
-                name, impl_traits
-            );
-
-            trait_funcs.extend(quote! {
-                #[doc = #func_check_doc1]
-                ///
-                #[doc = #func_check_doc2]
-                pub fn #func_name_check(&self) -> bool
-                    where #opt_name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>: 'cglue_a + #impl_traits
-                {
-                    self.#func_name_ref().is_some()
-                }
-
-                #[doc = #func_final_doc1]
-                ///
-                #[doc = #func_final_doc2]
-                pub fn #func_name_final(self) -> ::core::option::Option<impl 'cglue_a + #impl_traits>
-                    where #opt_final_name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>: 'cglue_a + #impl_traits
-                {
-                    let #name {
-                        container,
-                        #mand_vtbl_list
-                        #opt_vtbl_list
-                        ..
-                    } = self;
-
-                    Some(#opt_final_name {
-                        container,
-                        #mand_vtbl_list
-                        #opt_vtbl_unwrap
-                    })
-                }
-
-                #[doc = #func_doc1]
-                ///
-                #[doc = #func_doc2]
-                pub fn #func_name(self) -> ::core::option::Option<#opt_name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>>
-                    where #opt_name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>: 'cglue_a + #impl_traits
-                {
-                    let #name {
-                        container,
-                        #mand_vtbl_list
-                        #full_opt_vtbl_list
-                    } = self;
-
-                    Some(#opt_name {
-                        container,
-                        #mand_vtbl_list
-                        #mixed_opt_vtbl_unwrap
-                    })
-                }
-
-                #[doc = #func_mut_doc1]
-                pub fn #func_name_mut<'b>(&'b mut self) -> ::core::option::Option<&'b mut (impl 'cglue_a + #impl_traits)>
-                    where #opt_name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>: 'cglue_a + #impl_traits
-                {
-                    let #name {
-                        container,
-                        #mand_vtbl_list
-                        #opt_vtbl_list
-                        ..
-                    } = self;
-
-                    let _ = (#opt_vtbl_unwrap_validate);
-
-                    // Safety:
-                    //
-                    // Structure layouts are fully compatible,
-                    // optional reference validity was checked beforehand
-
-                    unsafe {
-                        (self as *mut Self as *mut #opt_name<CGlueInst, CGlueCtx, #gen_use>).as_mut()
-                    }
-                }
-
-                #[doc = #func_ref_doc1]
-                pub fn #func_name_ref<'b>(&'b self) -> ::core::option::Option<&'b (impl 'cglue_a + #impl_traits)>
-                    where #opt_name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>: 'cglue_a + #impl_traits
-                {
-                    let #name {
-                        #mand_vtbl_list
-                        #opt_vtbl_list
-                        ..
-                    } = self;
-
-                    let _ = (#opt_vtbl_unwrap_validate);
-
-                    // Safety:
-                    //
-                    // Structure layouts are fully compatible,
-                    // optional reference validity was checked beforehand
-
-                    unsafe {
-                        (self as *const Self as *const #opt_name<CGlueInst, CGlueCtx, #gen_use>).as_ref()
-                    }
+                    type Variant = #opt_name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>;
+                    type Final = #opt_final_name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>;
                 }
             });
         }
 
+        #[cfg(not(feature = "unstable"))]
+        let auto_vtables = format_ident!("{}AutoVtables", name);
+        #[cfg(not(feature = "unstable"))]
+        let (auto_vtables_def, filler_consts, forward_consts) =
+            self.filler_consts(&auto_vtables, &vtable_type);
+
         #[cfg(not(feature = "unstable"))]
         let (extra_filler_traits, filler_trait_imports) = if self.extra_filler_traits {
             let traits = quote! {
                 pub trait #fwd_filler_trait<'cglue_a, CGlueInst: #trg_path::InstanceBounds, CGlueCtx: #ctx_bound, #gen_declare>: 'cglue_a + Sized
                 where
-                    #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
+                    #cont_name<CGlueInst, CGlueCtx, #gen_use>: 'cglue_a + #trg_path::CGlueObjBase,
                     #gen_where_bounds
                 {
-                    fn fill_fwd_table(table: #vtable_type<'cglue_a, CGlueInst, CGlueCtx, #gen_use>) -> #vtable_type<'cglue_a, CGlueInst, CGlueCtx, #gen_use>;
+                    #filler_consts
                 }
 
                 impl<'cglue_a, CGlueInst: #trg_path::InstanceBounds<InstanceObjType = #crate_path::forward::Fwd<&'cglue_a mut CGlueT>>, CGlueT, CGlueCtx: #ctx_bound, #gen_declare>
                     #filler_trait<'cglue_a, CGlueInst, CGlueCtx, #gen_use>
                     for #crate_path::forward::Fwd<&'cglue_a mut CGlueT>
                 where
-                    #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
+                    #cont_name<CGlueInst, CGlueCtx, #gen_use>: 'cglue_a + #trg_path::CGlueObjBase,
                     CGlueT: #fwd_filler_trait<'cglue_a, CGlueInst, CGlueCtx, #gen_use>,
                     #gen_where_bounds
                 {
-                    fn fill_table(table: #vtable_type<'cglue_a, CGlueInst, CGlueCtx, #gen_use>) -> #vtable_type<'cglue_a, CGlueInst, CGlueCtx, #gen_use> {
-                        CGlueT::fill_fwd_table(table)
-                    }
+                    #forward_consts
                 }
             };
 
@@ -1144,36 +1531,214 @@ impl TraitGroup {
             (quote!(), quote!(#filler_trait,))
         };
 
+        #[cfg(not(feature = "unstable"))]
+        let auto_probe = format_ident!("{}AutoProbe", name);
+        #[cfg(not(feature = "unstable"))]
+        let auto_probe_impl =
+            self.auto_probe_impl(&auto_probe, &vtable_type, &trg_path, &gen_where_bounds);
+        #[cfg(not(feature = "unstable"))]
+        let filler_trait_imports = quote!(#filler_trait_imports #auto_probe, #auto_vtables,);
+
         #[cfg(feature = "unstable")]
         let filler_trait_imports = quote!();
 
-        let submod_name = format_ident!("cglue_{}", name.to_string().to_lowercase());
+        let (container_defs, container_new, container_new_bound) = match &self.base {
+            // Derived groups share the container with the base group, so that vtables stay the same.
+            Some(GroupBase { path, .. }) => {
+                let (mut base_cont, base_ident, _) =
+                    split_path_ident(path).expect("Failed to split base group path");
+                base_cont
+                    .segments
+                    .push(format_ident!("{}Container", base_ident).into());
+
+                (
+                    quote! {
+                        pub type #cont_name<CGlueInst, CGlueCtx, #gen_alias_declare>
+                            = #base_cont<CGlueInst, CGlueCtx, #gen_use>;
+                    },
+                    quote!(From::from((instance, context))),
+                    quote!(#cont_name<CGlueInst, CGlueCtx, #gen_use>: From<(CGlueInst, CGlueCtx)>,),
+                )
+            }
+            None => {
+                let cglue_obj_impl = self.cglue_obj_impl(&trg_path, &self.generics);
+
+                (
+                    quote! {
+                        #[repr(C)]
+                        #derive_layouts
+                        pub struct #cont_name<CGlueInst, CGlueCtx: #ctx_bound, #gen_declare>
+                        {
+                            instance: CGlueInst,
+                            context: CGlueCtx,
+                            #ret_tmp_defs
+                        }
+
+                        #cglue_obj_impl
+
+                        unsafe impl<CGlueInst: #trg_path::Opaquable, CGlueCtx: #ctx_bound, #gen_declare>
+                            #trg_path::Opaquable for #cont_name<CGlueInst, CGlueCtx, #gen_use>
+                        {
+                            type OpaqueTarget = #cont_name<CGlueInst::OpaqueTarget, CGlueCtx, #gen_use>;
+                        }
+
+                        impl<'cglue_a, CGlueInst: #trg_path::InstanceBounds, CGlueCtx: #ctx_bound, #gen_declare>
+                            From<(CGlueInst, CGlueCtx)> for #cont_name<CGlueInst, CGlueCtx, #gen_use>
+                        where
+                            Self: #trg_path::CGlueObjBase
+                        {
+                            fn from((instance, context): (CGlueInst, CGlueCtx)) -> Self {
+                                Self {
+                                    instance,
+                                    context,
+                                    #mand_ret_tmp_default
+                                    #full_opt_ret_tmp_default
+                                }
+                            }
+                        }
+
+                        impl<'cglue_a, CGlueT: Send, CGlueCtx: #ctx_bound, #gen_declare>
+                            From<(CGlueT, CGlueCtx)> for #cont_name<#crate_path::boxed::CBox<'cglue_a, CGlueT>, CGlueCtx, #gen_use>
+                        where
+                            Self: #trg_path::CGlueObjBase
+                        {
+                            fn from((this, context): (CGlueT, CGlueCtx)) -> Self {
+                                Self::from((#crate_path::boxed::CBox::from(this), context))
+                            }
+                        }
+                    },
+                    quote! {
+                        #cont_name {
+                            instance,
+                            context,
+                            #mand_ret_tmp_default
+                            #full_opt_ret_tmp_default
+                        }
+                    },
+                    quote!(),
+                )
+            }
+        };
+
+        let parts_type = self.parts_type();
+        let base_conversions = self.base_conversions(&trg_path, &gen_where_bounds);
+        let regroup_impl = self.regroup_impl(&trg_path, &gen_where_bounds);
 
-        let cglue_obj_impl = self.cglue_obj_impl(&trg_path, &self.generics);
+        let base_macro = format_ident!("__cglue_base_{}", name);
+        let base_info = &self.base_info;
+
+        let submod_name = format_ident!("cglue_{}", name.to_string().to_lowercase());
 
         #[cfg(feature = "unstable")]
         let cglue_inst_filler_trait_bound = quote!();
         #[cfg(not(feature = "unstable"))]
-        let cglue_inst_filler_trait_bound =
-            quote!(CGlueInst::Target: #filler_trait<'cglue_a, CGlueInst, CGlueCtx, #gen_use>,);
+        let cglue_inst_filler_trait_bound = if self.vtbl_table {
+            let table_filler = self.table_filler_name();
+            quote!(CGlueInst::Target: #table_filler<'cglue_a, CGlueInst, CGlueCtx, #gen_use>,)
+        } else {
+            quote!(CGlueInst::Target: #filler_trait<'cglue_a, CGlueInst, CGlueCtx, #gen_use>,)
+        };
         #[cfg(feature = "unstable")]
-        let create_vtbl = quote!(Default::default());
+        let create_vtbls = quote! {
+            let #vtable_type {
+                #mand_vtbl_list
+                #full_opt_vtbl_list
+            } = Default::default();
+        };
+        // Shared tables are taken out of the filler as a whole.
         #[cfg(not(feature = "unstable"))]
-        let create_vtbl = quote!(CGlueInst::Target::fill_table(Default::default()));
+        let create_vtbls = if self.vtbl_table {
+            quote!()
+        } else {
+            let opt_vtbls = self.optional_vtbl.iter().map(|info| {
+                let vtbl_name = &info.vtbl_name;
+                let vtbl_const_name = &info.vtbl_const_name;
+                quote! {
+                    let #vtbl_name = <CGlueInst::Target as #filler_trait<'cglue_a, CGlueInst, CGlueCtx, #gen_use>>::#vtbl_const_name;
+                }
+            });
+
+            quote! {
+                #mand_vtbl_let_default
+                #(#opt_vtbls)*
+            }
+        };
 
         #[cfg(feature = "unstable")]
         let filler_trait_impl = quote!();
         #[cfg(not(feature = "unstable"))]
         let filler_trait_impl = quote! {
+            #auto_vtables_def
+
             pub trait #filler_trait<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #gen_declare>: Sized
             where
-                #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
+                #cont_name<CGlueInst, CGlueCtx, #gen_use>: 'cglue_a + #trg_path::CGlueObjBase,
                 #gen_where_bounds
             {
-                fn fill_table(table: #vtable_type<'cglue_a, CGlueInst, CGlueCtx, #gen_use>) -> #vtable_type<'cglue_a, CGlueInst, CGlueCtx, #gen_use>;
+                #filler_consts
             }
 
             #extra_filler_traits
+
+            #auto_probe_impl
+        };
+
+        // Objects of groups with a shared table can only be built through the filler.
+        let (new_impl, from_parts_impl) = if self.vtbl_table {
+            (quote!(), quote!())
+        } else {
+            (
+                quote! {
+                    impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #gen_declare>
+                        #name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>
+                    where
+                        #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
+                        #vtbl_where_bounds #gen_where_bounds
+                    {
+                        #[doc = #new_doc]
+                        pub fn new(instance: CGlueInst, context: CGlueCtx, #optional_vtbl_defs) -> Self
+                            where #vtbl_where_bounds #container_new_bound
+                        {
+                            #mand_vtbl_let_default
+
+                            Self {
+                                container: #container_new,
+                                #group_vtbl_init
+                            }
+                        }
+                    }
+
+                    impl<'cglue_a, CGlueT: Send, #gen_declare> #name<'cglue_a, #crate_path::boxed::CBox<'cglue_a, CGlueT>, #crate_path::trait_group::NoContext, #gen_use>
+                        where #gen_where_bounds
+                    {
+                        #[doc = #new_doc]
+                        ///
+                        /// `instance` will be moved onto heap.
+                        pub fn new_boxed(this: CGlueT, #optional_vtbl_defs_boxed) -> Self
+                            where #vtbl_where_bounds_boxed
+                        {
+                            Self::new(From::from(this), Default::default(), #full_opt_vtbl_list)
+                        }
+                    }
+                },
+                quote! {
+                    /// Build the object out of its container and vtables.
+                    ///
+                    /// # Safety
+                    ///
+                    /// The vtables must belong to the type of the object inside the container.
+                    #[doc(hidden)]
+                    #[allow(clippy::type_complexity)]
+                    pub unsafe fn cglue_from_parts(
+                        (container, #mand_vtbl_list #full_opt_vtbl_list): #parts_type
+                    ) -> Self {
+                        Self {
+                            container,
+                            #group_vtbl_init
+                        }
+                    }
+                },
+            )
         };
 
         quote! {
@@ -1198,6 +1763,15 @@ impl TraitGroup {
                     #base_name_boxed,
                     #base_name_arc_box,
                     #base_name_ctx_box,
+                    #base_name_unsend_box,
+                    #base_name_arc_unsend_box,
+                    #base_name_ctx_unsend_box,
+                    #base_name_sync_box,
+                    #base_name_arc_sync_box,
+                    #base_name_ctx_sync_box,
+                    #base_name_inline,
+                    #base_name_arc_inline,
+                    #base_name_ctx_inline,
                     #opaque_name_ref,
                     #opaque_name_ctx_ref,
                     #opaque_name_arc_ref,
@@ -1207,6 +1781,15 @@ impl TraitGroup {
                     #opaque_name_boxed,
                     #opaque_name_arc_box,
                     #opaque_name_ctx_box,
+                    #opaque_name_unsend_box,
+                    #opaque_name_arc_unsend_box,
+                    #opaque_name_ctx_unsend_box,
+                    #opaque_name_sync_box,
+                    #opaque_name_arc_sync_box,
+                    #opaque_name_ctx_sync_box,
+                    #opaque_name_inline,
+                    #opaque_name_arc_inline,
+                    #opaque_name_ctx_inline,
                     #cont_name,
                     #opt_struct_imports
                 };
@@ -1218,17 +1801,17 @@ impl TraitGroup {
                 #[doc = #base_doc]
                 ///
                 /// Optional traits are not implemented here, however. There are numerous conversion
-                /// functions available for safely retrieving a concrete collection of traits.
+                /// macros available for safely retrieving a concrete collection of traits.
                 ///
-                /// `check_impl_` functions allow to check if the object implements the wanted traits.
+                /// `check!` macro allows to check if the object implements the wanted traits.
                 ///
-                /// `into_impl_` functions consume the object and produce a new final structure that
-                /// keeps only the required information.
+                /// `into!`, and `try_into!` macros consume the object and produce a new final
+                /// structure that keeps only the required information.
                 ///
-                /// `cast_impl_` functions merely check and transform the object into a type that can
+                /// `cast!` macro merely checks and transforms the object into a type that can
                 #[doc = #trback_doc]
                 ///
-                /// `as_ref_`, and `as_mut_` functions obtain references to safe objects, but do not
+                /// `as_ref!`, and `as_mut!` macros obtain references to safe objects, but do not
                 /// perform any memory transformations either. They are the safest to use, because
                 /// there is no risk of accidentally consuming the whole object.
                 #derive_layouts
@@ -1237,29 +1820,15 @@ impl TraitGroup {
                     #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
                     #gen_where_bounds_base
                 {
-                    #mandatory_vtbl_defs
-                    #optional_vtbl_defs
+                    #group_vtbl_defs
                     container: #cont_name<CGlueInst, CGlueCtx, #gen_use>,
                 }
 
-                #get_container_impl
-
-                #[repr(C)]
-                #derive_layouts
-                pub struct #cont_name<CGlueInst, CGlueCtx: #ctx_bound, #gen_declare>
-                {
-                    instance: CGlueInst,
-                    context: CGlueCtx,
-                    #ret_tmp_defs
-                }
+                #group_obj_impl
 
-                #cglue_obj_impl
+                #vtbl_table_impl
 
-                unsafe impl<CGlueInst: #trg_path::Opaquable, CGlueCtx: #ctx_bound, #gen_declare>
-                    #trg_path::Opaquable for #cont_name<CGlueInst, CGlueCtx, #gen_use>
-                {
-                    type OpaqueTarget = #cont_name<CGlueInst::OpaqueTarget, CGlueCtx, #gen_use>;
-                }
+                #container_defs
 
                 #[repr(C)]
                 #derive_layouts
@@ -1304,88 +1873,117 @@ impl TraitGroup {
 
                 #filler_trait_impl
 
-                pub type #base_name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>
+                pub type #base_name<'cglue_a, CGlueInst, CGlueCtx, #gen_alias_declare>
                     = #name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>;
 
-                pub type #base_name_boxed<'cglue_a, CGlueT, #gen_use>
+                pub type #base_name_boxed<'cglue_a, CGlueT, #gen_alias_declare>
                     = #base_name_ctx_box<'cglue_a, CGlueT, #crate_path::trait_group::NoContext, #gen_use>;
 
-                pub type #base_name_ctx_box<'cglue_a, CGlueT, CGlueCtx, #gen_use>
+                pub type #base_name_ctx_box<'cglue_a, CGlueT, CGlueCtx, #gen_alias_declare>
                     = #name<'cglue_a, #crate_path::boxed::CBox<'cglue_a, CGlueT>, CGlueCtx, #gen_use>;
 
-                pub type #base_name_arc_box<'cglue_a, CGlueT, CGlueArcTy, #gen_use>
+                pub type #base_name_arc_box<'cglue_a, CGlueT, CGlueArcTy, #gen_alias_declare>
                     = #base_name_ctx_box<'cglue_a, CGlueT, #crate_path::arc::CArc<CGlueArcTy>, #gen_use>;
 
-                pub type #base_name_ref<'cglue_a, CGlueT, #gen_use>
+                pub type #base_name_unsend_box<'cglue_a, CGlueT, #gen_alias_declare>
+                    = #base_name_ctx_unsend_box<'cglue_a, CGlueT, #crate_path::trait_group::NoContext, #gen_use>;
+
+                pub type #base_name_ctx_unsend_box<'cglue_a, CGlueT, CGlueCtx, #gen_alias_declare>
+                    = #name<'cglue_a, #crate_path::boxed::CUnsendBox<'cglue_a, CGlueT>, CGlueCtx, #gen_use>;
+
+                pub type #base_name_arc_unsend_box<'cglue_a, CGlueT, CGlueArcTy, #gen_alias_declare>
+                    = #base_name_ctx_unsend_box<'cglue_a, CGlueT, #crate_path::arc::CArc<CGlueArcTy>, #gen_use>;
+
+                pub type #base_name_sync_box<'cglue_a, CGlueT, #gen_alias_declare>
+                    = #base_name_ctx_sync_box<'cglue_a, CGlueT, #crate_path::trait_group::NoContext, #gen_use>;
+
+                pub type #base_name_ctx_sync_box<'cglue_a, CGlueT, CGlueCtx, #gen_alias_declare>
+                    = #name<'cglue_a, #crate_path::boxed::CSyncBox<'cglue_a, CGlueT>, CGlueCtx, #gen_use>;
+
+                pub type #base_name_arc_sync_box<'cglue_a, CGlueT, CGlueArcTy, #gen_alias_declare>
+                    = #base_name_ctx_sync_box<'cglue_a, CGlueT, #crate_path::arc::CArc<CGlueArcTy>, #gen_use>;
+
+                pub type #base_name_inline<'cglue_a, CGlueT, #gen_alias_declare const CGLUE_N: usize>
+                    = #base_name_ctx_inline<'cglue_a, CGlueT, #crate_path::trait_group::NoContext, #gen_use_inline CGLUE_N>;
+
+                pub type #base_name_ctx_inline<'cglue_a, CGlueT, CGlueCtx, #gen_alias_declare const CGLUE_N: usize>
+                    = #name<'cglue_a, #crate_path::inline::CInline<'cglue_a, CGlueT, CGLUE_N>, CGlueCtx, #gen_use>;
+
+                pub type #base_name_arc_inline<'cglue_a, CGlueT, CGlueArcTy, #gen_alias_declare const CGLUE_N: usize>
+                    = #base_name_ctx_inline<'cglue_a, CGlueT, #crate_path::arc::CArc<CGlueArcTy>, #gen_use_inline CGLUE_N>;
+
+                pub type #base_name_ref<'cglue_a, CGlueT, #gen_alias_declare>
                     = #name<'cglue_a, &'cglue_a CGlueT, #crate_path::trait_group::NoContext, #gen_use>;
 
-                pub type #base_name_ctx_ref<'cglue_a, CGlueT, CGlueCtx, #gen_use>
+                pub type #base_name_ctx_ref<'cglue_a, CGlueT, CGlueCtx, #gen_alias_declare>
                     = #name<'cglue_a, &'cglue_a CGlueT, CGlueCtx, #gen_use>;
 
-                pub type #base_name_arc_ref<'cglue_a, CGlueT, CGlueArcTy, #gen_use>
+                pub type #base_name_arc_ref<'cglue_a, CGlueT, CGlueArcTy, #gen_alias_declare>
                     = #name<'cglue_a, &'cglue_a CGlueT, #crate_path::arc::CArc<CGlueArcTy>, #gen_use>;
 
-                pub type #base_name_mut<'cglue_a, CGlueT, #gen_use>
+                pub type #base_name_mut<'cglue_a, CGlueT, #gen_alias_declare>
                     = #name<'cglue_a, &'cglue_a mut CGlueT, #crate_path::trait_group::NoContext, #gen_use>;
 
-                pub type #base_name_ctx_mut<'cglue_a, CGlueT, CGlueCtx, #gen_use>
+                pub type #base_name_ctx_mut<'cglue_a, CGlueT, CGlueCtx, #gen_alias_declare>
                     = #name<'cglue_a, &'cglue_a mut CGlueT, CGlueCtx, #gen_use>;
 
-                pub type #base_name_arc_mut<'cglue_a, CGlueT, CGlueArcTy, #gen_use>
+                pub type #base_name_arc_mut<'cglue_a, CGlueT, CGlueArcTy, #gen_alias_declare>
                     = #name<'cglue_a, &'cglue_a mut CGlueT, #crate_path::arc::CArc<CGlueArcTy>, #gen_use>;
 
-                pub type #opaque_name_boxed<'cglue_a, #gen_use>
+                pub type #opaque_name_boxed<'cglue_a, #gen_alias_declare>
                     = #base_name_boxed<'cglue_a, #c_void, #gen_use>;
 
-                pub type #opaque_name_ref<'cglue_a, #gen_use>
+                pub type #opaque_name_ref<'cglue_a, #gen_alias_declare>
                     = #base_name_ref<'cglue_a, #c_void, #gen_use>;
 
-                pub type #opaque_name_ctx_ref<'cglue_a, CGlueCtx, #gen_use>
+                pub type #opaque_name_ctx_ref<'cglue_a, CGlueCtx, #gen_alias_declare>
                     = #base_name_ctx_ref<'cglue_a, #c_void, CGlueCtx, #gen_use>;
 
-                pub type #opaque_name_arc_ref<'cglue_a, #gen_use>
+                pub type #opaque_name_arc_ref<'cglue_a, #gen_alias_declare>
                     = #base_name_arc_ref<'cglue_a, #c_void, #c_void, #gen_use>;
 
-                pub type #opaque_name_mut<'cglue_a, #gen_use>
+                pub type #opaque_name_mut<'cglue_a, #gen_alias_declare>
                     = #base_name_mut<'cglue_a, #c_void, #gen_use>;
 
-                pub type #opaque_name_ctx_mut<'cglue_a, CGlueCtx, #gen_use>
+                pub type #opaque_name_ctx_mut<'cglue_a, CGlueCtx, #gen_alias_declare>
                     = #base_name_ctx_mut<'cglue_a, #c_void, CGlueCtx, #gen_use>;
 
-                pub type #opaque_name_arc_mut<'cglue_a, #gen_use>
+                pub type #opaque_name_arc_mut<'cglue_a, #gen_alias_declare>
                     = #base_name_arc_mut<'cglue_a, #c_void, #c_void, #gen_use>;
 
-                pub type #opaque_name_ctx_box<'cglue_a, CGlueCtx, #gen_use>
+                pub type #opaque_name_ctx_box<'cglue_a, CGlueCtx, #gen_alias_declare>
                     = #base_name_ctx_box<'cglue_a, #c_void, CGlueCtx, #gen_use>;
 
-                pub type #opaque_name_arc_box<'cglue_a, #gen_use>
+                pub type #opaque_name_arc_box<'cglue_a, #gen_alias_declare>
                     = #base_name_arc_box<'cglue_a, #c_void, #c_void, #gen_use>;
 
+                pub type #opaque_name_unsend_box<'cglue_a, #gen_alias_declare>
+                    = #base_name_unsend_box<'cglue_a, #c_void, #gen_use>;
 
-                impl<'cglue_a, CGlueInst: #trg_path::InstanceBounds, CGlueCtx: #ctx_bound, #gen_declare>
-                    From<(CGlueInst, CGlueCtx)> for #cont_name<CGlueInst, CGlueCtx, #gen_use>
-                where
-                    Self: #trg_path::CGlueObjBase
-                {
-                    fn from((instance, context): (CGlueInst, CGlueCtx)) -> Self {
-                        Self {
-                            instance,
-                            context,
-                            #mand_ret_tmp_default
-                            #full_opt_ret_tmp_default
-                        }
-                    }
-                }
+                pub type #opaque_name_ctx_unsend_box<'cglue_a, CGlueCtx, #gen_alias_declare>
+                    = #base_name_ctx_unsend_box<'cglue_a, #c_void, CGlueCtx, #gen_use>;
+
+                pub type #opaque_name_arc_unsend_box<'cglue_a, #gen_alias_declare>
+                    = #base_name_arc_unsend_box<'cglue_a, #c_void, #c_void, #gen_use>;
+
+                pub type #opaque_name_sync_box<'cglue_a, #gen_alias_declare>
+                    = #base_name_sync_box<'cglue_a, #c_void, #gen_use>;
+
+                pub type #opaque_name_ctx_sync_box<'cglue_a, CGlueCtx, #gen_alias_declare>
+                    = #base_name_ctx_sync_box<'cglue_a, #c_void, CGlueCtx, #gen_use>;
+
+                pub type #opaque_name_arc_sync_box<'cglue_a, #gen_alias_declare>
+                    = #base_name_arc_sync_box<'cglue_a, #c_void, #c_void, #gen_use>;
+
+                pub type #opaque_name_inline<'cglue_a, #gen_alias_declare const CGLUE_N: usize>
+                    = #base_name_inline<'cglue_a, #c_void, #gen_use_inline CGLUE_N>;
+
+                pub type #opaque_name_ctx_inline<'cglue_a, CGlueCtx, #gen_alias_declare const CGLUE_N: usize>
+                    = #base_name_ctx_inline<'cglue_a, #c_void, CGlueCtx, #gen_use_inline CGLUE_N>;
+
+                pub type #opaque_name_arc_inline<'cglue_a, #gen_alias_declare const CGLUE_N: usize>
+                    = #base_name_arc_inline<'cglue_a, #c_void, #c_void, #gen_use_inline CGLUE_N>;
 
-                impl<'cglue_a, CGlueT: Send, CGlueCtx: #ctx_bound, #gen_declare>
-                    From<(CGlueT, CGlueCtx)> for #cont_name<#crate_path::boxed::CBox<'cglue_a, CGlueT>, CGlueCtx, #gen_use>
-                where
-                    Self: #trg_path::CGlueObjBase
-                {
-                    fn from((this, context): (CGlueT, CGlueCtx)) -> Self {
-                        Self::from((#crate_path::boxed::CBox::from(this), context))
-                    }
-                }
 
                 impl<'cglue_a, CGlueInst: #trg_path::InstanceBounds, CGlueCtx: #ctx_bound, #gen_declare>
                     From<#cont_name<CGlueInst, CGlueCtx, #gen_use>> for #name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>
@@ -1395,17 +1993,11 @@ impl TraitGroup {
                     #vtbl_where_bounds #gen_where_bounds
                 {
                     fn from(container: #cont_name<CGlueInst, CGlueCtx, #gen_use>) -> Self {
-                        let vtbl = #create_vtbl;
-
-                        let #vtable_type {
-                            #mand_vtbl_list
-                            #full_opt_vtbl_list
-                        } = vtbl;
+                        #create_vtbls
 
                         Self {
                             container,
-                            #mand_vtbl_list
-                            #full_opt_vtbl_list
+                            #group_vtbl_init
                         }
                     }
                 }
@@ -1457,41 +2049,7 @@ impl TraitGroup {
                     }
                 }
 
-                impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #gen_declare>
-                    #name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>
-                where
-                    #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
-                    #vtbl_where_bounds #gen_where_bounds
-                {
-                    #[doc = #new_doc]
-                    pub fn new(instance: CGlueInst, context: CGlueCtx, #optional_vtbl_defs) -> Self
-                        where #vtbl_where_bounds
-                    {
-                        Self {
-                            container: #cont_name {
-                                instance,
-                                context,
-                                #mand_ret_tmp_default
-                                #full_opt_ret_tmp_default
-                            },
-                            #mand_vtbl_default
-                            #full_opt_vtbl_list
-                        }
-                    }
-                }
-
-                impl<'cglue_a, CGlueT: Send, #gen_declare> #name<'cglue_a, #crate_path::boxed::CBox<'cglue_a, CGlueT>, #crate_path::trait_group::NoContext, #gen_use>
-                    where #gen_where_bounds
-                {
-                    #[doc = #new_doc]
-                    ///
-                    /// `instance` will be moved onto heap.
-                    pub fn new_boxed(this: CGlueT, #optional_vtbl_defs_boxed) -> Self
-                        where #vtbl_where_bounds_boxed
-                    {
-                        Self::new(From::from(this), Default::default(), #full_opt_vtbl_list)
-                    }
-                }
+                #new_impl
 
                 /// Convert into opaque object.
                 ///
@@ -1506,36 +2064,572 @@ impl TraitGroup {
                     type OpaqueTarget = #name<'cglue_a, CGlueInst::OpaqueTarget, CGlueCtx, #gen_use>;
                 }
 
-                impl<
-                    'cglue_a,
-                    CGlueInst, //: #trg_path::InstanceBounds
-                    CGlueCtx: #ctx_bound,
-                    #gen_declare
-                >
+                impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #gen_declare>
                     #name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>
                 where
                     #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
                     #gen_where_bounds
                 {
-                    #trait_funcs
+                    /// Split the object into its container and vtables.
+                    #[doc(hidden)]
+                    #[allow(clippy::type_complexity)]
+                    pub fn cglue_into_parts(self) -> #parts_type {
+                        #self_vtbl_bind
+
+                        (container, #mand_vtbl_list #full_opt_vtbl_list)
+                    }
+
+                    #from_parts_impl
                 }
 
+                #base_conversions
+
+                #regroup_impl
+
+                #[doc(hidden)]
+                #[allow(unused_macros)]
+                macro_rules! #base_macro {
+                    ($($cglue_tt:tt)*) => {
+                        #crate_path::cglue_trait_group!(@cglue_base(#base_info) $($cglue_tt)*);
+                    };
+                }
+
+                #[doc(hidden)]
+                #[allow(unused_imports)]
+                pub(crate) use #base_macro as #name;
+
                 #mandatory_as_ref_impls
 
                 #mandatory_internal_trait_impls
 
+                #variant_impl
+
                 #opt_structs
             }
             }
         }
     }
 
-    fn internal_trait_impls<'a>(
-        &'a self,
+    /// Type of the tuple the group is split into - container, mandatory and optional vtables.
+    fn parts_type(&self) -> TokenStream {
+        let cont_name = &self.cont_name;
+        let all_gen_use = &self.generics.gen_use;
+
+        let mut vtbls = TokenStream::new();
+
+        for (
+            TraitInfo {
+                path,
+                vtbl_typename,
+                generics: ParsedGenerics { gen_use, .. },
+                assocs:
+                    ParsedGenerics {
+                        gen_use: assoc_use, ..
+                    },
+                ..
+            },
+            optional,
+        ) in self
+            .mandatory_vtbl
+            .iter()
+            .map(|v| (v, false))
+            .chain(self.optional_vtbl.iter().map(|v| (v, true)))
+        {
+            let vtbl = quote!(&'cglue_a #path #vtbl_typename<'cglue_a, #cont_name<CGlueInst, CGlueCtx, #all_gen_use>, #gen_use #assoc_use>);
+
+            if optional {
+                vtbls.extend(quote!(::core::option::Option<#vtbl>,));
+            } else {
+                vtbls.extend(quote!(#vtbl,));
+            }
+        }
+
+        quote!((#cont_name<CGlueInst, CGlueCtx, #all_gen_use>, #vtbls))
+    }
+
+    /// Conversions between a derived group and its base group.
+    ///
+    /// Upcasting moves all vtables over as they are, while downcasting checks that the optional
+    /// traits of the base group which are mandatory in the derived group are implemented.
+    ///
+    /// Shared tables are only built at compile time, thus upcasting requires the base group to
+    /// store its vtables inline, and downcasting requires both of the groups to do so.
+    fn base_conversions(
+        &self,
+        trg_path: &TokenStream,
+        gen_where_bounds: &TokenStream,
+    ) -> TokenStream {
+        let base = match &self.base {
+            Some(base) => base,
+            None => return quote!(),
+        };
+
+        let GroupBase {
+            path,
+            vtbl_table: base_vtbl_table,
+            mandatory_vtbl: base_mandatory,
+            optional_vtbl: base_optional,
+        } = base;
+
+        if *base_vtbl_table {
+            return quote!();
+        }
+
+        let name = &self.name;
+        let cont_name = &self.cont_name;
+        let ctx_bound = super::traits::ctx_bound();
+
+        let ParsedGenerics {
+            gen_declare,
+            gen_use,
+            ..
+        } = &self.generics;
+
+        let base_ty = quote!(#path<'cglue_a, CGlueInst, CGlueCtx, #gen_use>);
+
+        let mand_vtbl_list = self.vtbl_list(self.mandatory_vtbl.iter());
+        let opt_vtbl_list = self.vtbl_list(self.optional_vtbl.iter());
+        let base_mand_list = self.vtbl_list(base_mandatory.iter());
+
+        let is_mandatory = |vtbl_name: &Ident| {
+            self.mandatory_vtbl
+                .iter()
+                .any(|v| &v.vtbl_name == vtbl_name)
+        };
+
+        let promoted = base_optional
+            .iter()
+            .map(|v| &v.vtbl_name)
+            .filter(|v| is_mandatory(v))
+            .collect::<Vec<_>>();
+
+        let mut upcast_opt_list = TokenStream::new();
+        let mut downcast_opt_list = TokenStream::new();
+
+        for TraitInfo { vtbl_name, .. } in base_optional {
+            if is_mandatory(vtbl_name) {
+                upcast_opt_list.extend(quote!(Some(#vtbl_name),));
+                downcast_opt_list.extend(quote!(#vtbl_name,));
+            } else if self.optional_vtbl.iter().any(|v| &v.vtbl_name == vtbl_name) {
+                upcast_opt_list.extend(quote!(#vtbl_name,));
+                downcast_opt_list.extend(quote!(#vtbl_name,));
+            } else {
+                upcast_opt_list.extend(quote!(None,));
+                // Dropped from the derived group. The binding is only needed for failed downcasts.
+                if promoted.is_empty() {
+                    downcast_opt_list.extend(quote!(_,));
+                } else {
+                    downcast_opt_list.extend(quote!(#vtbl_name,));
+                }
+            }
+        }
+
+        let group_vtbl_init = self.group_vtbl_init();
+
+        let downcast_ok = quote! {
+            ::core::result::Result::Ok(Self {
+                container,
+                #group_vtbl_init
+            })
+        };
+
+        let downcast_body = if promoted.is_empty() {
+            downcast_ok
+        } else {
+            let base_opt_list = self.vtbl_list(base_optional.iter());
+
+            quote! {
+                if let (#(Some(#promoted),)*) = (#(#promoted,)*) {
+                    return #downcast_ok;
+                }
+
+                // Safety:
+                //
+                // The parts have been taken out of the very same object.
+                ::core::result::Result::Err(unsafe {
+                    <#base_ty>::cglue_from_parts((container, #base_mand_list #base_opt_list))
+                })
+            }
+        };
+
+        let upcast = quote! {
+            impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #gen_declare>
+                From<#name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>> for #base_ty
+            where
+                #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
+                #gen_where_bounds
+            {
+                fn from(input: #name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>) -> Self {
+                    let (container, #mand_vtbl_list #opt_vtbl_list) = input.cglue_into_parts();
+
+                    // Safety:
+                    //
+                    // Both groups share the container, and the vtables were built for it.
+                    unsafe {
+                        Self::cglue_from_parts((container, #base_mand_list #upcast_opt_list))
+                    }
+                }
+            }
+        };
+
+        if self.vtbl_table {
+            return upcast;
+        }
+
+        quote! {
+            #upcast
+
+            impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #gen_declare>
+                ::core::convert::TryFrom<#base_ty> for #name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>
+            where
+                #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
+                #gen_where_bounds
+            {
+                type Error = #base_ty;
+
+                fn try_from(input: #base_ty) -> ::core::result::Result<Self, Self::Error> {
+                    let (container, #base_mand_list #downcast_opt_list) = input.cglue_into_parts();
+
+                    #downcast_body
+                }
+            }
+        }
+    }
+
+    /// Probe for automatic detection of optional traits in `cglue_impl_group!`.
+    ///
+    /// Every optional trait is identified by its index. The probe has an inherent `CGLUE_VTBL`
+    /// constant whenever the probed type implements the trait at the given index, which takes
+    /// precedence over the `None` provided by `AutoSkip`.
+    #[cfg(not(feature = "unstable"))]
+    fn auto_probe_impl(
+        &self,
+        auto_probe: &Ident,
+        vtable_type: &Ident,
+        trg_path: &TokenStream,
+        gen_where_bounds: &TokenStream,
+    ) -> TokenStream {
+        let cont_name = &self.cont_name;
+        let ctx_bound = super::traits::ctx_bound();
+
+        let ParsedGenerics {
+            gen_declare,
+            gen_use,
+            ..
+        } = &self.generics;
+
+        let cglue_a_lifetime = Lifetime {
+            apostrophe: proc_macro2::Span::call_site(),
+            ident: format_ident!("cglue_a"),
+        };
+
+        let table_ty = quote!(#vtable_type<'cglue_a, CGlueInst, CGlueCtx, #gen_use>);
+
+        let optional_traits = self.optional_vtbl.len();
+
+        let mut ret = quote! {
+            /// Probe for optional traits implemented by `CGlueT`.
+            ///
+            /// `CGLUE_N` is the index of the optional trait being probed.
+            #[doc(hidden)]
+            pub struct #auto_probe<CGlueT, CGlueV, const CGLUE_N: usize>(
+                pub ::core::marker::PhantomData<(CGlueV, *const CGlueT)>,
+            );
+
+            impl<CGlueT, CGlueV, CGlueX, const CGLUE_N: usize> #trg_path::AutoSkip<CGlueX>
+                for #auto_probe<CGlueT, CGlueV, CGLUE_N>
+            {
+            }
+
+            impl<CGlueT, CGlueV, const CGLUE_N: usize> #auto_probe<CGlueT, CGlueV, CGLUE_N> {
+                /// Number of the optional traits of the group.
+                pub const CGLUE_OPTIONAL_TRAITS: usize = #optional_traits;
+            }
+        };
+
+        for (i, info) in self.optional_vtbl.iter().enumerate() {
+            let vtbl_type = self.vtbl_type(info);
+            let idx = proc_macro2::Literal::usize_unsuffixed(i);
+
+            let vtbl_where_bounds = Self::vtbl_where_bounds(
+                std::iter::once(info),
+                cont_name,
+                quote!(CGlueInst),
+                quote!(CGlueCtx),
+                &self.generics,
+                Some(&quote!(CGlueT)),
+                &cglue_a_lifetime,
+            );
+
+            ret.extend(quote! {
+                impl<'cglue_a, CGlueT, CGlueInst, CGlueCtx: #ctx_bound, #gen_declare>
+                    #auto_probe<CGlueT, #table_ty, #idx>
+                where
+                    #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
+                    #vtbl_where_bounds #gen_where_bounds
+                {
+                    pub const CGLUE_VTBL: ::core::option::Option<&'cglue_a #vtbl_type> =
+                        ::core::option::Option::Some(#trg_path::StaticVtbl::CGLUE_VTBL);
+                }
+            });
+        }
+
+        ret
+    }
+
+    /// Constants of the vtable filler traits, one for every optional trait.
+    ///
+    /// The first `MAX_AUTO_TRAITS` of them default to vtables detected by `cglue_impl_group!` with
+    /// `auto`, which are held in a tuple of the given type.
+    ///
+    /// Returns the definition of the tuple type, constants of the filler traits, and their
+    /// forwarding implementations.
+    #[cfg(not(feature = "unstable"))]
+    fn filler_consts(
+        &self,
+        auto_vtables: &Ident,
+        vtable_type: &Ident,
+    ) -> (TokenStream, TokenStream, TokenStream) {
+        let gen_use = &self.generics.gen_use;
+        let gen_alias_declare = self.generics.alias_declare();
+
+        let mut auto_types = TokenStream::new();
+        let mut auto_none = TokenStream::new();
+        let mut consts = TokenStream::new();
+        let mut forward = TokenStream::new();
+
+        for i in 0..MAX_AUTO_TRAITS {
+            match self.optional_vtbl.get(i) {
+                Some(info) => {
+                    let vtbl_type = self.vtbl_type(info);
+                    auto_types.extend(quote!(::core::option::Option<&'cglue_a #vtbl_type>,));
+                }
+                // Groups without optional traits still need to use the generic parameters.
+                None if i == 0 => auto_types.extend(quote! {
+                    ::core::option::Option<::core::marker::PhantomData<
+                        #vtable_type<'cglue_a, CGlueInst, CGlueCtx, #gen_use>
+                    >>,
+                }),
+                None => auto_types.extend(quote!(::core::option::Option<()>,)),
+            }
+            auto_none.extend(quote!(::core::option::Option::None,));
+        }
+
+        for (i, info) in self.optional_vtbl.iter().enumerate() {
+            let vtbl_const_name = &info.vtbl_const_name;
+            let vtbl_type = self.vtbl_type(info);
+
+            let default = if i < MAX_AUTO_TRAITS {
+                let idx = syn::Index::from(i);
+                quote!(Self::CGLUE_AUTO.#idx)
+            } else {
+                quote!(::core::option::Option::None)
+            };
+
+            let doc = format!(
+                " Vtable of `{}`, if the type implements it.",
+                info.name_ident
+            );
+
+            consts.extend(quote! {
+                #[doc = #doc]
+                const #vtbl_const_name: ::core::option::Option<&'cglue_a #vtbl_type> = #default;
+            });
+
+            forward.extend(quote! {
+                const #vtbl_const_name: ::core::option::Option<&'cglue_a #vtbl_type> =
+                    CGlueT::#vtbl_const_name;
+            });
+        }
+
+        let tuple = quote! {
+            /// Optional vtables detected by `cglue_impl_group!` with `auto`.
+            #[doc(hidden)]
+            pub type #auto_vtables<'cglue_a, CGlueInst, CGlueCtx, #gen_alias_declare> = (#auto_types);
+        };
+
+        let consts = quote! {
+            /// Optional vtables detected by `cglue_impl_group!` with `auto`.
+            #[doc(hidden)]
+            const CGLUE_AUTO: #auto_vtables<'cglue_a, CGlueInst, CGlueCtx, #gen_use> = (#auto_none);
+
+            #consts
+        };
+
+        (tuple, consts, forward)
+    }
+
+    /// `GetVtblOpt` implementations for all traits of the group, and conversion from any other
+    /// group that shares the traits.
+    ///
+    /// Vtables are monomorphized for a specific container type, thus they can only be moved over
+    /// when neither of the containers store anything besides the instance and context. Groups
+    /// with a shared table can only be regrouped into other groups.
+    fn regroup_impl(&self, trg_path: &TokenStream, gen_where_bounds: &TokenStream) -> TokenStream {
+        let name = &self.name;
+        let cont_name = &self.cont_name;
+        let ctx_bound = super::traits::ctx_bound();
+
+        let ParsedGenerics {
+            gen_declare,
+            gen_use: all_gen_use,
+            ..
+        } = &self.generics;
+
+        let cont_ty = quote!(#cont_name<CGlueInst, CGlueCtx, #all_gen_use>);
+        let src_cont_ty = quote!(<CGlueS as #trg_path::GetContainer>::ContType);
+
+        let mut ret = TokenStream::new();
+        let mut src_bounds = TokenStream::new();
+        let mut get_vtbls = TokenStream::new();
+        let mut cast_vtbls = TokenStream::new();
+
+        for (
+            TraitInfo {
+                vtbl_name,
+                path,
+                vtbl_typename,
+                generics: ParsedGenerics { gen_use, .. },
+                assocs:
+                    ParsedGenerics {
+                        gen_use: assoc_use, ..
+                    },
+                ..
+            },
+            optional,
+        ) in self
+            .mandatory_vtbl
+            .iter()
+            .map(|v| (v, false))
+            .chain(self.optional_vtbl.iter().map(|v| (v, true)))
+        {
+            let vtbl_ty = quote!(#path #vtbl_typename<'cglue_a, #cont_ty, #gen_use #assoc_use>);
+            let src_vtbl_ty =
+                quote!(#path #vtbl_typename<'cglue_a, #src_cont_ty, #gen_use #assoc_use>);
+
+            let vtbl_field = self.vtbl_field(quote!(self), vtbl_name);
+
+            let vtbl_opt = if optional {
+                vtbl_field
+            } else {
+                quote!(::core::option::Option::Some(#vtbl_field))
+            };
+
+            ret.extend(quote! {
+                impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #gen_declare>
+                    #trg_path::GetVtblOpt<'cglue_a, #vtbl_ty>
+                    for #name<'cglue_a, CGlueInst, CGlueCtx, #all_gen_use>
+                where
+                    #cont_ty: #trg_path::CGlueObjBase,
+                    #gen_where_bounds
+                {
+                    fn get_vtbl_opt(&self) -> ::core::option::Option<&'cglue_a #vtbl_ty> {
+                        #vtbl_opt
+                    }
+                }
+            });
+
+            src_bounds.extend(quote!(CGlueS: #trg_path::GetVtblOpt<'cglue_a, #src_vtbl_ty>,));
+
+            get_vtbls.extend(quote! {
+                let #vtbl_name = <CGlueS as #trg_path::GetVtblOpt<'cglue_a, #src_vtbl_ty>>::get_vtbl_opt(&src);
+            });
+
+            let cast = quote!(&*(#vtbl_name as *const #src_vtbl_ty as *const #vtbl_ty));
+
+            if optional {
+                cast_vtbls.extend(quote!(#vtbl_name.map(|#vtbl_name| #cast),));
+            } else {
+                cast_vtbls.extend(quote!(#cast,));
+            }
+        }
+
+        // Shared tables can not be built out of vtables of other groups.
+        if self.vtbl_table {
+            return ret;
+        }
+
+        let mand_vtbls = self
+            .mandatory_vtbl
+            .iter()
+            .map(|v| &v.vtbl_name)
+            .collect::<Vec<_>>();
+
+        let unwrap_mandatory = if mand_vtbls.is_empty() {
+            quote!()
+        } else {
+            quote! {
+                let (#(#mand_vtbls,)*) = match (#(#mand_vtbls,)*) {
+                    (#(::core::option::Option::Some(#mand_vtbls),)*) => (#(#mand_vtbls,)*),
+                    _ => return ::core::result::Result::Err(src),
+                };
+            }
+        };
+
+        ret.extend(quote! {
+            impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #gen_declare>
+                #name<'cglue_a, CGlueInst, CGlueCtx, #all_gen_use>
+            where
+                #cont_ty: #trg_path::CGlueObjBase + From<(CGlueInst, CGlueCtx)>,
+                #gen_where_bounds
+            {
+                /// Build the group out of another group that shares its traits.
+                ///
+                /// Use `regroup!` macro instead of calling this directly.
+                #[doc(hidden)]
+                pub fn cglue_regroup<CGlueS>(src: CGlueS) -> ::core::result::Result<Self, CGlueS>
+                where
+                    CGlueS: #trg_path::GetContainer,
+                    #src_cont_ty: #trg_path::CGlueObjBase<InstType = CGlueInst, Context = CGlueCtx> + 'cglue_a,
+                    #src_bounds
+                {
+                    use ::core::mem::{align_of, size_of};
+
+                    type CGlueBare<CGlueInst, CGlueCtx> = #trg_path::CGlueObjContainer<CGlueInst, CGlueCtx, ()>;
+
+                    let bare_layout = |size, align| {
+                        size == size_of::<CGlueBare<CGlueInst, CGlueCtx>>()
+                            && align == align_of::<CGlueBare<CGlueInst, CGlueCtx>>()
+                    };
+
+                    if !bare_layout(size_of::<#src_cont_ty>(), align_of::<#src_cont_ty>())
+                        || !bare_layout(size_of::<#cont_ty>(), align_of::<#cont_ty>())
+                    {
+                        return ::core::result::Result::Err(src);
+                    }
+
+                    #get_vtbls
+
+                    #unwrap_mandatory
+
+                    let (instance, context) = #trg_path::CGlueObjBase::cobj_base_owned(
+                        #trg_path::GetContainer::into_ccont(src)
+                    );
+
+                    // Safety:
+                    //
+                    // Neither of the containers hold anything besides the instance and context,
+                    // thus vtables built for one of them are valid for the other.
+                    unsafe {
+                        ::core::result::Result::Ok(Self::cglue_from_parts((
+                            From::from((instance, context)),
+                            #cast_vtbls
+                        )))
+                    }
+                }
+            }
+        });
+
+        ret
+    }
+
+    fn internal_trait_impls<'a>(
+        &'a self,
         self_ident: &Ident,
         iter: impl Iterator<Item = &'a TraitInfo>,
         all_generics: &ParsedGenerics,
         crate_path: &TokenStream,
+        variant: bool,
     ) -> TokenStream {
         let mut ret = TokenStream::new();
 
@@ -1544,19 +2638,21 @@ impl TraitGroup {
         let ctx_bound = super::traits::ctx_bound();
 
         let ParsedGenerics { gen_use, .. } = all_generics;
+        let gen_alias_declare = all_generics.alias_declare();
+
+        for info in iter {
+            let TraitInfo {
+                path,
+                raw_ident,
+                generics:
+                    ParsedGenerics {
+                        life_use: tr_life_use,
+                        gen_use: tr_gen_use,
+                        ..
+                    },
+                ..
+            } = info;
 
-        for TraitInfo {
-            path,
-            raw_ident,
-            generics:
-                ParsedGenerics {
-                    life_use: tr_life_use,
-                    gen_use: tr_gen_use,
-                    ..
-                },
-            ..
-        } in iter
-        {
             if let Some((ext_path, tr_info)) = self.ext_traits.get(raw_ident) {
                 let mut impls = TokenStream::new();
 
@@ -1579,9 +2675,15 @@ impl TraitGroup {
                     func.int_trait_impl(Some(ext_path), &ext_name, &mut impls);
                 }
 
+                let (slot_declare, slot_use) = if variant {
+                    self.variant_slots(Some(info))
+                } else {
+                    Default::default()
+                };
+
                 let gen = quote! {
-                    impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #gen_use>
-                        #path #raw_ident <#tr_life_use #tr_gen_use> for #self_ident<'cglue_a, CGlueInst, CGlueCtx, #gen_use>
+                    impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #slot_declare #gen_alias_declare>
+                        #path #raw_ident <#tr_life_use #tr_gen_use> for #self_ident<'cglue_a, CGlueInst, CGlueCtx, #slot_use #gen_use>
                     where
                         #cont_name<CGlueInst, CGlueCtx, #gen_use>: #crate_path::trait_group::CGlueObjBase,
                         Self: #ext_path #ext_name<#tr_life_use #tr_gen_use>
@@ -1719,187 +2821,488 @@ impl TraitGroup {
         ret
     }
 
-    /// Mixed vtable definitoins.
+    /// Generate a `GroupObj` implementation for a specific cglue object, which provides it with
+    /// `GetContainer`.
+    ///
+    /// `variant` specifies whether the object is generic over vtable slots.
+    fn group_obj_impl(
+        &self,
+        name: &Ident,
+        trg_path: &TokenStream,
+        all_generics: &ParsedGenerics,
+        variant: bool,
+    ) -> TokenStream {
+        let cont_name = &self.cont_name;
+
+        let ParsedGenerics {
+            gen_declare,
+            gen_use,
+            gen_where_bounds,
+            ..
+        } = &all_generics;
+
+        let ctx_bound = super::traits::ctx_bound();
+
+        let (slot_declare, slot_use) = if variant {
+            self.variant_slots(None)
+        } else {
+            Default::default()
+        };
+
+        quote! {
+            // Safety: the rest of the object's fields are vtable references.
+            unsafe impl<CGlueInst: #trg_path::InstanceBounds, CGlueCtx: #ctx_bound, #slot_declare #gen_declare>
+                #trg_path::GroupObj for #name<'_, CGlueInst, CGlueCtx, #slot_use #gen_use>
+            where
+                #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
+                #gen_where_bounds
+            {
+                type ContType = #cont_name<CGlueInst, CGlueCtx, #gen_use>;
+
+                fn cglue_cont(&self) -> &Self::ContType {
+                    &self.container
+                }
+
+                fn cglue_cont_mut(&mut self) -> &mut Self::ContType {
+                    &mut self.container
+                }
+            }
+        }
+    }
+
+    fn cglue_obj_impl(&self, trg_path: &TokenStream, all_generics: &ParsedGenerics) -> TokenStream {
+        let cont_name = &self.cont_name;
+
+        let ParsedGenerics {
+            gen_declare: all_gen_declare,
+            gen_use: all_gen_use,
+            gen_where_bounds: all_gen_where_bounds,
+            ..
+        } = &all_generics;
+
+        let ctx_bound = super::traits::ctx_bound();
+
+        let mut ret = quote! {
+            impl<CGlueInst: #trg_path::InstanceBounds, CGlueCtx: #ctx_bound, #all_gen_declare> #trg_path::CGlueObjBase
+                for #cont_name<CGlueInst, CGlueCtx, #all_gen_use>
+            where
+                CGlueInst::Target: Sized,
+                #all_gen_where_bounds
+            {
+                type ObjType = CGlueInst::Target;
+                type InstType = CGlueInst;
+                type Context = CGlueCtx;
+
+                fn cobj_base_ref(&self) -> (&Self::ObjType, &Self::Context) {
+                    (self.instance.deref(), &self.context)
+                }
+
+                fn cobj_base_owned(self) -> (Self::InstType, Self::Context) {
+                    (self.instance, self.context)
+                }
+            }
+
+            impl<CGlueInst: #trg_path::InstanceBounds, CGlueCtx: #ctx_bound, #all_gen_declare> #trg_path::CGlueObjInst
+                for #cont_name<CGlueInst, CGlueCtx, #all_gen_use>
+            where
+                CGlueInst::Target: Sized,
+                #all_gen_where_bounds
+            {
+                fn cobj_inst(&self) -> (&Self::InstType, &Self::Context) {
+                    (&self.instance, &self.context)
+                }
+            }
+        };
+
+        for TraitInfo {
+            path,
+            ret_tmp_typename,
+            ret_tmp_name,
+            generics: ParsedGenerics { gen_use, .. },
+            assocs: ParsedGenerics {
+                gen_use: assoc_use, ..
+            },
+            ..
+        } in self.mandatory_vtbl.iter().chain(self.optional_vtbl.iter())
+        {
+            ret.extend(quote!{
+                impl<CGlueInst: #trg_path::InstanceBounds, CGlueCtx: #ctx_bound, #all_gen_declare>
+                    #trg_path::CGlueObjRef<#path #ret_tmp_typename<CGlueCtx, #gen_use #assoc_use>>
+                    for #cont_name<CGlueInst, CGlueCtx, #all_gen_use>
+                where
+                    CGlueInst::Target: Sized,
+                    #all_gen_where_bounds
+                {
+                    fn cobj_ref(&self) -> (&Self::ObjType, &#path #ret_tmp_typename<CGlueCtx, #gen_use #assoc_use>, &Self::Context) {
+                        (self.instance.deref(), &self.#ret_tmp_name, &self.context)
+                    }
+                }
+
+                impl<
+                        CGlueInst: #trg_path::InstanceBounds + ::core::ops::DerefMut,
+                        CGlueCtx: #ctx_bound,
+                        #all_gen_declare
+                    > #trg_path::CGlueObjMut<#path #ret_tmp_typename<CGlueCtx, #gen_use #assoc_use>>
+                    for #cont_name<CGlueInst, CGlueCtx, #all_gen_use>
+                where
+                    CGlueInst::Target: Sized,
+                    #all_gen_where_bounds
+                {
+                    fn cobj_mut(&mut self) -> (&mut Self::ObjType, &mut #path #ret_tmp_typename<CGlueCtx, #gen_use #assoc_use>, &Self::Context) {
+                        (
+                            self.instance.deref_mut(),
+                            &mut self.#ret_tmp_name,
+                            &self.context,
+                        )
+                    }
+                }
+            });
+        }
+
+        ret
+    }
+
+    /// Type of the vtable of a trait in the group.
+    fn vtbl_type(&self, info: &TraitInfo) -> TokenStream {
+        let TraitInfo {
+            path,
+            vtbl_typename,
+            generics: ParsedGenerics { gen_use, .. },
+            assocs: ParsedGenerics {
+                gen_use: assoc_use, ..
+            },
+            ..
+        } = info;
+
+        let cont_name = &self.cont_name;
+        let all_gen_use = &self.generics.gen_use;
+
+        quote!(#path #vtbl_typename<'cglue_a, #cont_name<CGlueInst, CGlueCtx, #all_gen_use>, #gen_use #assoc_use>)
+    }
+
+    /// Generic parameter of the variant's vtable slot.
+    fn slot_ident(idx: usize) -> Ident {
+        format_ident!("CGlueSlot{}", idx)
+    }
+
+    /// Generic vtable slots of the group variant.
+    ///
+    /// Returns slot parameter declarations, and their uses. Slot of the `fixed` trait is not
+    /// declared, and is set to the vtable reference instead.
+    fn variant_slots(&self, fixed: Option<&TraitInfo>) -> (TokenStream, TokenStream) {
+        let mut declare = TokenStream::new();
+        let mut uses = TokenStream::new();
+
+        for (i, info) in self.optional_vtbl.iter().enumerate() {
+            if Some(info) == fixed {
+                let vtbl_type = self.vtbl_type(info);
+                uses.extend(quote!(&'cglue_a #vtbl_type,));
+            } else {
+                let slot = Self::slot_ident(i);
+                declare.extend(quote!(#slot: Copy,));
+                uses.extend(quote!(#slot,));
+            }
+        }
+
+        (declare, uses)
+    }
+
+    /// Vtable slots of a concrete group variant.
     ///
-    /// This function goes through optional vtables, and mixes them between `Option`, and
-    /// non-`Option` types for the definitions.
+    /// Slots of the implemented traits are vtable references, while the rest are optional vtable
+    /// references, or `()` for final variants.
     ///
     /// # Arguments
     ///
-    /// * `iter` - iterator of required/mandatory types. These types will have non-`Option` type
-    /// assigned. It is crucial to have the same order of values!
-    fn mixed_opt_vtbl_defs<'a>(&'a self, iter: impl Iterator<Item = &'a TraitInfo>) -> TokenStream {
+    /// * `iter` - implemented optional traits. Must be ordered the same way!
+    /// * `is_final` - whether the variant is final.
+    fn variant_slot_list<'a>(
+        &'a self,
+        iter: impl Iterator<Item = &'a TraitInfo>,
+        is_final: bool,
+    ) -> TokenStream {
         let mut ret = TokenStream::new();
 
         let mut iter = iter.peekable();
 
-        let cont_name = &self.cont_name;
-
-        let all_gen_use = &self.generics.gen_use;
+        for info in &self.optional_vtbl {
+            let vtbl_type = self.vtbl_type(info);
 
-        for (
-            TraitInfo {
-                vtbl_name,
-                path,
-                vtbl_typename,
-                generics: ParsedGenerics { gen_use, .. },
-                assocs:
-                    ParsedGenerics {
-                        gen_use: assoc_use, ..
-                    },
-                ..
-            },
-            mandatory,
-        ) in self.optional_vtbl.iter().map(|v| {
-            if iter.peek() == Some(&v) {
+            if iter.peek() == Some(&info) {
                 iter.next();
-                (v, true)
+                ret.extend(quote!(&'cglue_a #vtbl_type,));
+            } else if is_final {
+                ret.extend(quote!((),));
             } else {
-                (v, false)
+                ret.extend(quote!(::core::option::Option<&'cglue_a #vtbl_type>,));
+            }
+        }
+
+        ret
+    }
+
+    /// Generic variant of the group, underlying all concrete, and final variants.
+    ///
+    /// The variant is generic over slots of the optional vtables, thus trait implementations for
+    /// it scale linearly with the number of traits, as opposed to every combination of them.
+    fn variant_impl(&self, trg_path: &TokenStream, crate_path: &TokenStream) -> TokenStream {
+        if self.optional_vtbl.is_empty() {
+            return quote!();
+        }
+
+        let name = &self.name;
+        let cont_name = &self.cont_name;
+        let variant_name = format_ident!("{}Variant", name);
+
+        let ctx_bound = super::traits::ctx_bound();
+
+        let ParsedGenerics {
+            gen_declare,
+            gen_use,
+            gen_where_bounds,
+            ..
+        } = &self.generics;
+
+        let gen_lt_bounds = self.generics.declare_lt_for_all(&quote!('cglue_a));
+        let gen_sabi_bounds = self.generics.declare_sabi_for_all(crate_path);
+
+        let gen_where_bounds_base = quote! {
+            #gen_where_bounds
+            #gen_lt_bounds
+        };
+
+        let gen_where_bounds = quote! {
+            #gen_where_bounds_base
+            #gen_sabi_bounds
+        };
+
+        #[cfg(feature = "layout_checks")]
+        let derive_layouts = quote!(#[derive(#crate_path::__sabi::StableAbi)]);
+        #[cfg(not(feature = "layout_checks"))]
+        let derive_layouts = quote!();
+
+        let (slot_declare, slot_use) = self.variant_slots(None);
+
+        let mandatory_vtbl_defs = self.mandatory_vtbl_defs(self.mandatory_vtbl.iter());
+        let mand_vtbl_list = self.vtbl_list(self.mandatory_vtbl.iter());
+        let full_opt_vtbl_list = self.vtbl_list(self.optional_vtbl.iter());
+
+        let mut slot_defs = TokenStream::new();
+        let mut slot_bounds = TokenStream::new();
+        let mut opt_slot_bounds = TokenStream::new();
+        let mut slot_from_opt = TokenStream::new();
+        let mut slot_fits = quote!(true);
+        let mut slot_some = TokenStream::new();
+        let mut slot_idents = TokenStream::new();
+        let mut slot_mask = quote!(0);
+
+        for (i, info) in self.optional_vtbl.iter().enumerate() {
+            let slot = Self::slot_ident(i);
+            let vtbl_name = &info.vtbl_name;
+            let vtbl_type = self.vtbl_type(info);
+
+            slot_idents.extend(quote!(#slot,));
+
+            if self.vtbl_table {
+                let bit = Self::table_bit(i);
+                slot_mask.extend(quote! {
+                    | if <#slot as #trg_path::VtblSlot<'cglue_a, #vtbl_type>>::REQUIRED { #bit } else { 0 }
+                });
             }
-        }) {
-            let def = match mandatory {
-                true => {
-                    quote!(#vtbl_name: &'cglue_a #path #vtbl_typename<'cglue_a, #cont_name<CGlueInst, CGlueCtx, #all_gen_use>, #gen_use #assoc_use>, )
+
+            slot_defs.extend(quote!(#vtbl_name: #slot,));
+            slot_bounds.extend(quote!(#slot: #trg_path::VtblSlot<'cglue_a, #vtbl_type>,));
+            opt_slot_bounds.extend(quote!(#slot: #trg_path::OptVtblSlot<'cglue_a, #vtbl_type>,));
+            slot_from_opt.extend(quote!(#slot::from_opt_vtbl(*#vtbl_name),));
+            slot_fits.extend(quote!(&& #slot::from_opt_vtbl(*#vtbl_name).is_some()));
+            slot_some.extend(quote!(Some(#vtbl_name),));
+        }
+
+        let variant_ty = quote!(#variant_name<'cglue_a, CGlueInst, CGlueCtx, #slot_use #gen_use>);
+        let group_ty = quote!(#name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>);
+
+        // Variants of groups with a shared table only tell which of the vtables are available.
+        let (variant_fields, variant_mask, from_group_body, fits_body) = if self.vtbl_table {
+            let table_name = self.table_name();
+
+            let fields = quote! {
+                vtbls: &'cglue_a #table_name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>,
+                container: #cont_name<CGlueInst, CGlueCtx, #gen_use>,
+                _slots: ::core::marker::PhantomData<(#slot_idents)>,
+            };
+
+            let mask = quote! {
+                impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #slot_declare #gen_declare> #variant_ty
+                where
+                    #cont_name<CGlueInst, CGlueCtx, #gen_use>: 'cglue_a + #trg_path::CGlueObjBase,
+                    #slot_bounds
+                    #gen_where_bounds
+                {
+                    /// Mask of optional vtables required by the variant.
+                    const CGLUE_MASK: u64 = #slot_mask;
                 }
-                false => {
-                    quote!(#vtbl_name: ::core::option::Option<&'cglue_a #path #vtbl_typename<'cglue_a, #cont_name<CGlueInst, CGlueCtx, #all_gen_use>, #gen_use #assoc_use>>, )
+            };
+
+            let from_group = quote! {
+                if group.vtbls.mask & Self::CGLUE_MASK != Self::CGLUE_MASK {
+                    return Err(group);
                 }
+
+                let #name { vtbls, container } = group;
+
+                Ok(Self {
+                    vtbls,
+                    container,
+                    _slots: ::core::marker::PhantomData,
+                })
+            };
+
+            let fits = quote! {
+                group.vtbls.mask & Self::CGLUE_MASK == Self::CGLUE_MASK
+            };
+
+            (fields, mask, from_group, fits)
+        } else {
+            let fields = quote! {
+                #mandatory_vtbl_defs
+                #slot_defs
+                container: #cont_name<CGlueInst, CGlueCtx, #gen_use>,
+                _lt_cglue_a: ::core::marker::PhantomData<&'cglue_a ()>,
+            };
+
+            let from_group = quote! {
+                let #name {
+                    #full_opt_vtbl_list
+                    ..
+                } = &group;
+
+                match (#slot_from_opt) {
+                    (#slot_some) => {
+                        let #name {
+                            container,
+                            #mand_vtbl_list
+                            ..
+                        } = group;
+
+                        Ok(Self {
+                            container,
+                            #mand_vtbl_list
+                            #full_opt_vtbl_list
+                            _lt_cglue_a: ::core::marker::PhantomData,
+                        })
+                    }
+                    _ => Err(group),
+                }
+            };
+
+            let fits = quote! {
+                let #name {
+                    #full_opt_vtbl_list
+                    ..
+                } = group;
+
+                #slot_fits
             };
-            ret.extend(def);
-        }
 
-        ret
-    }
+            (fields, quote!(), from_group, fits)
+        };
 
-    /// Generate a `GetContainer` implementation for a specific cglue object.
-    fn get_container_impl(
-        &self,
-        name: &Ident,
-        trg_path: &TokenStream,
-        all_generics: &ParsedGenerics,
-    ) -> TokenStream {
-        let cont_name = &self.cont_name;
+        let group_obj_impl = self.group_obj_impl(&variant_name, trg_path, &self.generics, true);
 
-        let ParsedGenerics {
-            gen_declare,
-            gen_use,
-            gen_where_bounds,
-            ..
-        } = &all_generics;
+        let all_traits = || self.mandatory_vtbl.iter().chain(self.optional_vtbl.iter());
 
-        let ctx_bound = super::traits::ctx_bound();
+        let as_ref_impls =
+            self.as_ref_impls(&variant_name, all_traits(), &self.generics, trg_path, true);
+
+        let internal_trait_impls = self.internal_trait_impls(
+            &variant_name,
+            all_traits(),
+            &self.generics,
+            crate_path,
+            true,
+        );
+
+        let variant_doc = format!(
+            " Variant of {} with a subset of optional traits being implemented.",
+            name
+        );
 
         quote! {
-            impl<CGlueInst: #trg_path::InstanceBounds, CGlueCtx: #ctx_bound, #gen_declare>
-                #trg_path::GetContainer for #name<'_, CGlueInst, CGlueCtx, #gen_use>
+            #[doc = #variant_doc]
+            ///
+            /// Every optional trait has a vtable slot, that is either a vtable reference, an
+            /// optional vtable reference, or `()`, if the trait is not exposed. Concrete variants
+            /// are available as type aliases.
+            #[repr(C)]
+            #derive_layouts
+            pub struct #variant_name<'cglue_a, CGlueInst: 'cglue_a, CGlueCtx: #ctx_bound, #slot_declare #gen_declare>
             where
                 #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
-                #gen_where_bounds
+                #gen_where_bounds_base
             {
-                type ContType = #cont_name<CGlueInst, CGlueCtx, #gen_use>;
-
-                fn ccont_ref(&self) -> &Self::ContType {
-                    &self.container
-                }
+                #variant_fields
+            }
 
-                fn ccont_mut(&mut self) -> &mut Self::ContType {
-                    &mut self.container
-                }
+            #variant_mask
 
-                fn into_ccont(self) -> Self::ContType {
-                    self.container
+            impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #slot_declare #gen_declare>
+                #trg_path::GroupVariant<#group_ty> for #variant_ty
+            where
+                #cont_name<CGlueInst, CGlueCtx, #gen_use>: 'cglue_a + #trg_path::CGlueObjBase,
+                #slot_bounds
+                #gen_where_bounds
+            {
+                fn cglue_from_group(group: #group_ty) -> ::core::result::Result<Self, #group_ty> {
+                    #from_group_body
                 }
+            }
 
-                fn build_with_ccont(&self, container: Self::ContType) -> Self {
-                    Self {
-                        container,
-                        ..*self
-                    }
+            // Safety:
+            //
+            // Structure layouts are fully compatible, and the variant only fits the group, if
+            // all of its required vtables are present.
+            unsafe impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #slot_declare #gen_declare>
+                #trg_path::GroupVariantRef<#group_ty> for #variant_ty
+            where
+                #cont_name<CGlueInst, CGlueCtx, #gen_use>: 'cglue_a + #trg_path::CGlueObjBase,
+                #opt_slot_bounds
+                #gen_where_bounds
+            {
+                fn cglue_fits(group: &#group_ty) -> bool {
+                    #fits_body
                 }
             }
-        }
-    }
-
-    fn cglue_obj_impl(&self, trg_path: &TokenStream, all_generics: &ParsedGenerics) -> TokenStream {
-        let cont_name = &self.cont_name;
-
-        let ParsedGenerics {
-            gen_declare: all_gen_declare,
-            gen_use: all_gen_use,
-            gen_where_bounds: all_gen_where_bounds,
-            ..
-        } = &all_generics;
-
-        let ctx_bound = super::traits::ctx_bound();
 
-        let mut ret = quote! {
-            impl<CGlueInst: #trg_path::InstanceBounds, CGlueCtx: #ctx_bound, #all_gen_declare> #trg_path::CGlueObjBase
-                for #cont_name<CGlueInst, CGlueCtx, #all_gen_use>
+            impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #slot_declare #gen_declare> #variant_ty
             where
-                CGlueInst::Target: Sized,
-                #all_gen_where_bounds
+                #cont_name<CGlueInst, CGlueCtx, #gen_use>: 'cglue_a + #trg_path::CGlueObjBase,
+                #opt_slot_bounds
+                #gen_where_bounds
             {
-                type ObjType = CGlueInst::Target;
-                type InstType = CGlueInst;
-                type Context = CGlueCtx;
-
-                fn cobj_base_ref(&self) -> (&Self::ObjType, &Self::Context) {
-                    (self.instance.deref(), &self.context)
+                /// Cast back into the original group
+                pub fn upcast(self) -> #group_ty {
+                    #trg_path::GroupVariantRef::cglue_into_group(self)
                 }
+            }
 
-                fn cobj_base_owned(self) -> (Self::InstType, Self::Context) {
-                    (self.instance, self.context)
+            impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #slot_declare #gen_declare>
+                From<#variant_ty> for #group_ty
+            where
+                #cont_name<CGlueInst, CGlueCtx, #gen_use>: 'cglue_a + #trg_path::CGlueObjBase,
+                #opt_slot_bounds
+                #gen_where_bounds
+            {
+                fn from(input: #variant_ty) -> Self {
+                    input.upcast()
                 }
             }
-        };
 
-        for TraitInfo {
-            path,
-            ret_tmp_typename,
-            ret_tmp_name,
-            generics: ParsedGenerics { gen_use, .. },
-            assocs: ParsedGenerics {
-                gen_use: assoc_use, ..
-            },
-            ..
-        } in self.mandatory_vtbl.iter().chain(self.optional_vtbl.iter())
-        {
-            ret.extend(quote!{
-                impl<CGlueInst: #trg_path::InstanceBounds, CGlueCtx: #ctx_bound, #all_gen_declare>
-                    #trg_path::CGlueObjRef<#path #ret_tmp_typename<CGlueCtx, #gen_use #assoc_use>>
-                    for #cont_name<CGlueInst, CGlueCtx, #all_gen_use>
-                where
-                    CGlueInst::Target: Sized,
-                    #all_gen_where_bounds
-                {
-                    fn cobj_ref(&self) -> (&Self::ObjType, &#path #ret_tmp_typename<CGlueCtx, #gen_use #assoc_use>, &Self::Context) {
-                        (self.instance.deref(), &self.#ret_tmp_name, &self.context)
-                    }
-                }
+            #group_obj_impl
 
-                impl<
-                        CGlueInst: #trg_path::InstanceBounds + ::core::ops::DerefMut,
-                        CGlueCtx: #ctx_bound,
-                        #all_gen_declare
-                    > #trg_path::CGlueObjMut<#path #ret_tmp_typename<CGlueCtx, #gen_use #assoc_use>>
-                    for #cont_name<CGlueInst, CGlueCtx, #all_gen_use>
-                where
-                    CGlueInst::Target: Sized,
-                    #all_gen_where_bounds
-                {
-                    fn cobj_mut(&mut self) -> (&mut Self::ObjType, &mut #path #ret_tmp_typename<CGlueCtx, #gen_use #assoc_use>, &Self::Context) {
-                        (
-                            self.instance.deref_mut(),
-                            &mut self.#ret_tmp_name,
-                            &self.context,
-                        )
-                    }
-                }
-            });
-        }
+            #as_ref_impls
 
-        ret
+            #internal_trait_impls
+        }
     }
 
     /// `GetVtbl<Vtable>`, `CGlueObjRef<RetTmp>`, `CGlueObjOwned<RetTmp>`, `CGlueObjBuild<RetTmp>`, and `CGlueObjMut<T, RetTmp>` implementations for mandatory vtables.
@@ -1909,6 +3312,7 @@ impl TraitGroup {
             self.mandatory_vtbl.iter(),
             &self.generics,
             trg_path,
+            false,
         )
     }
 
@@ -1918,12 +3322,14 @@ impl TraitGroup {
     ///
     /// * `name` - type name to implement the conversion for.
     /// * `traits` - vtable types to implement the conversion to.
+    /// * `variant` - whether the type is generic over vtable slots, like the group variant is.
     fn as_ref_impls<'a>(
         &'a self,
         name: &Ident,
         traits: impl Iterator<Item = &'a TraitInfo>,
         all_generics: &ParsedGenerics,
         trg_path: &TokenStream,
+        variant: bool,
     ) -> TokenStream {
         let mut ret = TokenStream::new();
 
@@ -1935,48 +3341,70 @@ impl TraitGroup {
 
         let ctx_bound = super::traits::ctx_bound();
 
-        for TraitInfo {
-            vtbl_name,
-            path,
-            vtbl_typename,
-            vtbl_get_ident,
-            assoc_bind_ident,
-            generics: ParsedGenerics { gen_use, .. },
-            assocs: ParsedGenerics {
-                gen_use: assoc_use, ..
-            },
-            ..
-        } in traits
-        {
+        for info in traits {
+            let TraitInfo {
+                vtbl_name,
+                path,
+                vtbl_typename,
+                vtbl_get_ident,
+                assoc_bind_ident,
+                generics: ParsedGenerics { gen_use, .. },
+                assocs:
+                    ParsedGenerics {
+                        gen_use: assoc_use, ..
+                    },
+                ..
+            } = info;
+
+            let (slot_declare, slot_use) = if variant {
+                self.variant_slots(Some(info))
+            } else {
+                Default::default()
+            };
+
+            let vtbl_ref = match (self.vtbl_table, self.optional_vtbl.contains(info)) {
+                (false, _) => quote!(&self.#vtbl_name),
+                (true, false) => quote!(self.vtbls.#vtbl_name),
+                // Safety:
+                //
+                // Variants are only built when the table has all of their vtables.
+                (true, true) => quote! {
+                    match self.vtbls.#vtbl_name {
+                        ::core::option::Option::Some(vtbl) => vtbl,
+                        ::core::option::Option::None => unsafe { ::core::hint::unreachable_unchecked() },
+                    }
+                },
+            };
+
             ret.extend(quote! {
 
                 // TODO: bring back CGlueObjBuild
 
-                impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #all_gen_declare> #trg_path::GetVtblBase<#path #vtbl_typename<'cglue_a, #cont_name<CGlueInst, CGlueCtx, #all_gen_use>, #gen_use #assoc_use>>
-                    for #name<'cglue_a, CGlueInst, CGlueCtx, #all_gen_use>
+                impl<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #slot_declare #all_gen_declare> #trg_path::GetVtblBase<#path #vtbl_typename<'cglue_a, #cont_name<CGlueInst, CGlueCtx, #all_gen_use>, #gen_use #assoc_use>>
+                    for #name<'cglue_a, CGlueInst, CGlueCtx, #slot_use #all_gen_use>
                 where
                     #cont_name<CGlueInst, CGlueCtx, #all_gen_use>: #trg_path::CGlueObjBase,
                     #all_gen_where_bounds
                 {
                     fn get_vtbl_base(&self) -> &#path #vtbl_typename<'cglue_a, #cont_name<CGlueInst, CGlueCtx, #all_gen_use>, #gen_use #assoc_use> {
-                        &self.#vtbl_name
+                        #vtbl_ref
                     }
                 }
 
-                impl<'cglue_a, CGlueInst: #trg_path::InstanceBounds, CGlueCtx: #ctx_bound, #all_gen_declare> #path #vtbl_get_ident<'cglue_a, #gen_use #assoc_use>
-                    for #name<'cglue_a, CGlueInst, CGlueCtx, #all_gen_use>
+                impl<'cglue_a, CGlueInst: #trg_path::InstanceBounds, CGlueCtx: #ctx_bound, #slot_declare #all_gen_declare> #path #vtbl_get_ident<'cglue_a, #gen_use #assoc_use>
+                    for #name<'cglue_a, CGlueInst, CGlueCtx, #slot_use #all_gen_use>
                 where
                     <CGlueInst as ::core::ops::Deref>::Target: Sized,
                     #cont_name<CGlueInst, CGlueCtx, #all_gen_use>: #trg_path::CGlueObjBase,
                     #all_gen_where_bounds
                 {
                     fn get_vtbl(&self) -> &#path #vtbl_typename<'cglue_a, #cont_name<CGlueInst, CGlueCtx, #all_gen_use>, #gen_use #assoc_use> {
-                        &self.#vtbl_name
+                        #vtbl_ref
                     }
                 }
 
-                impl<'cglue_a, CGlueInst: #trg_path::InstanceBounds, CGlueCtx: #ctx_bound, #all_gen_declare> #path #assoc_bind_ident<#gen_use>
-                    for #name<'cglue_a, CGlueInst, CGlueCtx, #all_gen_use>
+                impl<'cglue_a, CGlueInst: #trg_path::InstanceBounds, CGlueCtx: #ctx_bound, #slot_declare #all_gen_declare> #path #assoc_bind_ident<#gen_use>
+                    for #name<'cglue_a, CGlueInst, CGlueCtx, #slot_use #all_gen_use>
                 where
                     <CGlueInst as ::core::ops::Deref>::Target: Sized,
                     #cont_name<CGlueInst, CGlueCtx, #all_gen_use>: #trg_path::CGlueObjBase,
@@ -1994,8 +3422,10 @@ impl TraitGroup {
     fn mandatory_vtbl_defaults(&self) -> TokenStream {
         let mut ret = TokenStream::new();
 
+        let crate_path = crate_path();
+
         for TraitInfo { vtbl_name, .. } in &self.mandatory_vtbl {
-            ret.extend(quote!(#vtbl_name: Default::default(),));
+            ret.extend(quote!(#vtbl_name: #crate_path::trait_group::StaticVtbl::CGLUE_VTBL,));
         }
 
         ret
@@ -2061,74 +3491,203 @@ impl TraitGroup {
         ret
     }
 
-    /// Try-unwrapping assignment list `vtbl: vtbl?, `.
-    ///
-    /// # Arguments
-    ///
-    /// * `iter` - vtable identifiers to list and try-unwrap.
-    fn vtbl_unwrap_list<'a>(&'a self, iter: impl Iterator<Item = &'a TraitInfo>) -> TokenStream {
-        let mut ret = TokenStream::new();
+    /// Bit of the optional vtable at index `idx` in the mask of the shared table.
+    fn table_bit(idx: usize) -> proc_macro2::Literal {
+        proc_macro2::Literal::u64_suffixed(1 << idx)
+    }
 
-        for TraitInfo { vtbl_name, .. } in iter {
-            ret.extend(quote!(#vtbl_name: #vtbl_name?,));
-        }
+    /// Name of the shared vtable table, used by `#[vtbl_table]` groups.
+    fn table_name(&self) -> Ident {
+        format_ident!("{}VtblTable", self.name)
+    }
 
-        ret
+    /// Name of the trait providing shared tables of types, used by `#[vtbl_table]` groups.
+    fn table_filler_name(&self) -> Ident {
+        format_ident!("{}VtblTableFiller", self.name)
     }
 
-    /// Mixed try-unwrap list for vtables.
+    /// Vtable fields of the group object.
     ///
-    /// This function goes through optional vtables, unwraps the ones in `iter`, leaves others
-    /// bare.
-    ///
-    /// # Arguments
+    /// Objects either hold all vtables inline, or point to a shared table of them.
+    fn group_vtbl_defs(&self) -> TokenStream {
+        if self.vtbl_table {
+            let table_name = self.table_name();
+            let gen_use = &self.generics.gen_use;
+            quote!(vtbls: &'cglue_a #table_name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>,)
+        } else {
+            let mandatory_vtbl_defs = self.mandatory_vtbl_defs(self.mandatory_vtbl.iter());
+            let optional_vtbl_defs = self.optional_vtbl_defs(quote!(CGlueInst), quote!(CGlueCtx));
+            quote!(#mandatory_vtbl_defs #optional_vtbl_defs)
+        }
+    }
+
+    /// Initialize vtable fields of the group object from local bindings of all of its vtables.
     ///
-    /// * `iter` - list of vtables to try-unwrap. Must be ordered the same way!
-    fn mixed_opt_vtbl_unwrap_list<'a>(
-        &'a self,
-        iter: impl Iterator<Item = &'a TraitInfo>,
-    ) -> TokenStream {
-        let mut ret = TokenStream::new();
+    /// Shared tables are built at compile time instead, and taken from the type of the instance.
+    fn group_vtbl_init(&self) -> TokenStream {
+        let mand_vtbl_list = self.vtbl_list(self.mandatory_vtbl.iter());
+        let full_opt_vtbl_list = self.vtbl_list(self.optional_vtbl.iter());
 
-        let mut iter = iter.peekable();
+        if self.vtbl_table {
+            let table_filler = self.table_filler_name();
+            let gen_use = &self.generics.gen_use;
+            quote!(vtbls: <CGlueInst::Target as #table_filler<'cglue_a, CGlueInst, CGlueCtx, #gen_use>>::CGLUE_TABLE,)
+        } else {
+            quote!(#mand_vtbl_list #full_opt_vtbl_list)
+        }
+    }
 
-        for (TraitInfo { vtbl_name, .. }, mandatory) in self.optional_vtbl.iter().map(|v| {
-            if iter.peek() == Some(&v) {
-                iter.next();
-                (v, true)
-            } else {
-                (v, false)
+    /// Bind the container and all vtables of the group object `obj` to local variables.
+    fn group_vtbl_bind(&self, obj: TokenStream) -> TokenStream {
+        let name = &self.name;
+        let mand_vtbl_list = self.vtbl_list(self.mandatory_vtbl.iter());
+        let full_opt_vtbl_list = self.vtbl_list(self.optional_vtbl.iter());
+
+        if self.vtbl_table {
+            let table_name = self.table_name();
+            quote! {
+                let #name { container, vtbls } = #obj;
+                let &#table_name { #mand_vtbl_list #full_opt_vtbl_list .. } = vtbls;
+            }
+        } else {
+            quote! {
+                let #name {
+                    container,
+                    #mand_vtbl_list
+                    #full_opt_vtbl_list
+                } = #obj;
             }
-        }) {
-            let def = match mandatory {
-                true => quote!(#vtbl_name: #vtbl_name?, ),
-                false => quote!(#vtbl_name, ),
-            };
-            ret.extend(def);
         }
+    }
 
-        ret
+    /// Access vtable field of the group object `obj`.
+    fn vtbl_field(&self, obj: TokenStream, vtbl_name: &Ident) -> TokenStream {
+        if self.vtbl_table {
+            quote!(#obj.vtbls.#vtbl_name)
+        } else {
+            quote!(#obj.#vtbl_name)
+        }
     }
 
-    /// Try-unwrap a list of vtables without assigning them (`vtbl?,`).
-    ///
-    /// # Arguments
+    /// Shared vtable table definition, if the group is marked with `#[vtbl_table]`.
     ///
-    /// * `iter` - vtables to unwrap.
-    fn vtbl_unwrap_validate<'a>(
-        &'a self,
-        iter: impl Iterator<Item = &'a TraitInfo>,
-    ) -> TokenStream {
-        let mut ret = TokenStream::new();
+    /// The table holds all vtables of the group, and a mask of the available optional ones. Every
+    /// type filling the group's vtables gets its table as a constant of the table filler trait.
+    fn vtbl_table_impl(&self, trg_path: &TokenStream, crate_path: &TokenStream) -> TokenStream {
+        if !self.vtbl_table {
+            return quote!();
+        }
 
-        for TraitInfo { vtbl_name, .. } in iter {
-            ret.extend(quote!((*#vtbl_name)?,));
+        let name = &self.name;
+        let cont_name = &self.cont_name;
+        let table_name = self.table_name();
+        let ctx_bound = super::traits::ctx_bound();
+
+        let ParsedGenerics {
+            gen_declare,
+            gen_use,
+            gen_where_bounds,
+            ..
+        } = &self.generics;
+
+        let gen_lt_bounds = self.generics.declare_lt_for_all(&quote!('cglue_a));
+        let gen_sabi_bounds = self.generics.declare_sabi_for_all(crate_path);
+
+        #[cfg(feature = "layout_checks")]
+        let derive_layouts = quote!(#[derive(#crate_path::__sabi::StableAbi)]);
+        #[cfg(not(feature = "layout_checks"))]
+        let derive_layouts = quote!();
+
+        let mandatory_vtbl_defs = self.mandatory_vtbl_defs(self.mandatory_vtbl.iter());
+        let optional_vtbl_defs = self.optional_vtbl_defs(quote!(CGlueInst), quote!(CGlueCtx));
+
+        let cglue_a_lifetime = Lifetime {
+            apostrophe: proc_macro2::Span::call_site(),
+            ident: format_ident!("cglue_a"),
+        };
+
+        let vtbl_where_bounds = Self::vtbl_where_bounds(
+            self.mandatory_vtbl.iter(),
+            cont_name,
+            quote!(CGlueInst),
+            quote!(CGlueCtx),
+            &self.generics,
+            None,
+            &cglue_a_lifetime,
+        );
+
+        let mut mask = quote!(0);
+        let mut vtbls = TokenStream::new();
+
+        for info in &self.mandatory_vtbl {
+            let vtbl_name = &info.vtbl_name;
+            vtbls.extend(quote!(#vtbl_name: #trg_path::StaticVtbl::CGLUE_VTBL,));
         }
 
-        ret
+        for (i, info) in self.optional_vtbl.iter().enumerate() {
+            let vtbl_name = &info.vtbl_name;
+            let vtbl_const_name = &info.vtbl_const_name;
+            let bit = Self::table_bit(i);
+            mask.extend(quote!(| if CGlueT::#vtbl_const_name.is_some() { #bit } else { 0 }));
+            vtbls.extend(quote!(#vtbl_name: CGlueT::#vtbl_const_name,));
+        }
+
+        let filler_trait = format_ident!("{}VtableFiller", name);
+        let table_filler = self.table_filler_name();
+
+        let table_doc = format!(" Shared table of vtables of {}.", name);
+
+        quote! {
+            #[repr(C)]
+            #[doc = #table_doc]
+            ///
+            /// One table is shared by all objects of the same type.
+            #derive_layouts
+            pub struct #table_name<'cglue_a, CGlueInst: 'cglue_a, CGlueCtx: #ctx_bound, #gen_declare>
+            where
+                #cont_name<CGlueInst, CGlueCtx, #gen_use>: #trg_path::CGlueObjBase,
+                #gen_where_bounds
+                #gen_lt_bounds
+            {
+                mask: u64,
+                #mandatory_vtbl_defs
+                #optional_vtbl_defs
+            }
+
+            /// Shared table of vtables of a type.
+            ///
+            /// The table is built at compile time, out of the vtables the type fills in.
+            pub trait #table_filler<'cglue_a, CGlueInst, CGlueCtx: #ctx_bound, #gen_declare>:
+                #filler_trait<'cglue_a, CGlueInst, CGlueCtx, #gen_use>
+            where
+                #cont_name<CGlueInst, CGlueCtx, #gen_use>: 'cglue_a + #trg_path::CGlueObjBase,
+                #gen_where_bounds
+                #gen_lt_bounds
+                #gen_sabi_bounds
+            {
+                const CGLUE_TABLE: &'cglue_a #table_name<'cglue_a, CGlueInst, CGlueCtx, #gen_use>;
+            }
+
+            impl<'cglue_a, CGlueT, CGlueInst, CGlueCtx: #ctx_bound, #gen_declare>
+                #table_filler<'cglue_a, CGlueInst, CGlueCtx, #gen_use> for CGlueT
+            where
+                CGlueT: #filler_trait<'cglue_a, CGlueInst, CGlueCtx, #gen_use>,
+                #cont_name<CGlueInst, CGlueCtx, #gen_use>: 'cglue_a + #trg_path::CGlueObjBase,
+                #vtbl_where_bounds
+                #gen_where_bounds
+                #gen_lt_bounds
+                #gen_sabi_bounds
+            {
+                const CGLUE_TABLE: &'cglue_a #table_name<'cglue_a, CGlueInst, CGlueCtx, #gen_use> =
+                    &#table_name {
+                        mask: #mask,
+                        #vtbls
+                    };
+            }
+        }
     }
 
-    /// Bind `Default` to mandatory vtables.
+    /// Bind `StaticVtbl` to the given vtables.
     pub fn vtbl_where_bounds<'a>(
         iter: impl Iterator<Item = &'a TraitInfo>,
         cont_name: &Ident,
@@ -2140,6 +3699,7 @@ impl TraitGroup {
     ) -> TokenStream {
         let mut ret = TokenStream::new();
 
+        let crate_path = crate_path();
         let all_gen_use = &all_generics.gen_use;
 
         for TraitInfo {
@@ -2168,7 +3728,7 @@ impl TraitGroup {
                 ret.extend(quote!(#trait_bound: #path #raw_ident<#life_use #gen_use>,));
             }
 
-            ret.extend(quote!(&#vtbl_lifetime #path #vtbl_typename<#vtbl_lifetime, #cont_name<#container_ident, #ctx_ident, #all_gen_use>, #gen_use #assoc_use>: #vtbl_lifetime + Default,));
+            ret.extend(quote!(&#vtbl_lifetime #path #vtbl_typename<#vtbl_lifetime, #cont_name<#container_ident, #ctx_ident, #all_gen_use>, #gen_use #assoc_use>: #vtbl_lifetime + #crate_path::trait_group::StaticVtbl,));
         }
 
         ret
@@ -2,7 +2,7 @@ use proc_macro2::TokenStream;
 
 use std::collections::BTreeMap;
 
-use super::func::{AssocType, CustomFuncImpl, ParsedFunc, WrappedType};
+use super::func::{AssocType, CustomFuncImpl, ParsedFunc, ParsedStaticFunc, WrappedType};
 use super::generics::{GenericType, ParsedGenerics};
 
 use quote::*;
@@ -39,6 +39,87 @@ impl Parse for PathTokens {
     }
 }
 
+/// Associated constant of a trait.
+///
+/// Constants are stored within the vtable as data fields, named after the lowercase name of the
+/// constant, prefixed with `const_` to not collide with the trait's methods.
+pub struct ParsedConst {
+    /// Name of the constant.
+    pub ident: Ident,
+    /// Name of the vtable field, and the accessor function.
+    pub field: Ident,
+    /// Type of the constant, with `'static` lifetimes made explicit.
+    pub ty: Type,
+    /// Type of the constant within the vtable.
+    pub c_ty: TokenStream,
+    /// Conversion from `ty` to `c_ty`, usable in const context.
+    pub to_c: TokenStream,
+    /// Conversion of `ret` from `c_ty` back to `ty`.
+    pub from_c: TokenStream,
+    /// Whether the trait provides a default value.
+    pub has_default: bool,
+}
+
+impl ParsedConst {
+    pub fn new(c: &TraitItemConst, crate_path: &TokenStream) -> Self {
+        let ident = c.ident.clone();
+        let field = format_ident!("const_{}", ident.to_string().to_lowercase());
+        let has_default = c.default.is_some();
+
+        let passthrough = |ty: &Type| Self {
+            ident: ident.clone(),
+            field: field.clone(),
+            ty: ty.clone(),
+            c_ty: quote!(#ty),
+            to_c: quote!(ret),
+            from_c: quote!(ret),
+            has_default,
+        };
+
+        let r = match &c.ty {
+            Type::Reference(r) if r.mutability.is_none() => r,
+            ty => return passthrough(ty),
+        };
+
+        match &*r.elem {
+            Type::Path(p) if p.path.is_ident("str") => Self {
+                ty: parse_quote!(&'static str),
+                c_ty: quote!(#crate_path::slice::CSliceRef<'static, u8>),
+                to_c: quote!(#crate_path::slice::CSliceRef::from_str(ret)),
+                from_c: quote!(unsafe { ret.into_str() }),
+                ..passthrough(&c.ty)
+            },
+            Type::Slice(sl) => {
+                let elem = &sl.elem;
+                Self {
+                    ty: parse_quote!(&'static [#elem]),
+                    c_ty: quote!(#crate_path::slice::CSliceRef<'static, #elem>),
+                    to_c: quote!(#crate_path::slice::CSliceRef::from_slice(ret)),
+                    from_c: quote!(ret.into()),
+                    ..passthrough(&c.ty)
+                }
+            }
+            _ => passthrough(&c.ty),
+        }
+    }
+
+    /// Name of the lowered constant that is defined on the vtable.
+    pub fn lowered_ident(&self) -> Ident {
+        format_ident!("CGLUE_{}", self.ident)
+    }
+}
+
+/// Parse all associated constants of the trait.
+pub fn parse_consts(tr: &ItemTrait, crate_path: &TokenStream) -> Vec<ParsedConst> {
+    tr.items
+        .iter()
+        .filter_map(|item| match item {
+            TraitItem::Const(c) => Some(ParsedConst::new(c, crate_path)),
+            _ => None,
+        })
+        .collect()
+}
+
 // TODO: Add dynamic setting of Send / Sync
 pub fn ctx_bound() -> TokenStream {
     let crate_path = crate::util::crate_path();
@@ -520,6 +601,98 @@ pub fn process_item(
     }
 }
 
+fn unwind_abi(attrs: &[impl AsRef<str>]) -> Option<bool> {
+    if cfg!(__cglue_force_no_unwind_abi) || attrs.iter().any(|i| i.as_ref() == "no_unwind_abi") {
+        return Some(false);
+    }
+    if attrs.iter().any(|i| i.as_ref() == "unwind_abi") {
+        return Some(true);
+    }
+    None
+}
+
+/// Check whether the function is bounded by `where Self: Sized`.
+fn bounded_by_sized(sig: &Signature) -> bool {
+    sig.generics
+        .where_clause
+        .iter()
+        .flat_map(|w| w.predicates.iter())
+        .filter_map(|p| match p {
+            WherePredicate::Type(p) if p.bounded_ty == parse_quote!(Self) => Some(&p.bounds),
+            _ => None,
+        })
+        .flatten()
+        .any(|b| match b {
+            TypeParamBound::Trait(t) => {
+                matches!(t.modifier, TraitBoundModifier::None)
+                    && t.path.segments.last().map(|s| s.ident == "Sized") == Some(true)
+            }
+            _ => false,
+        })
+}
+
+fn attr_names(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .map(|a| a.path.to_token_stream().to_string())
+        .collect()
+}
+
+/// Parse all associated functions (ones without a receiver) of the trait.
+///
+/// `boxed` is the opaque boxed trait object type, alongside the conversion of `ret` into it.
+///
+/// Functions that can not be exposed through FFI are skipped, if they have a default
+/// implementation.
+pub fn parse_static_funcs(
+    tr: &ItemTrait,
+    crate_path: &TokenStream,
+    boxed: (&TokenStream, &TokenStream),
+) -> Vec<ParsedStaticFunc> {
+    let generics = ParsedGenerics::from(&tr.generics);
+    let global_unwind_abi = unwind_abi(&attr_names(&tr.attrs));
+
+    tr.items
+        .iter()
+        .filter_map(|item| match item {
+            TraitItem::Method(m) => Some(m),
+            _ => None,
+        })
+        .filter(|m| m.sig.receiver().is_none())
+        .filter_map(|m| {
+            let attrs = attr_names(&m.attrs);
+
+            if attrs.iter().any(|i| i == "skip_func") {
+                return None;
+            }
+
+            let func = ParsedStaticFunc::new(
+                m.sig.clone(),
+                tr.ident.clone(),
+                &generics,
+                crate_path,
+                boxed,
+                m.default.is_some(),
+                unwind_abi(&attrs)
+                    .or(global_unwind_abi)
+                    .unwrap_or(cfg!(feature = "unwind_abi_default")),
+            );
+
+            if func.is_none() && m.default.is_none() {
+                panic!("Associated function `{}` can not be exposed through FFI, and has no default implementation! This is not supported.", m.sig.ident);
+            }
+
+            if func.as_ref().map(ParsedStaticFunc::returns_self) == Some(true)
+                && !bounded_by_sized(&m.sig)
+            {
+                panic!("Associated function `{}::{}` returns `Self`, thus it must be bounded by `where Self: Sized`, and called through `{}Factory`!", tr.ident, m.sig.ident, tr.ident);
+            }
+
+            func
+        })
+        .collect()
+}
+
 pub fn parse_trait(
     tr: &ItemTrait,
     crate_path: &TokenStream,
@@ -592,23 +765,7 @@ pub fn parse_trait(
         })
         .next();
 
-    fn unwind_abi(attrs: &[impl AsRef<str>]) -> Option<bool> {
-        if cfg!(__cglue_force_no_unwind_abi) || attrs.iter().any(|i| i.as_ref() == "no_unwind_abi")
-        {
-            return Some(false);
-        }
-        if attrs.iter().any(|i| i.as_ref() == "unwind_abi") {
-            return Some(true);
-        }
-        None
-    }
-
-    let global_unwind_abi = unwind_abi(
-        &tr.attrs
-            .iter()
-            .map(|a| a.path.to_token_stream().to_string())
-            .collect::<Vec<_>>(),
-    );
+    let global_unwind_abi = unwind_abi(&attr_names(&tr.attrs));
 
     // Parse all functions in the trait
     for item in &tr.items {
@@ -629,11 +786,7 @@ pub fn parse_trait(
                 crate_path,
             ),
             TraitItem::Method(m) => {
-                let attrs = m
-                    .attrs
-                    .iter()
-                    .map(|a| a.path.to_token_stream().to_string())
-                    .collect::<Vec<_>>();
+                let attrs = attr_names(&m.attrs);
 
                 if attrs.iter().any(|i| i == "skip_func") {
                     continue;
@@ -796,10 +949,17 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
     let ret_tmp_ident_phantom = format_ident!("{}RetTmpPhantom", trait_name);
     let accessor_trait_ident = format_ident!("{}OpaqueObj", trait_name);
     let assoc_bind_ident = format_ident!("{}AssocBind", trait_name);
+    let factory_ident = format_ident!("{}Factory", trait_name);
 
     let base_box_trait_obj_ident = format_ident!("{}BaseBox", trait_name);
     let base_ctx_trait_obj_ident = format_ident!("{}BaseCtxBox", trait_name);
     let base_arc_trait_obj_ident = format_ident!("{}BaseArcBox", trait_name);
+    let base_unsend_trait_obj_ident = format_ident!("{}BaseUnsendBox", trait_name);
+    let base_ctx_unsend_trait_obj_ident = format_ident!("{}BaseCtxUnsendBox", trait_name);
+    let base_arc_unsend_trait_obj_ident = format_ident!("{}BaseArcUnsendBox", trait_name);
+    let base_inline_trait_obj_ident = format_ident!("{}BaseInline", trait_name);
+    let base_ctx_inline_trait_obj_ident = format_ident!("{}BaseCtxInline", trait_name);
+    let base_arc_inline_trait_obj_ident = format_ident!("{}BaseArcInline", trait_name);
     let base_mut_trait_obj_ident = format_ident!("{}BaseMut", trait_name);
     let base_ctx_mut_trait_obj_ident = format_ident!("{}BaseCtxMut", trait_name);
     let base_arc_mut_trait_obj_ident = format_ident!("{}BaseArcMut", trait_name);
@@ -811,6 +971,12 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
     let opaque_box_trait_obj_ident = format_ident!("{}Box", trait_name);
     let opaque_ctx_trait_obj_ident = format_ident!("{}CtxBox", trait_name);
     let opaque_arc_trait_obj_ident = format_ident!("{}ArcBox", trait_name);
+    let opaque_unsend_trait_obj_ident = format_ident!("{}UnsendBox", trait_name);
+    let opaque_ctx_unsend_trait_obj_ident = format_ident!("{}CtxUnsendBox", trait_name);
+    let opaque_arc_unsend_trait_obj_ident = format_ident!("{}ArcUnsendBox", trait_name);
+    let opaque_inline_trait_obj_ident = format_ident!("{}Inline", trait_name);
+    let opaque_ctx_inline_trait_obj_ident = format_ident!("{}CtxInline", trait_name);
+    let opaque_arc_inline_trait_obj_ident = format_ident!("{}ArcInline", trait_name);
     let opaque_mut_trait_obj_ident = format_ident!("{}Mut", trait_name);
     let opaque_ctx_mut_trait_obj_ident = format_ident!("{}CtxMut", trait_name);
     let opaque_arc_mut_trait_obj_ident = format_ident!("{}ArcMut", trait_name);
@@ -821,11 +987,45 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
     let (funcs, generics, (assocs, assoc_idents, assoc_equality), trait_type_defs) =
         parse_trait(&tr, &crate_path, true, process_item);
 
+    let ParsedGenerics {
+        gen_use: assoc_use, ..
+    } = &assocs;
+
+    let ParsedGenerics { gen_use, .. } = &generics;
+
+    // Associated functions return `Self` as an opaque boxed object.
+    let static_box_ty = quote!(#opaque_box_trait_obj_ident<'cglue_a, #gen_use #assoc_use>);
+    let static_base_box_ty =
+        quote!(#base_box_trait_obj_ident<'cglue_a, CGlueT, #gen_use #assoc_use>);
+    let static_box_conv = quote! {
+        #trg_path::Opaquable::into_opaque(
+            <#static_base_box_ty as ::core::convert::From<CGlueT>>::from(ret)
+        )
+    };
+    let static_box_bound = quote! {
+        CGlueT: Send,
+        #static_base_box_ty: ::core::convert::From<CGlueT> + #trg_path::Opaquable<OpaqueTarget = #static_box_ty>,
+    };
+
+    let static_funcs = parse_static_funcs(&tr, &crate_path, (&static_box_ty, &static_box_conv));
+
+    let consts = parse_consts(&tr, &crate_path);
+
     let cglue_c_opaque_bound = cglue_c_opaque_bound();
     let ctx_bound = ctx_bound();
 
     tr.ident = trait_impl_name.clone();
 
+    // Argument attributes are consumed by the parsing above.
+    for item in tr.items.iter_mut() {
+        if let TraitItem::Method(m) = item {
+            m.sig
+                .inputs
+                .iter_mut()
+                .for_each(super::func::strip_obj_arg_attr);
+        }
+    }
+
     let mut trait_type_bounds = TokenStream::new();
 
     let ParsedGenerics {
@@ -857,6 +1057,7 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
     };
 
     let gen_declare_stripped = generics.declare_without_nonstatic_bounds();
+    let gen_alias_declare = generics.alias_declare();
     let gen_lt_bounds = generics.declare_lt_for_all(&quote!('cglue_a));
     let gen_sabi_bounds = generics.declare_sabi_for_all(&crate_path);
     let assoc_sabi_bounds = assocs.declare_sabi_for_all(&crate_path);
@@ -900,6 +1101,85 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
         func.vtbl_default_def(&mut vtbl_default_funcs);
     }
 
+    // Associated constants, stored as data in the vtable
+    let mut vtbl_const_definitions = TokenStream::new();
+    let mut vtbl_const_lowering = TokenStream::new();
+    let mut vtbl_const_getters = TokenStream::new();
+    let mut trait_const_defs = TokenStream::new();
+
+    let life_static = life_use
+        .iter()
+        .map(|_| quote!('static,))
+        .collect::<TokenStream>();
+
+    for c in &consts {
+        let ParsedConst {
+            ident,
+            field,
+            ty,
+            c_ty,
+            to_c,
+            from_c,
+            has_default,
+        } = c;
+
+        let lowered = c.lowered_ident();
+
+        vtbl_const_definitions.extend(quote!(#field: #c_ty,));
+
+        vtbl_const_lowering.extend(quote! {
+            const #lowered: #c_ty = {
+                let ret = <CGlueC::ObjType as #trait_name<#life_static #gen_use>>::#ident;
+                #to_c
+            };
+        });
+
+        vtbl_default_funcs.extend(quote! {
+            #field: <#vtbl_ident<'cglue_a, CGlueC, #gen_use #assoc_use>>::#lowered,
+        });
+
+        let doc_text = format!(" Get `{}::{}` of the underlying type.", trait_name, ident);
+
+        vtbl_const_getters.extend(quote! {
+            #[doc = #doc_text]
+            fn #field(&self) -> #ty
+            where
+                <Self as #trg_path::GetContainer>::ContType: 'cglue_a,
+            {
+                let ret = self.get_vtbl().#field;
+                #from_c
+            }
+        });
+
+        if !has_default {
+            let msg = format!(
+                "`{}::{}` is not known at compile time for CGlue objects, use `{}` accessor instead",
+                trait_name, ident, field
+            );
+            trait_const_defs
+                .extend(quote!(const #ident: #ty = #crate_path::__cglue_const_panic!(#msg);));
+        }
+    }
+
+    let vtbl_const_lowering = if vtbl_const_lowering.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            impl<
+                'cglue_a,
+                CGlueC: #trg_path::CGlueObjBase,
+                #gen_declare_stripped
+                #assoc_declare_stripped
+            > #vtbl_ident<'cglue_a, CGlueC, #gen_use #assoc_use>
+            where
+                #gen_where_bounds
+                CGlueC::ObjType: for<#life_declare> #trait_name<#life_use #gen_use #assoc_equality>,
+            {
+                #vtbl_const_lowering
+            }
+        }
+    };
+
     // Define wrapped functions for the vtable
     let mut cfuncs = TokenStream::new();
 
@@ -951,17 +1231,23 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
     // TODO: clean this up
     let mut need_mut = false;
     let mut need_own = false;
+    let mut need_arc = false;
     let mut need_cgluef = false;
     let mut return_self = false;
 
     for func in &funcs {
-        let (nm, no, rs) = func.trait_impl(&mut trait_impl_fns);
+        let (nm, no, na, rs) = func.trait_impl(&mut trait_impl_fns);
         need_mut = nm || need_mut;
         need_own = no || need_own;
+        need_arc = na || need_arc;
         need_cgluef = !no || need_cgluef;
         return_self = rs || return_self;
     }
 
+    for func in &static_funcs {
+        func.trait_impl(&mut trait_impl_fns);
+    }
+
     let required_ctx = if need_mut {
         quote!(#trg_path::CGlueObjMut<#ret_tmp_ty, Context = CGlueCtx> + )
     } else {
@@ -976,6 +1262,15 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
         None
     };
 
+    let cglue_c_clone_arc = if need_arc {
+        Some(quote!(
+            CGlueC: #trg_path::CGlueObjInst,
+            CGlueC::InstType: #trg_path::CloneArc<ArcTarget = CGlueC::ObjType>,
+        ))
+    } else {
+        None
+    };
+
     let cglue_c_bounds = quote!(: #required_ctx 'cglue_a);
 
     // Add supertrait bounds here. Note that it probably won't work, unless they are one of the
@@ -1002,6 +1297,16 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
             func.int_trait_impl(None, ext_name, &mut impls);
         }
 
+        let ext_trait = quote!(#ext_name<#life_use #gen_use>);
+
+        for func in &static_funcs {
+            func.int_trait_impl(&ext_trait, &mut impls);
+        }
+
+        for ParsedConst { ident, ty, .. } in &consts {
+            impls.extend(quote!(const #ident: #ty = <Self as #ext_trait>::#ident;));
+        }
+
         quote! {
             #unsafety impl<#life_declare CGlueT, CGlueV, CGlueC, CGlueR, #gen_declare> #trait_name<#life_use #gen_use>
                 for #trg_path::CGlueTraitObj<'_, CGlueT, CGlueV, CGlueC, CGlueR>
@@ -1015,6 +1320,96 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
         quote!()
     };
 
+    // Factory table for associated functions
+    let (factory, factory_export) = if static_funcs.is_empty() {
+        (quote!(), quote!())
+    } else {
+        let mut factory_func_definitions = TokenStream::new();
+        let mut factory_funcs = TokenStream::new();
+        let mut factory_default_funcs = TokenStream::new();
+        let mut factory_cfuncs = TokenStream::new();
+
+        for func in &static_funcs {
+            func.factory_def(&mut factory_func_definitions);
+            func.factory_func_def(&mut factory_funcs);
+            func.factory_default_def(&mut factory_default_funcs, &assocs);
+            func.cfunc_def(
+                &mut factory_cfuncs,
+                &assocs,
+                &assoc_equality,
+                &static_box_bound,
+            );
+        }
+
+        let box_bound = if static_funcs.iter().any(ParsedStaticFunc::returns_self) {
+            Some(&static_box_bound)
+        } else {
+            None
+        };
+
+        let factory_doc = format!(" CGlue factory table for trait {}.", trait_name);
+
+        let phantom_data_definitions = generics.phantom_data_definitions();
+        let phantom_data_init = generics.phantom_data_init();
+        let assoc_phantom_data_definitions = assocs.phantom_data_definitions();
+        let assoc_phantom_data_init = assocs.phantom_data_init();
+
+        (
+            quote! {
+                /* Factory table for associated functions. */
+
+                #[doc = #factory_doc]
+                ///
+                /// This table contains ABI-safe wrappers for associated functions of the trait,
+                /// those that do not take `self`. It can be exported by a plugin to construct new
+                /// objects without needing an existing instance.
+                #[repr(C)]
+                #derive_layouts
+                pub struct #factory_ident<
+                    'cglue_a,
+                    #gen_declare_stripped
+                    #assoc_declare_stripped
+                >
+                where
+                    #gen_where_bounds_base_nolt
+                {
+                    #factory_func_definitions
+                    #phantom_data_definitions
+                    #assoc_phantom_data_definitions
+                    _lt_cglue_a: ::core::marker::PhantomData<&'cglue_a ()>,
+                }
+
+                impl<
+                    'cglue_a,
+                    #gen_declare_stripped
+                    #assoc_declare_stripped
+                > #factory_ident<'cglue_a, #gen_use #assoc_use>
+                where
+                    #gen_where_bounds
+                {
+                    /// Create a static factory table for the given type.
+                    pub fn of_type<CGlueT>() -> &'cglue_a Self
+                    where
+                        CGlueT: 'cglue_a + for<#life_declare> #trait_name<#life_use #gen_use #assoc_equality>,
+                        #box_bound
+                    {
+                        &#factory_ident {
+                            #factory_default_funcs
+                            #phantom_data_init
+                            #assoc_phantom_data_init
+                            _lt_cglue_a: ::core::marker::PhantomData,
+                        }
+                    }
+
+                    #factory_funcs
+                }
+
+                #factory_cfuncs
+            },
+            quote!(#factory_ident,),
+        )
+    };
+
     // Formatted documentation strings
     let vtbl_doc = format!(" CGlue vtable for trait {}.", trait_name);
 
@@ -1024,6 +1419,24 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
         trait_name
     );
     let base_arc_trait_obj_doc = format!(" Boxed CGlue trait object for trait {} with a [`CArc`](cglue::arc::CArc) reference counted context.", trait_name);
+    let base_unsend_trait_obj_doc = format!(
+        " Boxed CGlue trait object for trait {}, that can hold objects that are not `Send`.",
+        trait_name
+    );
+    let base_ctx_unsend_trait_obj_doc = format!(
+        " Boxed CGlue trait object for trait {} with a context, that can hold objects that are not `Send`.",
+        trait_name
+    );
+    let base_arc_unsend_trait_obj_doc = format!(" Boxed CGlue trait object for trait {} with a [`CArc`](cglue::arc::CArc) reference counted context, that can hold objects that are not `Send`.", trait_name);
+    let base_inline_trait_obj_doc = format!(
+        " Inline (small-buffer) CGlue trait object for trait {}.",
+        trait_name
+    );
+    let base_ctx_inline_trait_obj_doc = format!(
+        " Inline (small-buffer) CGlue trait object for trait {} with a context.",
+        trait_name
+    );
+    let base_arc_inline_trait_obj_doc = format!(" Inline (small-buffer) CGlue trait object for trait {} with a [`CArc`](cglue::arc::CArc) reference counted context.", trait_name);
     let base_mut_trait_obj_doc = format!(" By-mut CGlue trait object for trait {}.", trait_name);
     let base_ctx_mut_trait_obj_doc = format!(
         " By-mut CGlue trait object for trait {} with a context.",
@@ -1044,6 +1457,30 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
         trait_name
     );
     let opaque_arc_trait_obj_doc = format!(" Opaque Boxed CGlue trait object for trait {} with a [`CArc`](cglue::arc::CArc) reference counted context.", trait_name);
+    let opaque_unsend_trait_obj_doc = format!(
+        " Opaque Boxed CGlue trait object for trait {}, that is not `Send`.",
+        trait_name
+    );
+    let opaque_ctx_unsend_trait_obj_doc = format!(
+        " Opaque Boxed CGlue trait object for trait {} with a context, that is not `Send`.",
+        trait_name
+    );
+    let opaque_arc_unsend_trait_obj_doc = format!(
+        " Opaque Boxed CGlue trait object for trait {} with a [`CArc`](cglue::arc::CArc) reference counted context, that is not `Send`.",
+        trait_name
+    );
+    let opaque_inline_trait_obj_doc = format!(
+        " Opaque inline (small-buffer) CGlue trait object for trait {}.",
+        trait_name
+    );
+    let opaque_ctx_inline_trait_obj_doc = format!(
+        " Opaque inline (small-buffer) CGlue trait object for trait {} with a context.",
+        trait_name
+    );
+    let opaque_arc_inline_trait_obj_doc = format!(
+        " Opaque inline (small-buffer) CGlue trait object for trait {} with a [`CArc`](cglue::arc::CArc) reference counted context.",
+        trait_name
+    );
     let opaque_mut_trait_obj_doc = format!(
         " Opaque by-mut CGlue trait object for trait {}.",
         trait_name
@@ -1079,7 +1516,7 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
             /// directly. Use the trait functions.
             #[repr(C)]
             #derive_layouts
-            pub struct #ret_tmp_ident<CGlueCtx: #ctx_bound, #gen_use #assoc_use>
+            pub struct #ret_tmp_ident<CGlueCtx: #ctx_bound, #gen_alias_declare #assoc_use>
             {
                 #ret_tmp_type_defs
                 #phantom_data_definitions
@@ -1087,12 +1524,12 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
                 _ty_cglue_ctx: ::core::marker::PhantomData<CGlueCtx>,
             }
 
-            impl<CGlueCtx: #ctx_bound, #gen_use #assoc_use> #ret_tmp_ident<CGlueCtx, #gen_use #assoc_use>
+            impl<CGlueCtx: #ctx_bound, #gen_alias_declare #assoc_use> #ret_tmp_ident<CGlueCtx, #gen_use #assoc_use>
             {
                 #ret_tmp_getter_defs
             }
 
-            impl<CGlueCtx: #ctx_bound, #gen_use #assoc_use> Default for #ret_tmp_ident<CGlueCtx, #gen_use #assoc_use>
+            impl<CGlueCtx: #ctx_bound, #gen_alias_declare #assoc_use> Default for #ret_tmp_ident<CGlueCtx, #gen_use #assoc_use>
             {
                 fn default() -> Self {
                     Self {
@@ -1109,7 +1546,7 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
             /// Technically unused phantom data definition structure.
             #[repr(C)]
             #derive_layouts
-            pub struct #ret_tmp_ident_phantom<CGlueCtx: #ctx_bound, #gen_use #assoc_use>
+            pub struct #ret_tmp_ident_phantom<CGlueCtx: #ctx_bound, #gen_alias_declare #assoc_use>
             {
                 #phantom_data_definitions
                 #assoc_phantom_data_definitions
@@ -1126,7 +1563,7 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
             /// groups/objects. If C++11 templates are generated, it is important to define a
             /// custom type for CGlueTraitObj that does not have `ret_tmp` defined, and change all
             /// type aliases of this trait to use that particular structure.
-            pub type #ret_tmp_ident<CGlueCtx, #gen_use #assoc_use> = ::core::marker::PhantomData<#ret_tmp_ident_phantom<CGlueCtx, #gen_use #assoc_use>>;
+            pub type #ret_tmp_ident<CGlueCtx, #gen_alias_declare #assoc_use> = ::core::marker::PhantomData<#ret_tmp_ident_phantom<CGlueCtx, #gen_use #assoc_use>>;
         }
     };
 
@@ -1163,10 +1600,17 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
                 #ret_tmp_ident,
                 #accessor_trait_ident,
                 #assoc_bind_ident,
+                #factory_export
 
                 #base_box_trait_obj_ident,
                 #base_ctx_trait_obj_ident,
                 #base_arc_trait_obj_ident,
+                #base_unsend_trait_obj_ident,
+                #base_ctx_unsend_trait_obj_ident,
+                #base_arc_unsend_trait_obj_ident,
+                #base_inline_trait_obj_ident,
+                #base_ctx_inline_trait_obj_ident,
+                #base_arc_inline_trait_obj_ident,
                 #base_mut_trait_obj_ident,
                 #base_ctx_mut_trait_obj_ident,
                 #base_arc_mut_trait_obj_ident,
@@ -1178,6 +1622,12 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
                 #opaque_box_trait_obj_ident,
                 #opaque_ctx_trait_obj_ident,
                 #opaque_arc_trait_obj_ident,
+                #opaque_unsend_trait_obj_ident,
+                #opaque_ctx_unsend_trait_obj_ident,
+                #opaque_arc_unsend_trait_obj_ident,
+                #opaque_inline_trait_obj_ident,
+                #opaque_ctx_inline_trait_obj_ident,
+                #opaque_arc_inline_trait_obj_ident,
                 #opaque_mut_trait_obj_ident,
                 #opaque_ctx_mut_trait_obj_ident,
                 #opaque_arc_mut_trait_obj_ident,
@@ -1207,8 +1657,9 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
                 #gen_where_bounds_base_nolt
             {
                 #vtbl_func_definitions
+                #vtbl_const_definitions
                 #assoc_phantom_data_definitions
-                _lt_cglue_a: ::core::marker::PhantomData<&'cglue_a CGlueC>,
+                _lt_cglue_a: ::core::marker::PhantomData<#trg_path::CGlueContMarker<'cglue_a, CGlueC>>,
             }
 
             impl<
@@ -1223,26 +1674,42 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
                 #vtbl_getter_defintions
             }
 
+            #vtbl_const_lowering
+
             #ret_tmp
 
-            /* Default implementation. */
+            /* Static vtable. */
 
-            /// Default vtable reference creation.
-            impl<'cglue_a, CGlueC #cglue_c_bounds, CGlueCtx: #ctx_bound, #gen_declare_stripped #assoc_declare_stripped> Default
+            /// Static vtable reference of the given type.
+            impl<'cglue_a, CGlueC #cglue_c_bounds, CGlueCtx: #ctx_bound, #gen_declare_stripped #assoc_declare_stripped> #trg_path::StaticVtbl
                 for &'cglue_a #vtbl_ident<'cglue_a, CGlueC, #gen_use #assoc_use>
-            where #gen_where_bounds #trait_type_bounds #cglue_c_into_inner
+            where #gen_where_bounds #trait_type_bounds #cglue_c_into_inner #cglue_c_clone_arc
                 CGlueC::ObjType: for<#life_declare> #trait_name<#life_use #gen_use #assoc_equality>,
                 CGlueC: #trg_path::Opaquable,
                 CGlueC::OpaqueTarget: #trg_path::GenericTypeBounds,
-                #vtbl_ident<'cglue_a, CGlueC, #gen_use #assoc_use>: #trg_path::CGlueBaseVtbl,
+                #vtbl_ident<'cglue_a, CGlueC, #gen_use #assoc_use>: 'cglue_a + #trg_path::CGlueBaseVtbl,
+            {
+                const CGLUE_VTBL: Self = &#vtbl_ident {
+                    #vtbl_default_funcs
+                    #assoc_phantom_data_init
+                    _lt_cglue_a: ::core::marker::PhantomData,
+                };
+            }
+
+            /// Default vtable reference creation.
+            impl<
+                'cglue_a,
+                CGlueC: #trg_path::CGlueObjBase,
+                #gen_declare_stripped
+                #assoc_declare_stripped
+            > Default for &'cglue_a #vtbl_ident<'cglue_a, CGlueC, #gen_use #assoc_use>
+            where
+                #gen_where_bounds
+                Self: #trg_path::StaticVtbl,
             {
                 /// Create a static vtable for the given type.
                 fn default() -> Self {
-                    &#vtbl_ident {
-                        #vtbl_default_funcs
-                        #assoc_phantom_data_init
-                        _lt_cglue_a: ::core::marker::PhantomData,
-                    }
+                    #trg_path::StaticVtbl::CGLUE_VTBL
                 }
             }
 
@@ -1287,43 +1754,67 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
                 CGlueC::ObjType: for<#life_declare> #trait_name<#life_use #gen_use #assoc_equality> {}
 
             #[doc = #base_box_trait_obj_doc]
-            pub type #base_box_trait_obj_ident<'cglue_a, CGlueT, #gen_use #assoc_use>
+            pub type #base_box_trait_obj_ident<'cglue_a, CGlueT, #gen_alias_declare #assoc_use>
                 = #base_trait_obj_ident<'cglue_a, #crate_path::boxed::CBox<'cglue_a, CGlueT>, #trg_path::NoContext, #gen_use #assoc_use>;
 
             #[doc = #base_ctx_trait_obj_doc]
-            pub type #base_ctx_trait_obj_ident<'cglue_a, CGlueT, CGlueCtx, #gen_use #assoc_use>
+            pub type #base_ctx_trait_obj_ident<'cglue_a, CGlueT, CGlueCtx, #gen_alias_declare #assoc_use>
                 = #base_trait_obj_ident<'cglue_a, #crate_path::boxed::CBox<'cglue_a, CGlueT>, CGlueCtx, #gen_use #assoc_use>;
 
             #[doc = #base_arc_trait_obj_doc]
-            pub type #base_arc_trait_obj_ident<'cglue_a, CGlueT, CGlueC, #gen_use #assoc_use>
+            pub type #base_arc_trait_obj_ident<'cglue_a, CGlueT, CGlueC, #gen_alias_declare #assoc_use>
                 = #base_ctx_trait_obj_ident<'cglue_a, CGlueT, #crate_path::arc::CArc<CGlueC>, #gen_use #assoc_use>;
 
+            #[doc = #base_unsend_trait_obj_doc]
+            pub type #base_unsend_trait_obj_ident<'cglue_a, CGlueT, #gen_alias_declare #assoc_use>
+                = #base_trait_obj_ident<'cglue_a, #crate_path::boxed::CUnsendBox<'cglue_a, CGlueT>, #trg_path::NoContext, #gen_use #assoc_use>;
+
+            #[doc = #base_ctx_unsend_trait_obj_doc]
+            pub type #base_ctx_unsend_trait_obj_ident<'cglue_a, CGlueT, CGlueCtx, #gen_alias_declare #assoc_use>
+                = #base_trait_obj_ident<'cglue_a, #crate_path::boxed::CUnsendBox<'cglue_a, CGlueT>, CGlueCtx, #gen_use #assoc_use>;
+
+            #[doc = #base_arc_unsend_trait_obj_doc]
+            pub type #base_arc_unsend_trait_obj_ident<'cglue_a, CGlueT, CGlueC, #gen_alias_declare #assoc_use>
+                = #base_ctx_unsend_trait_obj_ident<'cglue_a, CGlueT, #crate_path::arc::CArc<CGlueC>, #gen_use #assoc_use>;
+
+            #[doc = #base_inline_trait_obj_doc]
+            pub type #base_inline_trait_obj_ident<'cglue_a, CGlueT, #gen_alias_declare #assoc_use const CGLUE_N: usize>
+                = #base_trait_obj_ident<'cglue_a, #crate_path::inline::CInline<'cglue_a, CGlueT, CGLUE_N>, #trg_path::NoContext, #gen_use #assoc_use>;
+
+            #[doc = #base_ctx_inline_trait_obj_doc]
+            pub type #base_ctx_inline_trait_obj_ident<'cglue_a, CGlueT, CGlueCtx, #gen_alias_declare #assoc_use const CGLUE_N: usize>
+                = #base_trait_obj_ident<'cglue_a, #crate_path::inline::CInline<'cglue_a, CGlueT, CGLUE_N>, CGlueCtx, #gen_use #assoc_use>;
+
+            #[doc = #base_arc_inline_trait_obj_doc]
+            pub type #base_arc_inline_trait_obj_ident<'cglue_a, CGlueT, CGlueC, #gen_alias_declare #assoc_use const CGLUE_N: usize>
+                = #base_ctx_inline_trait_obj_ident<'cglue_a, CGlueT, #crate_path::arc::CArc<CGlueC>, #gen_use #assoc_use CGLUE_N>;
+
             #[doc = #base_mut_trait_obj_doc]
-            pub type #base_mut_trait_obj_ident<'cglue_a, CGlueT, #gen_use #assoc_use>
+            pub type #base_mut_trait_obj_ident<'cglue_a, CGlueT, #gen_alias_declare #assoc_use>
                 = #base_trait_obj_ident<'cglue_a, &'cglue_a mut CGlueT, #trg_path::NoContext, #gen_use #assoc_use>;
 
             #[doc = #base_ctx_mut_trait_obj_doc]
-            pub type #base_ctx_mut_trait_obj_ident<'cglue_a, CGlueT, CGlueCtx, #gen_use #assoc_use>
+            pub type #base_ctx_mut_trait_obj_ident<'cglue_a, CGlueT, CGlueCtx, #gen_alias_declare #assoc_use>
                 = #base_trait_obj_ident<'cglue_a, &'cglue_a mut CGlueT, CGlueCtx, #gen_use #assoc_use>;
 
             #[doc = #base_arc_mut_trait_obj_doc]
-            pub type #base_arc_mut_trait_obj_ident<'cglue_a, CGlueT, CGlueC, #gen_use #assoc_use>
+            pub type #base_arc_mut_trait_obj_ident<'cglue_a, CGlueT, CGlueC, #gen_alias_declare #assoc_use>
                 = #base_trait_obj_ident<'cglue_a, &'cglue_a mut CGlueT, #crate_path::arc::CArc<CGlueC>, #gen_use #assoc_use>;
 
             #[doc = #base_ref_trait_obj_doc]
-            pub type #base_ref_trait_obj_ident<'cglue_a, CGlueT, #gen_use #assoc_use>
+            pub type #base_ref_trait_obj_ident<'cglue_a, CGlueT, #gen_alias_declare #assoc_use>
                 = #base_trait_obj_ident<'cglue_a, &'cglue_a CGlueT, #trg_path::NoContext, #gen_use #assoc_use>;
 
             #[doc = #base_ctx_ref_trait_obj_doc]
-            pub type #base_ctx_ref_trait_obj_ident<'cglue_a, CGlueT, CGlueCtx, #gen_use #assoc_use>
+            pub type #base_ctx_ref_trait_obj_ident<'cglue_a, CGlueT, CGlueCtx, #gen_alias_declare #assoc_use>
                 = #base_trait_obj_ident<'cglue_a, &'cglue_a CGlueT, CGlueCtx, #gen_use #assoc_use>;
 
             #[doc = #base_arc_ref_trait_obj_doc]
-            pub type #base_arc_ref_trait_obj_ident<'cglue_a, CGlueT, CGlueC, #gen_use #assoc_use>
+            pub type #base_arc_ref_trait_obj_ident<'cglue_a, CGlueT, CGlueC, #gen_alias_declare #assoc_use>
                 = #base_trait_obj_ident<'cglue_a, &'cglue_a CGlueT, #crate_path::arc::CArc<CGlueC>, #gen_use #assoc_use>;
 
             #[doc = #base_trait_obj_doc]
-            pub type #base_trait_obj_ident<'cglue_a, CGlueInst, CGlueCtx, #gen_use #assoc_use>
+            pub type #base_trait_obj_ident<'cglue_a, CGlueInst, CGlueCtx, #gen_alias_declare #assoc_use>
                 = #trg_path::CGlueTraitObj::<
                     'cglue_a,
                     CGlueInst,
@@ -1338,45 +1829,71 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
                 >;
 
             #[doc = #opaque_box_trait_obj_doc]
-            pub type #opaque_box_trait_obj_ident<'cglue_a, #gen_use #assoc_use>
+            pub type #opaque_box_trait_obj_ident<'cglue_a, #gen_alias_declare #assoc_use>
                 = #base_box_trait_obj_ident<'cglue_a, #c_void, #gen_use #assoc_use>;
 
             #[doc = #opaque_ctx_trait_obj_doc]
-            pub type #opaque_ctx_trait_obj_ident<'cglue_a, CGlueCtx, #gen_use #assoc_use>
+            pub type #opaque_ctx_trait_obj_ident<'cglue_a, CGlueCtx, #gen_alias_declare #assoc_use>
                 = #base_ctx_trait_obj_ident<'cglue_a, #c_void, CGlueCtx, #gen_use #assoc_use>;
 
             #[doc = #opaque_arc_trait_obj_doc]
-            pub type #opaque_arc_trait_obj_ident<'cglue_a, #gen_use #assoc_use>
+            pub type #opaque_arc_trait_obj_ident<'cglue_a, #gen_alias_declare #assoc_use>
                 = #base_arc_trait_obj_ident<'cglue_a, #c_void, #c_void, #gen_use #assoc_use>;
 
+            #[doc = #opaque_unsend_trait_obj_doc]
+            pub type #opaque_unsend_trait_obj_ident<'cglue_a, #gen_alias_declare #assoc_use>
+                = #base_unsend_trait_obj_ident<'cglue_a, #c_void, #gen_use #assoc_use>;
+
+            #[doc = #opaque_ctx_unsend_trait_obj_doc]
+            pub type #opaque_ctx_unsend_trait_obj_ident<'cglue_a, CGlueCtx, #gen_alias_declare #assoc_use>
+                = #base_ctx_unsend_trait_obj_ident<'cglue_a, #c_void, CGlueCtx, #gen_use #assoc_use>;
+
+            #[doc = #opaque_arc_unsend_trait_obj_doc]
+            pub type #opaque_arc_unsend_trait_obj_ident<'cglue_a, #gen_alias_declare #assoc_use>
+                = #base_arc_unsend_trait_obj_ident<'cglue_a, #c_void, #c_void, #gen_use #assoc_use>;
+
+            #[doc = #opaque_inline_trait_obj_doc]
+            pub type #opaque_inline_trait_obj_ident<'cglue_a, #gen_alias_declare #assoc_use const CGLUE_N: usize>
+                = #base_inline_trait_obj_ident<'cglue_a, #c_void, #gen_use #assoc_use CGLUE_N>;
+
+            #[doc = #opaque_ctx_inline_trait_obj_doc]
+            pub type #opaque_ctx_inline_trait_obj_ident<'cglue_a, CGlueCtx, #gen_alias_declare #assoc_use const CGLUE_N: usize>
+                = #base_ctx_inline_trait_obj_ident<'cglue_a, #c_void, CGlueCtx, #gen_use #assoc_use CGLUE_N>;
+
+            #[doc = #opaque_arc_inline_trait_obj_doc]
+            pub type #opaque_arc_inline_trait_obj_ident<'cglue_a, #gen_alias_declare #assoc_use const CGLUE_N: usize>
+                = #base_arc_inline_trait_obj_ident<'cglue_a, #c_void, #c_void, #gen_use #assoc_use CGLUE_N>;
+
             #[doc = #opaque_mut_trait_obj_doc]
-            pub type #opaque_mut_trait_obj_ident<'cglue_a, #gen_use #assoc_use>
+            pub type #opaque_mut_trait_obj_ident<'cglue_a, #gen_alias_declare #assoc_use>
                 = #base_mut_trait_obj_ident<'cglue_a, #c_void, #gen_use #assoc_use>;
 
             #[doc = #opaque_ctx_mut_trait_obj_doc]
-            pub type #opaque_ctx_mut_trait_obj_ident<'cglue_a, CGlueCtx, #gen_use #assoc_use>
+            pub type #opaque_ctx_mut_trait_obj_ident<'cglue_a, CGlueCtx, #gen_alias_declare #assoc_use>
                 = #base_ctx_mut_trait_obj_ident<'cglue_a, #c_void, CGlueCtx, #gen_use #assoc_use>;
 
             #[doc = #opaque_arc_mut_trait_obj_doc]
-            pub type #opaque_arc_mut_trait_obj_ident<'cglue_a, #gen_use #assoc_use>
+            pub type #opaque_arc_mut_trait_obj_ident<'cglue_a, #gen_alias_declare #assoc_use>
                 = #base_arc_mut_trait_obj_ident<'cglue_a, #c_void, #c_void, #gen_use #assoc_use>;
 
             #[doc = #opaque_ref_trait_obj_doc]
-            pub type #opaque_ref_trait_obj_ident<'cglue_a, #gen_use #assoc_use>
+            pub type #opaque_ref_trait_obj_ident<'cglue_a, #gen_alias_declare #assoc_use>
                 = #base_ref_trait_obj_ident<'cglue_a, #c_void, #gen_use #assoc_use>;
 
             #[doc = #opaque_ctx_ref_trait_obj_doc]
-            pub type #opaque_ctx_ref_trait_obj_ident<'cglue_a, CGlueCtx, #gen_use #assoc_use>
+            pub type #opaque_ctx_ref_trait_obj_ident<'cglue_a, CGlueCtx, #gen_alias_declare #assoc_use>
                 = #base_ctx_ref_trait_obj_ident<'cglue_a, #c_void, CGlueCtx, #gen_use #assoc_use>;
 
             #[doc = #opaque_arc_ref_trait_obj_doc]
-            pub type #opaque_arc_ref_trait_obj_ident<'cglue_a, #gen_use #assoc_use>
+            pub type #opaque_arc_ref_trait_obj_ident<'cglue_a, #gen_alias_declare #assoc_use>
                 = #base_arc_ref_trait_obj_ident<'cglue_a, #c_void, #c_void, #gen_use #assoc_use>;
 
             /* Internal wrapper functions. */
 
             #cfuncs
 
+            #factory
+
             /* Define trait for simpler type accesses */
 
             pub trait #accessor_trait_ident<'cglue_a #cglue_a_outlives, #life_declare #gen_declare #assoc_declare>
@@ -1399,7 +1916,7 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
 
             /* Binds associated types for a given trait. */
 
-            pub trait #assoc_bind_ident<#gen_use> {
+            pub trait #assoc_bind_ident<#gen_alias_declare> {
                 type Assocs;
             }
 
@@ -1428,6 +1945,8 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
                 #gen_where_bounds_base
             {
                 fn get_vtbl(&self) -> &#vtbl_ident<'cglue_a, <Self as #trg_path::GetContainer>::ContType, #gen_use #assoc_use>;
+
+                #vtbl_const_getters
             }
 
             impl<
@@ -1471,6 +1990,7 @@ pub fn gen_trait(mut tr: ItemTrait, ext_name: Option<&Ident>) -> TokenStream {
             {
                 // TODO: #assoc_type_def
                 #trait_type_defs
+                #trait_const_defs
                 #trait_impl_fns
             }
 
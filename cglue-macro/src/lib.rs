@@ -78,7 +78,10 @@ pub fn trait_obj(args: TokenStream) -> TokenStream {
 ///
 /// # Arguments
 ///
-/// 1. The name of the group.
+/// 1. The name of the group. It may be followed by `: BaseGroup` to derive it from another group.
+///    Then, the trait lists may only contain traits of the base group - mandatory list makes
+///    optional traits of the base group mandatory, and the optional list picks which ones stay
+///    optional.
 ///
 /// 2. Mandatory traits for the group. Either a single trait name, or a braced list of traits.
 ///
@@ -91,6 +94,9 @@ pub fn trait_obj(args: TokenStream) -> TokenStream {
 ///
 /// 4. Optional block for external trait definitions. This block is needed when using non-standard
 ///    external traits.
+///
+/// The name may be preceded by `#[vtbl_table]` attribute. Objects of such group hold a single
+/// reference to a shared table of vtables, as opposed to storing all vtables inline.
 #[proc_macro]
 pub fn cglue_trait_group(args: TokenStream) -> TokenStream {
     let args = parse_macro_input!(args as TraitGroup);
@@ -101,13 +107,17 @@ pub fn cglue_trait_group(args: TokenStream) -> TokenStream {
 ///
 /// # Arguments
 ///
-/// 1. The name of the type to implement the group for.
+/// 1. The name of the type to implement the group for. It may also be a generic parameter with
+///    bounds, such as `T: Trait`, to implement the group for all types satisfying them.
 ///
 /// 2. The name of the group to implement.
 ///
 /// 3. Optional traits that this object contains. Either a single trait, or a braced list of
 ///    traits. Note that the list must redefine identical aliases, as defined in
 ///    `cglue_trait_group!` invokation.
+///
+/// 3.1. `auto` may be specified instead of the list to detect the implemented optional traits
+///   automatically. Only CGlue instance types are supported in this mode.
 #[proc_macro]
 #[cfg_attr(feature = "unstable", allow(unused))]
 pub fn cglue_impl_group(args: TokenStream) -> TokenStream {
@@ -154,6 +164,40 @@ pub fn group_obj(args: TokenStream) -> TokenStream {
     gen.into()
 }
 
+/// Convert a trait group object into a different trait group that shares its traits.
+///
+/// The syntax is the same as a cast expression:
+///
+/// ```ignore
+/// regroup!(obj as OtherGroup)
+/// ```
+///
+/// The container and context are moved into the target group, alongside the vtables of matching
+/// traits. Every trait of the target group must be part of the source group, be it as a mandatory,
+/// or an optional trait.
+///
+/// Returns `Err` with the original object, if any of the target group's mandatory traits is not
+/// implemented by the object, or if any of the groups contain traits that need temporary return
+/// storage, such as ones returning wrapped references.
+#[proc_macro]
+pub fn regroup(args: TokenStream) -> TokenStream {
+    let GroupCastType {
+        mut target,
+        expr,
+        ident,
+    } = parse_macro_input!(args as GroupCastType);
+
+    if let Ok(ident) = parse2::<Ident>(ident) {
+        target.path = ext_abs_remap(prelude_remap_with_ident(target.path, &ident));
+    }
+
+    let gen = quote! {
+        #target ::cglue_regroup(#expr)
+    };
+
+    gen.into()
+}
+
 /// Checked cast to a list of optional traits.
 ///
 /// The syntax is similar to a cast expression, but uses `impl` keyword:
@@ -257,6 +301,33 @@ pub fn into(args: TokenStream) -> TokenStream {
     cast.cast_group(CastType::Into).into()
 }
 
+/// Checked cast to a list of optional traits.
+///
+/// The syntax is similar to a cast expression, but uses `impl` keyword:
+///
+/// ```ignore
+/// try_into!(obj impl Trait1 + Trait2 + Trait3);
+/// ```
+///
+/// `try_into!` is final, just like `into!`, but it returns `Result`, giving the original object
+/// back as `Err`, if it does not implement the traits. This allows to try multiple sets of traits
+/// in order.
+///
+/// This macro accepts either:
+///
+/// 1. A list of optional traits, without any mandatory traits.
+///
+/// or
+///
+/// 2. A list of optional traits, with every mandatory trait.
+///
+/// In either case a successfully cast object will still implement the mandatory traits.
+#[proc_macro]
+pub fn try_into(args: TokenStream) -> TokenStream {
+    let cast = parse_macro_input!(args as TraitCastGroup);
+    cast.cast_group(CastType::TryInto).into()
+}
+
 /// Check if the group can be cast to the specified traits.
 ///
 /// The syntax is similar to a cast expression, but uses `impl` keyword:
@@ -356,7 +427,7 @@ pub fn return_wrap(_: TokenStream, input: TokenStream) -> TokenStream {
     input
 }
 
-/// Wrap the associated type with a CGlue trait object.
+/// Wrap the associated type, or the argument of a CGlue trait type with a CGlue trait object.
 #[proc_macro_attribute]
 pub fn wrap_with_obj(_: TokenStream, input: TokenStream) -> TokenStream {
     input
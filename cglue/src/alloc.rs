@@ -0,0 +1,237 @@
+//! # FFI-safe allocators.
+//!
+//! By default, [`CBox`](crate::boxed::CBox), [`CVec`](crate::vec::CVec) and
+//! [`CSliceBox`](crate::boxed::CSliceBox) allocate on the global allocator of the module that
+//! created them. This module exposes [`CAllocator`], a function table that can be passed to the
+//! `*_in` constructors of these containers to allocate memory elsewhere, for instance, in a
+//! host-provided arena.
+//!
+//! # Examples
+//!
+//! ```
+//! use cglue::alloc::{CAllocator, CLayout};
+//! use cglue::boxed::CBox;
+//! use cglue::trait_group::c_void;
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//!
+//! static IN_USE: AtomicUsize = AtomicUsize::new(0);
+//!
+//! unsafe extern "C" fn alloc(_: *const c_void, layout: CLayout) -> *mut u8 {
+//!     IN_USE.fetch_add(layout.size, Ordering::Relaxed);
+//!     std::alloc::alloc(layout.into())
+//! }
+//!
+//! unsafe extern "C" fn realloc(
+//!     _: *const c_void,
+//!     ptr: *mut u8,
+//!     layout: CLayout,
+//!     new_size: usize,
+//! ) -> *mut u8 {
+//!     IN_USE.fetch_add(new_size, Ordering::Relaxed);
+//!     IN_USE.fetch_sub(layout.size, Ordering::Relaxed);
+//!     std::alloc::realloc(ptr, layout.into(), new_size)
+//! }
+//!
+//! unsafe extern "C" fn dealloc(_: *const c_void, ptr: *mut u8, layout: CLayout) {
+//!     IN_USE.fetch_sub(layout.size, Ordering::Relaxed);
+//!     std::alloc::dealloc(ptr, layout.into())
+//! }
+//!
+//! static COUNTING: CAllocator = CAllocator {
+//!     ctx: core::ptr::null(),
+//!     alloc,
+//!     realloc,
+//!     dealloc,
+//! };
+//!
+//! let b = CBox::new_in(42u64, &COUNTING);
+//!
+//! assert_eq!(*b, 42);
+//! assert_eq!(IN_USE.load(Ordering::Relaxed), 8);
+//!
+//! std::mem::drop(b);
+//!
+//! assert_eq!(IN_USE.load(Ordering::Relaxed), 0);
+//! ```
+
+use crate::trait_group::c_void;
+use core::ptr::NonNull;
+use std::alloc::Layout;
+
+/// FFI-safe memory layout.
+///
+/// This is an equivalent of [`Layout`], with the same invariants - `align` must be a non-zero
+/// power of 2, and `size` rounded up to `align` must not overflow `isize`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct CLayout {
+    pub size: usize,
+    pub align: usize,
+}
+
+impl CLayout {
+    /// Get the layout of type `T`.
+    pub const fn new<T>() -> Self {
+        Self {
+            size: core::mem::size_of::<T>(),
+            align: core::mem::align_of::<T>(),
+        }
+    }
+
+    /// Get the layout of an array of `T` with `len` elements.
+    ///
+    /// Returns `None` on overflow.
+    pub fn array<T>(len: usize) -> Option<Self> {
+        Layout::array::<T>(len).ok().map(Self::from)
+    }
+}
+
+impl From<Layout> for CLayout {
+    fn from(layout: Layout) -> Self {
+        Self {
+            size: layout.size(),
+            align: layout.align(),
+        }
+    }
+}
+
+impl From<CLayout> for Layout {
+    fn from(layout: CLayout) -> Self {
+        // SAFETY: CLayout upholds the same invariants as Layout.
+        unsafe { Layout::from_size_align_unchecked(layout.size, layout.align) }
+    }
+}
+
+/// FFI-safe allocator.
+///
+/// This is a function table with an opaque `ctx` pointer, that gets passed to every function.
+/// Containers store a `'static` reference to the allocator that was used to create them, and
+/// use it to grow and free their memory.
+///
+/// The functions follow semantics of [`GlobalAlloc`](std::alloc::GlobalAlloc). Namely, they
+/// are never called with zero-sized layouts, `alloc` and `realloc` return null on failure, and
+/// `realloc` keeps the alignment of the passed layout.
+///
+/// # Safety
+///
+/// Since the allocator is shared between all containers created with it, the functions must
+/// be safe to call from multiple threads at once.
+#[repr(C)]
+#[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+pub struct CAllocator {
+    /// Opaque allocator state, passed to all functions.
+    pub ctx: *const c_void,
+    /// Allocate a block of memory with given layout.
+    pub alloc: unsafe extern "C" fn(ctx: *const c_void, layout: CLayout) -> *mut u8,
+    /// Resize a previously allocated block of memory to `new_size` bytes.
+    pub realloc: unsafe extern "C" fn(
+        ctx: *const c_void,
+        ptr: *mut u8,
+        layout: CLayout,
+        new_size: usize,
+    ) -> *mut u8,
+    /// Free a previously allocated block of memory.
+    pub dealloc: unsafe extern "C" fn(ctx: *const c_void, ptr: *mut u8, layout: CLayout),
+}
+
+unsafe impl Send for CAllocator {}
+unsafe impl Sync for CAllocator {}
+
+static GLOBAL: CAllocator = CAllocator {
+    ctx: core::ptr::null(),
+    alloc: cglue_global_alloc,
+    realloc: cglue_global_realloc,
+    dealloc: cglue_global_dealloc,
+};
+
+impl CAllocator {
+    /// Get the allocator that wraps global allocator of the current module.
+    pub fn global() -> &'static Self {
+        &GLOBAL
+    }
+
+    /// Allocate memory for given layout.
+    ///
+    /// Zero-sized layouts do not get passed to the allocator, and a dangling, well-aligned
+    /// pointer is returned instead. Allocation failures are handled by
+    /// [`handle_alloc_error`](std::alloc::handle_alloc_error).
+    pub fn allocate(&self, layout: CLayout) -> NonNull<u8> {
+        if layout.size == 0 {
+            return Self::dangling(layout);
+        }
+
+        NonNull::new(unsafe { (self.alloc)(self.ctx, layout) })
+            .unwrap_or_else(|| std::alloc::handle_alloc_error(layout.into()))
+    }
+
+    /// Resize memory block to `new_size` bytes.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `allocate` or `reallocate` of this allocator, with the
+    /// same `layout`.
+    pub unsafe fn reallocate(
+        &self,
+        ptr: NonNull<u8>,
+        layout: CLayout,
+        new_size: usize,
+    ) -> NonNull<u8> {
+        if layout.size == 0 {
+            return self.allocate(CLayout {
+                size: new_size,
+                ..layout
+            });
+        } else if new_size == 0 {
+            self.deallocate(ptr, layout);
+            return Self::dangling(layout);
+        }
+
+        NonNull::new((self.realloc)(self.ctx, ptr.as_ptr(), layout, new_size)).unwrap_or_else(
+            || {
+                std::alloc::handle_alloc_error(
+                    CLayout {
+                        size: new_size,
+                        ..layout
+                    }
+                    .into(),
+                )
+            },
+        )
+    }
+
+    /// Free a memory block.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `allocate` or `reallocate` of this allocator, with the
+    /// same `layout`.
+    pub unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: CLayout) {
+        if layout.size != 0 {
+            (self.dealloc)(self.ctx, ptr.as_ptr(), layout)
+        }
+    }
+
+    fn dangling(layout: CLayout) -> NonNull<u8> {
+        // SAFETY: alignment is never zero.
+        unsafe { NonNull::new_unchecked(layout.align as *mut u8) }
+    }
+}
+
+unsafe extern "C" fn cglue_global_alloc(_: *const c_void, layout: CLayout) -> *mut u8 {
+    std::alloc::alloc(layout.into())
+}
+
+unsafe extern "C" fn cglue_global_realloc(
+    _: *const c_void,
+    ptr: *mut u8,
+    layout: CLayout,
+    new_size: usize,
+) -> *mut u8 {
+    std::alloc::realloc(ptr, layout.into(), new_size)
+}
+
+unsafe extern "C" fn cglue_global_dealloc(_: *const c_void, ptr: *mut u8, layout: CLayout) {
+    std::alloc::dealloc(ptr, layout.into())
+}
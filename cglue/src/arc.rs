@@ -1,8 +1,11 @@
 //! # FFI-safe Arc.
 use crate::trait_group::c_void;
-use crate::trait_group::Opaquable;
+use crate::trait_group::{CloneArc, Opaquable};
+use core::any::TypeId;
+use core::convert::TryFrom;
+use core::mem::{ManuallyDrop, MaybeUninit};
 use core::ptr::NonNull;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 
 unsafe impl<T: Sync + Send> Send for CArc<T> {}
 unsafe impl<T: Sync + Send> Sync for CArc<T> {}
@@ -34,6 +37,7 @@ pub struct CArc<T: Sized + 'static> {
     )]
     #[cfg_attr(feature = "abi_stable11", sabi(unsafe_change_type = Option<unsafe extern "C" fn (Option<&T>)>))]
     drop_fn: Option<unsafe extern "C" fn(*const T)>,
+    vtbl: Option<&'static CArcVtbl<T>>,
 }
 
 impl<T: Sized + 'static> AsRef<Option<&'static T>> for CArc<T> {
@@ -81,6 +85,7 @@ impl<T> CArc<T> {
             instance: core::mem::replace(&mut self.instance, core::ptr::null()),
             clone_fn: self.clone_fn.take(),
             drop_fn: self.drop_fn.take(),
+            vtbl: self.vtbl.take(),
         }
     }
 
@@ -102,6 +107,113 @@ impl<T> CArc<T> {
     pub fn transpose(self) -> Option<CArcSome<T>> {
         self.into()
     }
+
+    /// Create a new weak reference to the arc.
+    ///
+    /// If the arc is `None`, or it was created without a function table, the returned weak
+    /// reference will never upgrade.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::arc::CArc;
+    ///
+    /// let arc = CArc::from(0u64);
+    /// let weak = arc.downgrade();
+    ///
+    /// assert_eq!(arc.weak_count(), Some(1));
+    /// assert!(weak.upgrade().is_some());
+    ///
+    /// std::mem::drop(arc);
+    ///
+    /// assert!(weak.upgrade().is_none());
+    /// ```
+    pub fn downgrade(&self) -> CWeak<T> {
+        <Option<&CArcSome<T>>>::from(self)
+            .map(CArcSome::downgrade)
+            .unwrap_or_default()
+    }
+
+    /// Get the number of strong references to the arc.
+    ///
+    /// Returns `Some(0)` if the arc is `None`, and `None` if the arc was created without a
+    /// function table.
+    pub fn strong_count(&self) -> Option<usize> {
+        match <Option<&CArcSome<T>>>::from(self) {
+            Some(arc) => CArcSome::strong_count(arc),
+            None => Some(0),
+        }
+    }
+
+    /// Get the number of weak references to the arc.
+    ///
+    /// Returns `Some(0)` if the arc is `None`, and `None` if the arc was created without a
+    /// function table.
+    pub fn weak_count(&self) -> Option<usize> {
+        match <Option<&CArcSome<T>>>::from(self) {
+            Some(arc) => CArcSome::weak_count(arc),
+            None => Some(0),
+        }
+    }
+
+    /// Check whether two arcs point to the same allocation.
+    ///
+    /// Two `None` arcs are considered equal.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self.instance, other.instance)
+    }
+
+    /// Get a mutable reference to the inner value, if there are no other strong or weak
+    /// references to it.
+    ///
+    /// Always returns `None` for opaque arcs, see [`CArcSome::get_mut`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::arc::CArc;
+    ///
+    /// let mut arc = CArc::from(0u64);
+    ///
+    /// *arc.get_mut().unwrap() = 4;
+    ///
+    /// let arc2 = arc.clone();
+    ///
+    /// assert!(arc.get_mut().is_none());
+    ///
+    /// std::mem::drop(arc2);
+    ///
+    /// assert_eq!(arc.get_mut(), Some(&mut 4));
+    /// ```
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        <Option<&mut CArcSome<T>>>::from(self).and_then(CArcSome::get_mut)
+    }
+
+    /// Return the inner value, if the arc has exactly one strong reference.
+    ///
+    /// Otherwise, the same arc is returned back in the `Err` variant. `None` and opaque arcs are
+    /// always returned back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::arc::CArc;
+    ///
+    /// let arc = CArc::from(42u64);
+    /// let arc2 = arc.clone();
+    ///
+    /// let arc = arc.try_unwrap().unwrap_err();
+    ///
+    /// std::mem::drop(arc2);
+    ///
+    /// assert_eq!(arc.try_unwrap().ok(), Some(42));
+    /// ```
+    pub fn try_unwrap(self) -> Result<T, Self> {
+        match self.transpose() {
+            Some(arc) => CArcSome::try_unwrap(arc).map_err(CArcSome::transpose),
+            None => Err(Self::default()),
+        }
+    }
 }
 
 impl<T> From<Option<CArcSome<T>>> for CArc<T> {
@@ -112,6 +224,7 @@ impl<T> From<Option<CArcSome<T>>> for CArc<T> {
                     instance: arc.instance.as_ptr(),
                     clone_fn: Some(arc.clone_fn),
                     drop_fn: arc.drop_fn.take(),
+                    vtbl: arc.vtbl.take(),
                 };
                 core::mem::forget(arc);
                 ret
@@ -120,6 +233,7 @@ impl<T> From<Option<CArcSome<T>>> for CArc<T> {
                 instance: core::ptr::null(),
                 clone_fn: None,
                 drop_fn: None,
+                vtbl: None,
             },
         }
     }
@@ -137,6 +251,7 @@ impl<T> From<Arc<T>> for CArc<T> {
             instance: Arc::into_raw(arc),
             clone_fn: Some(c_clone),
             drop_fn: Some(c_drop),
+            vtbl: Some(CArcVtbl::VTBL),
         }
     }
 }
@@ -150,6 +265,35 @@ impl<T> From<Option<Arc<T>>> for CArc<T> {
     }
 }
 
+/// Convert back into an `Arc`.
+///
+/// This works the same way as the conversion of [`CArcSome`]. `None` arcs are always returned
+/// back in the `Err` variant.
+///
+/// # Examples
+///
+/// ```
+/// use cglue::arc::CArc;
+/// use std::convert::TryFrom;
+/// use std::sync::Arc;
+///
+/// let arc = Arc::new(42u64);
+/// let arc2 = Arc::try_from(CArc::<u64>::from(arc.clone())).ok().unwrap();
+///
+/// assert!(Arc::ptr_eq(&arc, &arc2));
+/// assert!(Arc::<u64>::try_from(CArc::default()).is_err());
+/// ```
+impl<T> TryFrom<CArc<T>> for Arc<T> {
+    type Error = CArc<T>;
+
+    fn try_from(arc: CArc<T>) -> Result<Self, Self::Error> {
+        match arc.transpose() {
+            Some(arc) => Arc::try_from(arc).map_err(CArcSome::transpose),
+            None => Err(CArc::default()),
+        }
+    }
+}
+
 impl<T> From<&mut CArc<T>> for Option<&mut CArcSome<T>> {
     fn from(copt: &mut CArc<T>) -> Self {
         if copt.instance.is_null() {
@@ -177,10 +321,12 @@ impl<T> From<CArc<T>> for Option<CArcSome<T>> {
                 instance,
                 clone_fn: Some(clone_fn),
                 drop_fn,
+                vtbl,
             } => Some(CArcSome {
                 instance: NonNull::new(instance as _)?,
                 clone_fn,
                 drop_fn,
+                vtbl,
             }),
             _ => None,
         };
@@ -199,6 +345,7 @@ impl<T> Default for CArc<T> {
             instance: core::ptr::null(),
             clone_fn: None,
             drop_fn: None,
+            vtbl: None,
         }
     }
 }
@@ -220,6 +367,121 @@ unsafe extern "C" fn c_drop<T: Sized + 'static>(ptr_to_arc: *const T) {
     }
 }
 
+unsafe extern "C" fn c_strong_count<T: Sized + 'static>(ptr_to_arc: *const T) -> usize {
+    Arc::strong_count(&ManuallyDrop::new(Arc::from_raw(ptr_to_arc)))
+}
+
+unsafe extern "C" fn c_weak_count<T: Sized + 'static>(ptr_to_arc: *const T) -> usize {
+    Arc::weak_count(&ManuallyDrop::new(Arc::from_raw(ptr_to_arc)))
+}
+
+unsafe extern "C" fn c_get_mut<T: Sized + 'static>(ptr_to_arc: *const T) -> *mut T {
+    let mut arc = ManuallyDrop::new(Arc::from_raw(ptr_to_arc));
+    Arc::get_mut(&mut arc)
+        .map(|v| v as *mut T)
+        .unwrap_or(core::ptr::null_mut())
+}
+
+unsafe extern "C" fn c_try_unwrap<T: Sized + 'static>(ptr_to_arc: *const T, out: *mut T) -> bool {
+    match Arc::try_unwrap(Arc::from_raw(ptr_to_arc)) {
+        Ok(val) => {
+            out.write(val);
+            true
+        }
+        Err(arc) => {
+            let _ = Arc::into_raw(arc);
+            false
+        }
+    }
+}
+
+unsafe extern "C" fn c_downgrade<T: Sized + 'static>(ptr_to_arc: *const T) -> *const T {
+    let arc = ManuallyDrop::new(Arc::from_raw(ptr_to_arc));
+    Weak::into_raw(Arc::downgrade(&arc))
+}
+
+unsafe extern "C" fn c_upgrade<T: Sized + 'static>(ptr_to_weak: *const T) -> *const T {
+    ManuallyDrop::new(Weak::from_raw(ptr_to_weak))
+        .upgrade()
+        .map(Arc::into_raw)
+        .unwrap_or(core::ptr::null())
+}
+
+unsafe extern "C" fn c_weak_clone<T: Sized + 'static>(ptr_to_weak: *const T) -> *const T {
+    let weak = ManuallyDrop::new(Weak::from_raw(ptr_to_weak));
+    Weak::into_raw(Weak::clone(&weak))
+}
+
+unsafe extern "C" fn c_weak_drop<T: Sized + 'static>(ptr_to_weak: *const T) {
+    let _ = Weak::from_raw(ptr_to_weak);
+}
+
+unsafe extern "C" fn c_weak_strong_count<T: Sized + 'static>(ptr_to_weak: *const T) -> usize {
+    ManuallyDrop::new(Weak::from_raw(ptr_to_weak)).strong_count()
+}
+
+unsafe extern "C" fn c_weak_weak_count<T: Sized + 'static>(ptr_to_weak: *const T) -> usize {
+    ManuallyDrop::new(Weak::from_raw(ptr_to_weak)).weak_count()
+}
+
+/// Marker of the current module.
+///
+/// Only its address is used, which is unique to each module, just like that of
+/// [`CAllocator::global`](crate::alloc::CAllocator::global).
+static MODULE: u8 = 0;
+
+extern "C" fn c_module() -> &'static u8 {
+    &MODULE
+}
+
+/// Function table of an FFI-safe Arc.
+///
+/// All operations beyond simple clone and drop go through this table, so that they are always
+/// performed by the module that created the arc.
+#[repr(C)]
+#[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+pub struct CArcVtbl<T: Sized + 'static> {
+    clone: unsafe extern "C" fn(*const T) -> *const T,
+    drop: unsafe extern "C" fn(*const T),
+    strong_count: unsafe extern "C" fn(*const T) -> usize,
+    weak_count: unsafe extern "C" fn(*const T) -> usize,
+    get_mut: unsafe extern "C" fn(*const T) -> *mut T,
+    try_unwrap: unsafe extern "C" fn(*const T, *mut T) -> bool,
+    downgrade: unsafe extern "C" fn(*const T) -> *const T,
+    upgrade: unsafe extern "C" fn(*const T) -> *const T,
+    weak_clone: unsafe extern "C" fn(*const T) -> *const T,
+    weak_drop: unsafe extern "C" fn(*const T),
+    weak_strong_count: unsafe extern "C" fn(*const T) -> usize,
+    weak_weak_count: unsafe extern "C" fn(*const T) -> usize,
+    module: extern "C" fn() -> &'static u8,
+}
+
+impl<T: 'static> CArcVtbl<T> {
+    const VTBL: &'static Self = &Self {
+        clone: c_clone,
+        drop: c_drop,
+        strong_count: c_strong_count,
+        weak_count: c_weak_count,
+        get_mut: c_get_mut,
+        try_unwrap: c_try_unwrap,
+        downgrade: c_downgrade,
+        upgrade: c_upgrade,
+        weak_clone: c_weak_clone,
+        weak_drop: c_weak_drop,
+        weak_strong_count: c_weak_strong_count,
+        weak_weak_count: c_weak_weak_count,
+        module: c_module,
+    };
+}
+
+/// Check whether `T` is the opaque type arcs get converted to.
+///
+/// Function tables of opaque arcs operate on the original type, thus anything that hands out the
+/// value itself must refuse to work on them.
+fn is_opaque<T: 'static>() -> bool {
+    TypeId::of::<T>() == TypeId::of::<c_void>()
+}
+
 const _: [(); std::mem::size_of::<CArcSome<u128>>()] = [(); std::mem::size_of::<CArc<u128>>()];
 
 /// FFI-Safe Arc
@@ -253,6 +515,7 @@ pub struct CArcSome<T: Sized + 'static> {
     )]
     #[cfg_attr(feature = "abi_stable11", sabi(unsafe_change_type = Option<unsafe extern "C" fn (Option<&T>)>))]
     drop_fn: Option<unsafe extern "C" fn(*const T)>,
+    vtbl: Option<&'static CArcVtbl<T>>,
 }
 
 unsafe impl<T: Sync + Send> Send for CArcSome<T> {}
@@ -287,6 +550,182 @@ impl<T> CArcSome<T> {
         std::mem::forget(self);
         Arc::from_raw(ptr)
     }
+
+    /// Check whether the arc was created by `Arc<T>` of the current module.
+    ///
+    /// This holds when the function table points to the module marker of this module, meaning the
+    /// allocation comes from the global allocator of this module.
+    fn is_local(&self) -> bool {
+        !is_opaque::<T>()
+            && self
+                .vtbl
+                .map_or(false, |vtbl| core::ptr::eq((vtbl.module)(), &MODULE))
+    }
+
+    /// Create a new weak reference to the arc.
+    ///
+    /// If the arc was created without a function table, the returned weak reference will never
+    /// upgrade.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::arc::CArcSome;
+    ///
+    /// let arc = CArcSome::from(0u64);
+    /// let weak = CArcSome::downgrade(&arc);
+    ///
+    /// assert_eq!(weak.strong_count(), 1);
+    /// assert_eq!(weak.upgrade().map(|a| *a), Some(0));
+    /// ```
+    pub fn downgrade(this: &Self) -> CWeak<T> {
+        match this.vtbl {
+            Some(vtbl) => CWeak {
+                instance: unsafe { (vtbl.downgrade)(this.instance.as_ptr()) },
+                vtbl: Some(vtbl),
+            },
+            None => CWeak::default(),
+        }
+    }
+
+    /// Get the number of strong references to the arc.
+    ///
+    /// Returns `None` if the arc was created without a function table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::arc::CArcSome;
+    ///
+    /// let arc = CArcSome::from(0u64);
+    /// let _arc2 = arc.clone();
+    ///
+    /// assert_eq!(CArcSome::strong_count(&arc), Some(2));
+    /// ```
+    pub fn strong_count(this: &Self) -> Option<usize> {
+        this.vtbl
+            .map(|vtbl| unsafe { (vtbl.strong_count)(this.instance.as_ptr()) })
+    }
+
+    /// Get the number of weak references to the arc.
+    ///
+    /// Returns `None` if the arc was created without a function table.
+    pub fn weak_count(this: &Self) -> Option<usize> {
+        this.vtbl
+            .map(|vtbl| unsafe { (vtbl.weak_count)(this.instance.as_ptr()) })
+    }
+
+    /// Check whether two arcs point to the same allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::arc::CArcSome;
+    ///
+    /// let arc = CArcSome::from(0u64);
+    /// let arc2 = arc.clone();
+    /// let arc3 = CArcSome::from(0u64);
+    ///
+    /// assert!(CArcSome::ptr_eq(&arc, &arc2));
+    /// assert!(!CArcSome::ptr_eq(&arc, &arc3));
+    /// ```
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.instance == other.instance
+    }
+
+    /// Get a mutable reference to the inner value, if there are no other strong or weak
+    /// references to it.
+    ///
+    /// Returns `None` if the arc is shared, or if it was created without a function table.
+    ///
+    /// Opaque arcs (`CArcSome<c_void>`) always return `None`, because the function table of the
+    /// original type would hand out a reference to a value of a different type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::arc::CArcSome;
+    /// use cglue::trait_group::Opaquable;
+    ///
+    /// let mut arc = CArcSome::from(0u64);
+    ///
+    /// *CArcSome::get_mut(&mut arc).unwrap() = 4;
+    /// assert_eq!(*arc, 4);
+    ///
+    /// let mut arc = arc.into_opaque();
+    ///
+    /// assert!(CArcSome::get_mut(&mut arc).is_none());
+    /// ```
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        if is_opaque::<T>() {
+            return None;
+        }
+        let vtbl = this.vtbl?;
+        unsafe { (vtbl.get_mut)(this.instance.as_ptr()).as_mut() }
+    }
+
+    /// Return the inner value, if the arc has exactly one strong reference.
+    ///
+    /// Otherwise, the same arc is returned back in the `Err` variant. Arcs created without a
+    /// function table are always returned back.
+    ///
+    /// Opaque arcs (`CArcSome<c_void>`) are always returned back as well, because the value of
+    /// the original type does not fit in `c_void`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::arc::CArcSome;
+    /// use cglue::trait_group::Opaquable;
+    ///
+    /// let arc = CArcSome::from(42u64);
+    /// let weak = CArcSome::downgrade(&arc);
+    ///
+    /// assert_eq!(CArcSome::try_unwrap(arc).ok(), Some(42));
+    /// assert!(weak.upgrade().is_none());
+    ///
+    /// let arc = CArcSome::from(42u64).into_opaque();
+    ///
+    /// assert!(CArcSome::try_unwrap(arc).is_err());
+    /// ```
+    pub fn try_unwrap(this: Self) -> Result<T, Self> {
+        let vtbl = match this.vtbl {
+            Some(vtbl) if !is_opaque::<T>() => vtbl,
+            _ => return Err(this),
+        };
+
+        let mut out = MaybeUninit::uninit();
+
+        if unsafe { (vtbl.try_unwrap)(this.instance.as_ptr(), out.as_mut_ptr()) } {
+            core::mem::forget(this);
+            Ok(unsafe { out.assume_init() })
+        } else {
+            Err(this)
+        }
+    }
+}
+
+impl<T: Clone> CArcSome<T> {
+    /// Converts `CArcSome<T>` into `Arc<T>`, cloning the value if the arc can not be converted
+    /// directly.
+    ///
+    /// This only clones shared arcs that come from other modules, see the `TryFrom`
+    /// implementation of `Arc<T>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::arc::CArcSome;
+    /// use std::sync::Arc;
+    ///
+    /// let arc = Arc::new(42u64);
+    /// let arc2 = CArcSome::<u64>::from(arc.clone()).into_arc_or_clone();
+    ///
+    /// assert!(Arc::ptr_eq(&arc, &arc2));
+    /// ```
+    pub fn into_arc_or_clone(self) -> Arc<T> {
+        Arc::try_from(self).unwrap_or_else(|arc: Self| Arc::new((*arc).clone()))
+    }
 }
 
 impl<T> From<T> for CArcSome<T> {
@@ -302,6 +741,38 @@ impl<T> From<Arc<T>> for CArcSome<T> {
             instance: NonNull::new(Arc::into_raw(arc) as _).unwrap(),
             clone_fn: c_clone,
             drop_fn: Some(c_drop),
+            vtbl: Some(CArcVtbl::VTBL),
+        }
+    }
+}
+
+/// Convert back into an `Arc`.
+///
+/// Arcs created by the current module are converted back as they are, keeping the allocation
+/// shared. Arcs from other modules are unwrapped if this is the only reference to them, and
+/// returned back in the `Err` variant otherwise. Opaque arcs are always returned back.
+///
+/// # Examples
+///
+/// ```
+/// use cglue::arc::CArcSome;
+/// use std::convert::TryFrom;
+/// use std::sync::Arc;
+///
+/// let arc = Arc::new(vec![1, 2]);
+/// let arc2 = Arc::try_from(CArcSome::<Vec<i32>>::from(arc.clone())).ok().unwrap();
+///
+/// assert!(Arc::ptr_eq(&arc, &arc2));
+/// assert_eq!(*arc2, [1, 2]);
+/// ```
+impl<T> TryFrom<CArcSome<T>> for Arc<T> {
+    type Error = CArcSome<T>;
+
+    fn try_from(arc: CArcSome<T>) -> Result<Self, Self::Error> {
+        if arc.is_local() {
+            Ok(unsafe { arc.into_arc() })
+        } else {
+            CArcSome::try_unwrap(arc).map(Arc::new)
         }
     }
 }
@@ -342,3 +813,167 @@ impl<T> core::ops::Deref for CArcSome<T> {
 unsafe impl<T> Opaquable for CArcSome<T> {
     type OpaqueTarget = CArcSome<c_void>;
 }
+
+impl<T> CloneArc for CArcSome<T> {
+    type ArcTarget = T;
+
+    unsafe fn clone_arc(&self) -> Arc<T> {
+        self.clone().into_arc()
+    }
+}
+
+/// FFI-Safe Weak
+///
+/// This is an FFI-Safe equivalent of `Weak<T>`, created by downgrading a [`CArc`] or
+/// [`CArcSome`].
+///
+/// Just like arcs, the weak reference uses functions from the module that created the arc.
+#[repr(C)]
+#[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+pub struct CWeak<T: Sized + 'static> {
+    instance: *const T,
+    vtbl: Option<&'static CArcVtbl<T>>,
+}
+
+unsafe impl<T: Sync + Send> Send for CWeak<T> {}
+unsafe impl<T: Sync + Send> Sync for CWeak<T> {}
+
+impl<T> CWeak<T> {
+    /// Create a new weak reference that never upgrades.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::arc::CWeak;
+    ///
+    /// let weak = CWeak::<u64>::new();
+    ///
+    /// assert!(weak.upgrade().is_none());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attempt to upgrade the weak reference into an arc.
+    ///
+    /// Returns `None` if the inner value has already been dropped.
+    pub fn upgrade(&self) -> Option<CArcSome<T>> {
+        let vtbl = self.vtbl?;
+        let instance = NonNull::new(unsafe { (vtbl.upgrade)(self.instance) } as _)?;
+        Some(CArcSome {
+            instance,
+            clone_fn: vtbl.clone,
+            drop_fn: Some(vtbl.drop),
+            vtbl: Some(vtbl),
+        })
+    }
+
+    /// Get the number of strong references to the inner value.
+    ///
+    /// Returns 0 if the weak reference never upgrades.
+    pub fn strong_count(&self) -> usize {
+        self.vtbl
+            .map(|vtbl| unsafe { (vtbl.weak_strong_count)(self.instance) })
+            .unwrap_or(0)
+    }
+
+    /// Get the number of weak references to the inner value.
+    ///
+    /// Returns 0 if there are no strong references left.
+    pub fn weak_count(&self) -> usize {
+        self.vtbl
+            .map(|vtbl| unsafe { (vtbl.weak_weak_count)(self.instance) })
+            .unwrap_or(0)
+    }
+
+    /// Check whether two weak references point to the same allocation.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self.instance, other.instance)
+    }
+}
+
+impl<T> Default for CWeak<T> {
+    fn default() -> Self {
+        Self {
+            instance: core::ptr::null(),
+            vtbl: None,
+        }
+    }
+}
+
+impl<T> Clone for CWeak<T> {
+    fn clone(&self) -> Self {
+        match self.vtbl {
+            Some(vtbl) => Self {
+                instance: unsafe { (vtbl.weak_clone)(self.instance) },
+                vtbl: Some(vtbl),
+            },
+            None => Self::default(),
+        }
+    }
+}
+
+impl<T> Drop for CWeak<T> {
+    fn drop(&mut self) {
+        if let Some(vtbl) = self.vtbl.take() {
+            unsafe { (vtbl.weak_drop)(self.instance) }
+        }
+    }
+}
+
+impl<T> From<&CArcSome<T>> for CWeak<T> {
+    fn from(arc: &CArcSome<T>) -> Self {
+        CArcSome::downgrade(arc)
+    }
+}
+
+impl<T> From<&CArc<T>> for CWeak<T> {
+    fn from(arc: &CArc<T>) -> Self {
+        arc.downgrade()
+    }
+}
+
+unsafe impl<T> Opaquable for CWeak<T> {
+    type OpaqueTarget = CWeak<c_void>;
+}
+
+/// Serializes the same way as `Option<T>`.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for CArc<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        AsRef::<Option<&T>>::as_ref(self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for CArc<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Option::<T>::deserialize(deserializer).map(|v| v.map(Arc::new).into())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for CArcSome<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        (**self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for CArcSome<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Self::from)
+    }
+}
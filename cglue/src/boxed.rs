@@ -1,16 +1,23 @@
 //! # FFI-safe wrapped box.
+use crate::alloc::{CAllocator, CLayout};
 use crate::slice::CSliceMut;
 use crate::trait_group::c_void;
 use crate::trait_group::*;
+use crate::vec::CVec;
 use core::ops::{Deref, DerefMut};
 use core::ptr::NonNull;
 use std::boxed::Box;
+use std::vec::Vec;
 
 /// FFI-safe box
 ///
 /// This box has a static self reference, alongside a custom drop function.
 ///
 /// The drop function can be called from anywhere, it will free on correct allocator internally.
+/// Boxes created with [`CBox::new_in`] also record the [`CAllocator`] they were allocated on.
+///
+/// Boxes are never `Sync`, because opaque boxes can not tell whether the object they hold is.
+/// Objects that need to be shared across threads have to be placed in a [`CSyncBox`] instead.
 #[repr(C)]
 #[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
 pub struct CBox<'a, T: 'a> {
@@ -23,13 +30,16 @@ pub struct CBox<'a, T: 'a> {
     instance: NonNull<T>,
     #[cfg_attr(
         all(feature = "abi_stable10", not(feature = "abi_stable11")),
-        sabi(unsafe_change_type = "Option<unsafe extern \"C\" fn(&mut T)>")
+        sabi(
+            unsafe_change_type = "Option<unsafe extern \"C\" fn(&mut T, Option<&'static CAllocator>)>"
+        )
     )]
     #[cfg_attr(
         feature = "abi_stable11",
-        sabi(unsafe_change_type = Option<unsafe extern "C" fn(&mut T)>)
+        sabi(unsafe_change_type = Option<unsafe extern "C" fn(&mut T, Option<&'static CAllocator>)>)
     )]
-    drop_fn: Option<unsafe extern "C" fn(NonNull<T>)>,
+    drop_fn: Option<unsafe extern "C" fn(NonNull<T>, Option<&'static CAllocator>)>,
+    alloc: Option<&'static CAllocator>,
     #[cfg_attr(
         all(feature = "abi_stable10", not(feature = "abi_stable11")),
         sabi(unsafe_change_type = "::abi_stable::marker_type::UnsafeIgnoredType<()>")
@@ -42,15 +52,90 @@ pub struct CBox<'a, T: 'a> {
 }
 
 unsafe impl<'a, T: 'a> Send for CBox<'a, T> where &'a mut T: Send {}
-unsafe impl<'a, T: 'a> Sync for CBox<'a, T> where &'a mut T: Sync {}
 
 impl<T> super::trait_group::IntoInner for CBox<'_, T> {
     type InnerTarget = T;
 
     unsafe fn into_inner(self) -> Self::InnerTarget {
-        let b = Box::from_raw(self.instance.as_ptr());
-        std::mem::forget(self);
-        *b
+        let this = core::mem::ManuallyDrop::new(self);
+        match this.alloc {
+            Some(alloc) => {
+                let ret = this.instance.as_ptr().read();
+                alloc.deallocate(this.instance.cast(), CLayout::new::<T>());
+                ret
+            }
+            None => *Box::from_raw(this.instance.as_ptr()),
+        }
+    }
+}
+
+impl<T> CBox<'_, T> {
+    /// Create a new box on the given allocator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::alloc::CAllocator;
+    /// use cglue::boxed::CBox;
+    ///
+    /// let b = CBox::new_in(42u64, CAllocator::global());
+    ///
+    /// assert_eq!(*b, 42);
+    /// assert!(b.allocator().is_some());
+    /// ```
+    pub fn new_in(this: T, alloc: &'static CAllocator) -> Self {
+        let instance = alloc.allocate(CLayout::new::<T>()).cast::<T>();
+        unsafe { instance.as_ptr().write(this) };
+        Self {
+            instance,
+            drop_fn: Some(cglue_drop_box::<T>),
+            alloc: Some(alloc),
+            _phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Get the allocator the box was created on.
+    ///
+    /// Boxes converted from `Box<T>` record the global allocator of the module that converted
+    /// them. Returns `None` if the box was built without recording its allocator.
+    pub fn allocator(&self) -> Option<&'static CAllocator> {
+        self.alloc
+    }
+
+    /// Convert back into a `Box`.
+    ///
+    /// If the box was allocated on the global allocator of the current module, the memory gets
+    /// reused. Otherwise, the value gets moved into a new box, and the original memory is freed on
+    /// the recorded allocator.
+    ///
+    /// # Safety
+    ///
+    /// The box must either record its allocator, or have been allocated on the global allocator
+    /// of the current module. Boxes converted from `Box<T>` and [`CBox::new_in`] always record
+    /// it, but the ones built by foreign code may not.
+    ///
+    /// `T` must be the type the box was created with, which does not hold for opaque boxes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::alloc::CAllocator;
+    /// use cglue::boxed::CBox;
+    ///
+    /// let b = CBox::new_in(42u64, CAllocator::global());
+    ///
+    /// assert_eq!(*unsafe { b.into_box() }, 42);
+    /// ```
+    pub unsafe fn into_box(self) -> Box<T> {
+        match self.alloc {
+            Some(alloc) if !core::ptr::eq(alloc, CAllocator::global()) => {
+                Box::new(self.into_inner())
+            }
+            _ => {
+                let this = core::mem::ManuallyDrop::new(self);
+                Box::from_raw(this.instance.as_ptr())
+            }
+        }
     }
 }
 
@@ -74,6 +159,7 @@ impl<T> From<Box<T>> for CBox<'_, T> {
         Self {
             instance,
             drop_fn: Some(cglue_drop_box::<T>),
+            alloc: Some(CAllocator::global()),
             _phantom: core::marker::PhantomData,
         }
     }
@@ -97,18 +183,191 @@ impl<T> From<(T, NoContext)> for CBox<'_, T> {
 impl<T> Drop for CBox<'_, T> {
     fn drop(&mut self) {
         if let Some(drop_fn) = self.drop_fn.take() {
-            unsafe { drop_fn(self.instance) };
+            unsafe { drop_fn(self.instance, self.alloc) };
         }
     }
 }
 
-// FIXME: express both Send and !Send box safely (https://github.com/h33p/cglue/issues/18)
+// Opaque boxes are `Send`, objects that are not need to be placed in a `CUnsendBox` instead.
 unsafe impl<'a, T: Send> Opaquable for CBox<'a, T> {
     type OpaqueTarget = CBox<'a, c_void>;
 }
 
-unsafe extern "C" fn cglue_drop_box<T>(this: NonNull<T>) {
-    let _ = Box::from_raw(this.as_ptr());
+unsafe extern "C" fn cglue_drop_box<T>(this: NonNull<T>, alloc: Option<&'static CAllocator>) {
+    match alloc {
+        Some(alloc) => {
+            core::ptr::drop_in_place(this.as_ptr());
+            alloc.deallocate(this.cast(), CLayout::new::<T>());
+        }
+        None => {
+            let _ = Box::from_raw(this.as_ptr());
+        }
+    }
+}
+
+/// FFI-safe box of objects that are not `Send`
+///
+/// This is a [`CBox`] that stays neither `Send`, nor `Sync` after being opaqued. It allows to
+/// build CGlue objects out of types that can not be sent across threads, such as `Rc` based
+/// state, which opaque [`CBox`] does not accept.
+///
+/// # Examples
+///
+/// ```
+/// use cglue::boxed::CUnsendBox;
+/// use cglue::trait_group::Opaquable;
+/// use std::rc::Rc;
+///
+/// let b = CUnsendBox::from(Rc::new(42));
+/// assert_eq!(**b, 42);
+///
+/// let _opaque = b.into_opaque();
+/// ```
+///
+/// Such boxes can not be sent across threads:
+///
+/// ```compile_fail
+/// use cglue::boxed::CUnsendBox;
+///
+/// fn assert_send<T: Send>(_: T) {}
+///
+/// assert_send(CUnsendBox::from(0u8));
+/// ```
+#[repr(transparent)]
+#[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+pub struct CUnsendBox<'a, T: 'a> {
+    inner: CBox<'a, T>,
+    _phantom: core::marker::PhantomData<*mut ()>,
+}
+
+impl<T> super::trait_group::IntoInner for CUnsendBox<'_, T> {
+    type InnerTarget = T;
+
+    unsafe fn into_inner(self) -> Self::InnerTarget {
+        self.inner.into_inner()
+    }
+}
+
+impl<T> Deref for CUnsendBox<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for CUnsendBox<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<'a, T> From<CBox<'a, T>> for CUnsendBox<'a, T> {
+    fn from(inner: CBox<'a, T>) -> Self {
+        Self {
+            inner,
+            _phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<T> From<Box<T>> for CUnsendBox<'_, T> {
+    fn from(this: Box<T>) -> Self {
+        CBox::<T>::from(this).into()
+    }
+}
+
+impl<T> From<T> for CUnsendBox<'_, T> {
+    fn from(this: T) -> Self {
+        CBox::<T>::from(this).into()
+    }
+}
+
+unsafe impl<'a, T> Opaquable for CUnsendBox<'a, T> {
+    type OpaqueTarget = CUnsendBox<'a, c_void>;
+}
+
+/// FFI-safe box of objects that are both `Send` and `Sync`
+///
+/// Opaque [`CBox`] only requires the object to be `Send`. This box can only be built out of types
+/// that are also `Sync`, thus it stays both `Send` and `Sync` after being opaqued. It allows CGlue
+/// objects to be shared across threads.
+///
+/// # Examples
+///
+/// ```
+/// use cglue::boxed::CSyncBox;
+/// use cglue::trait_group::Opaquable;
+/// use std::sync::Arc;
+///
+/// fn assert_sync<T: Send + Sync>(_: &T) {}
+///
+/// let b = CSyncBox::from(Arc::new(42));
+/// assert_eq!(**b, 42);
+///
+/// let opaque = b.into_opaque();
+/// assert_sync(&opaque);
+/// ```
+///
+/// Types that are not `Sync` are rejected:
+///
+/// ```compile_fail
+/// use cglue::boxed::CSyncBox;
+/// use std::cell::Cell;
+///
+/// let _ = CSyncBox::from(Cell::new(0u8));
+/// ```
+#[repr(transparent)]
+#[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+pub struct CSyncBox<'a, T: 'a> {
+    inner: CBox<'a, T>,
+}
+
+// Boxes can only be built out of `Sync` types, thus opaque ones stay `Sync` as well.
+unsafe impl<'a, T: 'a> Sync for CSyncBox<'a, T> where &'a mut T: Sync {}
+
+impl<T> super::trait_group::IntoInner for CSyncBox<'_, T> {
+    type InnerTarget = T;
+
+    unsafe fn into_inner(self) -> Self::InnerTarget {
+        self.inner.into_inner()
+    }
+}
+
+impl<T> Deref for CSyncBox<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for CSyncBox<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<'a, T: Send + Sync> From<CBox<'a, T>> for CSyncBox<'a, T> {
+    fn from(inner: CBox<'a, T>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T: Send + Sync> From<Box<T>> for CSyncBox<'_, T> {
+    fn from(this: Box<T>) -> Self {
+        CBox::<T>::from(this).into()
+    }
+}
+
+impl<T: Send + Sync> From<T> for CSyncBox<'_, T> {
+    fn from(this: T) -> Self {
+        CBox::<T>::from(this).into()
+    }
+}
+
+unsafe impl<'a, T: Send + Sync> Opaquable for CSyncBox<'a, T> {
+    type OpaqueTarget = CSyncBox<'a, c_void>;
 }
 
 /// FFI-safe (unsized) boxed slice
@@ -116,11 +375,94 @@ unsafe extern "C" fn cglue_drop_box<T>(this: NonNull<T>) {
 /// This box has a static self reference, alongside a custom drop function.
 ///
 /// The drop function can be called from anywhere, it will free on correct allocator internally.
+/// Boxes created with [`CSliceBox::from_iter_in`] also record the [`CAllocator`] they were
+/// allocated on.
 #[repr(C)]
 #[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
 pub struct CSliceBox<'a, T: 'a> {
     instance: CSliceMut<'a, T>,
-    drop_fn: Option<unsafe extern "C" fn(&mut CSliceMut<'a, T>)>,
+    drop_fn: Option<unsafe extern "C" fn(&mut CSliceMut<'a, T>, Option<&'static CAllocator>)>,
+    alloc: Option<&'static CAllocator>,
+}
+
+impl<T> CSliceBox<'_, T> {
+    /// Collect an iterator into a boxed slice on the given allocator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::alloc::CAllocator;
+    /// use cglue::boxed::CSliceBox;
+    ///
+    /// let b = CSliceBox::from_iter_in(0..4u8, CAllocator::global());
+    ///
+    /// assert_eq!(&*b, &[0, 1, 2, 3]);
+    /// ```
+    pub fn from_iter_in<I: IntoIterator<Item = T>>(iter: I, alloc: &'static CAllocator) -> Self {
+        let mut vec = CVec::new_in(alloc);
+        vec.extend(iter);
+        vec.shrink_to_fit();
+
+        let mut vec = core::mem::ManuallyDrop::new(vec);
+        let len = vec.len();
+        let data = vec.as_mut_ptr();
+
+        Self {
+            instance: unsafe { core::slice::from_raw_parts_mut(data, len) }.into(),
+            drop_fn: Some(cglue_drop_slice_box::<T>),
+            alloc: Some(alloc),
+        }
+    }
+
+    /// Get the allocator the box was created on.
+    ///
+    /// Boxes converted from `Box<[T]>` record the global allocator of the module that converted
+    /// them. Returns `None` if the box was built without recording its allocator.
+    pub fn allocator(&self) -> Option<&'static CAllocator> {
+        self.alloc
+    }
+
+    /// Convert back into a boxed slice.
+    ///
+    /// If the box was allocated on the global allocator of the current module, the memory gets
+    /// reused. Otherwise, the elements get moved into a new boxed slice, and the original memory
+    /// is freed on the recorded allocator.
+    ///
+    /// # Safety
+    ///
+    /// The box must either record its allocator, or have been allocated on the global allocator
+    /// of the current module. Boxes converted from `Box<[T]>` and [`CSliceBox::from_iter_in`]
+    /// always record it, but the ones built by foreign code may not.
+    ///
+    /// `T` must be the type the box was created with, which does not hold for opaque boxes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::alloc::CAllocator;
+    /// use cglue::boxed::CSliceBox;
+    ///
+    /// let b = CSliceBox::from_iter_in(0..4u8, CAllocator::global());
+    ///
+    /// assert_eq!(&*unsafe { b.into_box() }, &[0, 1, 2, 3]);
+    /// ```
+    pub unsafe fn into_box(self) -> Box<[T]> {
+        let this = core::mem::ManuallyDrop::new(self);
+        let len = this.instance.len();
+        let data = this.instance.as_mut_ptr();
+        match this.alloc {
+            Some(alloc) if !core::ptr::eq(alloc, CAllocator::global()) => {
+                let mut ret = Vec::with_capacity(len);
+                core::ptr::copy_nonoverlapping(data, ret.as_mut_ptr(), len);
+                ret.set_len(len);
+                if let Some(layout) = CLayout::array::<T>(len) {
+                    alloc.deallocate(NonNull::new_unchecked(data).cast(), layout);
+                }
+                ret.into_boxed_slice()
+            }
+            _ => Box::from_raw(core::ptr::slice_from_raw_parts_mut(data, len)),
+        }
+    }
 }
 
 impl<T> Deref for CSliceBox<'_, T> {
@@ -143,6 +485,7 @@ impl<T> From<Box<[T]>> for CSliceBox<'_, T> {
         Self {
             instance,
             drop_fn: Some(cglue_drop_slice_box::<T>),
+            alloc: Some(CAllocator::global()),
         }
     }
 }
@@ -150,7 +493,7 @@ impl<T> From<Box<[T]>> for CSliceBox<'_, T> {
 impl<T> Drop for CSliceBox<'_, T> {
     fn drop(&mut self) {
         if let Some(drop_fn) = self.drop_fn.take() {
-            unsafe { drop_fn(&mut self.instance) };
+            unsafe { drop_fn(&mut self.instance, self.alloc) };
         }
     }
 }
@@ -159,9 +502,104 @@ unsafe impl<'a, T> Opaquable for CSliceBox<'a, T> {
     type OpaqueTarget = CSliceBox<'a, c_void>;
 }
 
-unsafe extern "C" fn cglue_drop_slice_box<T>(this: &mut CSliceMut<'_, T>) {
+unsafe extern "C" fn cglue_drop_slice_box<T>(
+    this: &mut CSliceMut<'_, T>,
+    alloc: Option<&'static CAllocator>,
+) {
     // SAFETY: we extend the lifetime of the reference but free the underlying data immediately and
     // not use the reference again.
     let extended_instance = (this as *mut CSliceMut<_>).as_mut().unwrap();
-    let _ = Box::from_raw(extended_instance.as_slice_mut());
+    let len = extended_instance.len();
+    let slice: *mut [T] = extended_instance.as_slice_mut();
+    match alloc {
+        Some(alloc) => {
+            core::ptr::drop_in_place(slice);
+            if let Some(layout) = CLayout::array::<T>(len) {
+                alloc.deallocate(NonNull::new_unchecked(slice as *mut u8), layout);
+            }
+        }
+        None => {
+            let _ = Box::from_raw(slice);
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for CBox<'_, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        (**self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for CBox<'_, T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Self::from)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for CUnsendBox<'_, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        (**self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for CUnsendBox<'_, T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Self::from)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for CSyncBox<'_, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        (**self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de> + Send + Sync> serde::Deserialize<'de> for CSyncBox<'_, T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Self::from)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for CSliceBox<'_, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(&**self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for CSliceBox<'_, T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Box::<[T]>::deserialize(deserializer).map(Self::from)
+    }
 }
@@ -12,8 +12,13 @@
 //! `OpaqueCallback`, as the name suggests, marks the `context` as opaque, casts it to `c_void`
 //! pointer. It allows the code not to care about what's behind the context, it just knows that it
 //! needs to pass it over to the callback.
+//!
+//! `OpaqueCallback` returns a `bool` that signifies whether more data should be fed. Callbacks
+//! with arbitrary return values are available as `OpaqueCallbackRet`. Owned callbacks, that can be
+//! stored, are available as `CBoxCallback`, and one-shot callbacks as `COnceCallback`.
 
-use crate::trait_group::c_void;
+use crate::boxed::CBox;
+use crate::trait_group::{c_void, IntoInner, Opaquable};
 use std::prelude::v1::*;
 
 // C style callbacks that are needed so that C code can easily use callback like functions
@@ -158,3 +163,189 @@ impl<T, F: FnMut(T) -> bool> Callbackable<T> for F {
         (*self)(data)
     }
 }
+
+/// Callback with an arbitrary return value.
+#[repr(C)]
+#[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+pub struct CallbackRet<'a, T: 'a, A, R> {
+    context: &'a mut T,
+    func: extern "C" fn(&mut T, A) -> R,
+}
+
+impl<'a, T, A, R> CallbackRet<'a, T, A, R> {
+    pub fn into_opaque(self) -> CallbackRet<'a, c_void, A, R> {
+        unsafe {
+            CallbackRet {
+                context: &mut *(self.context as *mut T as *mut c_void),
+                func: std::mem::transmute::<
+                    extern "C" fn(&mut T, A) -> R,
+                    extern "C" fn(&mut c_void, A) -> R,
+                >(self.func),
+            }
+        }
+    }
+
+    pub fn new(context: &'a mut T, func: extern "C" fn(&mut T, A) -> R) -> Self {
+        Self { context, func }
+    }
+}
+
+/// Opaque callback with an arbitrary return value.
+///
+/// # Examples
+///
+/// ```
+/// use cglue::callback::OpaqueCallbackRet;
+///
+/// extern "C" fn sum_mapped(mut cb: OpaqueCallbackRet<usize, usize>) -> usize {
+///     (0..4).map(|v| cb.call(v)).sum()
+/// }
+///
+/// let mut cnt = 0;
+/// let mut cb = |v| {
+///     cnt += 1;
+///     v * 2
+/// };
+///
+/// assert_eq!(sum_mapped((&mut cb).into()), 12);
+/// assert_eq!(cnt, 4);
+/// ```
+#[repr(transparent)]
+#[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+pub struct OpaqueCallbackRet<'a, A: 'a, R: 'a>(CallbackRet<'a, c_void, A, R>);
+
+impl<'a, A, R> OpaqueCallbackRet<'a, A, R> {
+    pub fn call(&mut self, arg: A) -> R {
+        (self.0.func)(self.0.context, arg)
+    }
+}
+
+impl<'a, T, A, R> From<CallbackRet<'a, T, A, R>> for OpaqueCallbackRet<'a, A, R> {
+    fn from(callback: CallbackRet<'a, T, A, R>) -> Self {
+        Self(callback.into_opaque())
+    }
+}
+
+impl<'a, T: FnMut(A) -> R, A, R> From<&'a mut T> for OpaqueCallbackRet<'a, A, R> {
+    fn from(func: &'a mut T) -> Self {
+        extern "C" fn callback<T: FnMut(A) -> R, A, R>(func: &mut T, arg: A) -> R {
+            func(arg)
+        }
+
+        CallbackRet {
+            context: func,
+            func: callback::<T, A, R>,
+        }
+        .into()
+    }
+}
+
+/// Owned callback.
+///
+/// Unlike [`OpaqueCallbackRet`], this callback owns its context, and drops it when it itself is
+/// dropped. Thus it can be stored and called at a later point.
+///
+/// # Examples
+///
+/// ```
+/// use cglue::callback::CBoxCallback;
+///
+/// let mut stored = vec![];
+///
+/// let offset = 10;
+/// stored.push(CBoxCallback::from(move |v: usize| v + offset));
+/// stored.push(CBoxCallback::from(|v: usize| v * 2));
+///
+/// let out = stored.iter_mut().map(|cb| cb.call(4)).collect::<Vec<_>>();
+///
+/// assert_eq!(out, [14, 8]);
+/// ```
+#[repr(C)]
+#[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+pub struct CBoxCallback<'a, A, R> {
+    context: CBox<'a, c_void>,
+    func: extern "C" fn(&mut c_void, A) -> R,
+}
+
+impl<'a, A, R> CBoxCallback<'a, A, R> {
+    pub fn new<T: FnMut(A) -> R + Send + 'a>(func: T) -> Self {
+        extern "C" fn callback<T: FnMut(A) -> R, A, R>(func: &mut T, arg: A) -> R {
+            func(arg)
+        }
+
+        Self {
+            context: CBox::from(func).into_opaque(),
+            func: unsafe {
+                std::mem::transmute::<
+                    extern "C" fn(&mut T, A) -> R,
+                    extern "C" fn(&mut c_void, A) -> R,
+                >(callback::<T, A, R>)
+            },
+        }
+    }
+
+    pub fn call(&mut self, arg: A) -> R {
+        (self.func)(&mut self.context, arg)
+    }
+}
+
+impl<'a, T: FnMut(A) -> R + Send + 'a, A, R> From<T> for CBoxCallback<'a, A, R> {
+    fn from(func: T) -> Self {
+        Self::new(func)
+    }
+}
+
+/// One-shot callback.
+///
+/// This callback owns its context, and is consumed upon calling. It is useful for completion
+/// handlers.
+///
+/// # Examples
+///
+/// ```
+/// use cglue::callback::COnceCallback;
+///
+/// extern "C" fn finish(done: COnceCallback<usize, ()>) {
+///     done.call(42);
+/// }
+///
+/// let (tx, rx) = std::sync::mpsc::channel();
+///
+/// finish((move |v| tx.send(v).unwrap()).into());
+///
+/// assert_eq!(rx.recv(), Ok(42));
+/// ```
+#[repr(C)]
+#[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+pub struct COnceCallback<'a, A, R> {
+    context: CBox<'a, c_void>,
+    func: extern "C" fn(CBox<'a, c_void>, A) -> R,
+}
+
+impl<'a, A, R> COnceCallback<'a, A, R> {
+    pub fn new<T: FnOnce(A) -> R + Send + 'a>(func: T) -> Self {
+        extern "C" fn callback<T: FnOnce(A) -> R, A, R>(func: CBox<T>, arg: A) -> R {
+            (unsafe { func.into_inner() })(arg)
+        }
+
+        Self {
+            context: CBox::from(func).into_opaque(),
+            func: unsafe {
+                std::mem::transmute::<
+                    extern "C" fn(CBox<'a, T>, A) -> R,
+                    extern "C" fn(CBox<'a, c_void>, A) -> R,
+                >(callback::<T, A, R>)
+            },
+        }
+    }
+
+    pub fn call(self, arg: A) -> R {
+        (self.func)(self.context, arg)
+    }
+}
+
+impl<'a, T: FnOnce(A) -> R + Send + 'a, A, R> From<T> for COnceCallback<'a, A, R> {
+    fn from(func: T) -> Self {
+        Self::new(func)
+    }
+}
@@ -0,0 +1,175 @@
+//! # FFI-safe small-buffer box.
+use crate::trait_group::c_void;
+use crate::trait_group::*;
+use core::mem::MaybeUninit;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+use std::boxed::Box;
+
+/// Inline storage of [`CInline`].
+///
+/// This is a byte buffer of `N` bytes, aligned to 8 bytes.
+#[repr(C, align(8))]
+#[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+#[cfg_attr(feature = "abi_stable", sabi(unsafe_opaque_fields))]
+pub struct CInlineStorage<const N: usize> {
+    data: [MaybeUninit<u8>; N],
+}
+
+impl<const N: usize> CInlineStorage<N> {
+    fn uninit() -> Self {
+        Self {
+            data: [MaybeUninit::uninit(); N],
+        }
+    }
+}
+
+/// FFI-safe small-buffer box
+///
+/// Values that fit into `N` bytes, and are aligned to at most 8 bytes, are stored inline, without
+/// any heap allocation. Larger values fall back to being boxed on the global allocator of the
+/// module that created the container.
+///
+/// Just like [`CBox`](crate::boxed::CBox), the container carries its own drop function, so it can
+/// be dropped from anywhere. This makes it usable as a trait object container in place of
+/// `CBox`, which is useful for small, frequently created objects.
+///
+/// # Examples
+///
+/// ```
+/// use cglue::inline::CInline;
+///
+/// let small = CInline::<_, 16>::from(42u64);
+/// assert!(small.is_inline());
+/// assert_eq!(*small, 42);
+///
+/// let large = CInline::<_, 16>::from([0u64; 4]);
+/// assert!(!large.is_inline());
+/// assert_eq!(*large, [0; 4]);
+/// ```
+#[repr(C)]
+#[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+pub struct CInline<'a, T: 'a, const N: usize> {
+    heap: Option<NonNull<T>>,
+    drop_fn: Option<unsafe extern "C" fn(NonNull<T>, bool)>,
+    storage: CInlineStorage<N>,
+    _phantom: core::marker::PhantomData<&'a mut T>,
+}
+
+unsafe impl<'a, T: 'a, const N: usize> Send for CInline<'a, T, N> where &'a mut T: Send {}
+unsafe impl<'a, T: 'a, const N: usize> Sync for CInline<'a, T, N> where &'a mut T: Sync {}
+
+impl<T, const N: usize> CInline<'_, T, N> {
+    /// Check whether values of type `T` get stored inline.
+    pub const fn fits_inline() -> bool {
+        core::mem::size_of::<T>() <= N
+            && core::mem::align_of::<T>() <= core::mem::align_of::<CInlineStorage<N>>()
+    }
+
+    /// Check whether the value is stored inline.
+    pub fn is_inline(&self) -> bool {
+        self.heap.is_none()
+    }
+
+    fn instance(&self) -> NonNull<T> {
+        match self.heap {
+            Some(heap) => heap,
+            None => unsafe { NonNull::new_unchecked(self.storage.data.as_ptr() as *mut T) },
+        }
+    }
+}
+
+impl<T, const N: usize> super::trait_group::IntoInner for CInline<'_, T, N> {
+    type InnerTarget = T;
+
+    unsafe fn into_inner(self) -> Self::InnerTarget {
+        let this = core::mem::ManuallyDrop::new(self);
+        match this.heap {
+            Some(heap) => *Box::from_raw(heap.as_ptr()),
+            None => this.instance().as_ptr().read(),
+        }
+    }
+}
+
+impl<T, const N: usize> Deref for CInline<'_, T, N> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.instance().as_ptr() }
+    }
+}
+
+impl<T, const N: usize> DerefMut for CInline<'_, T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.instance().as_ptr() }
+    }
+}
+
+impl<T, const N: usize> From<T> for CInline<'_, T, N> {
+    fn from(this: T) -> Self {
+        let mut storage = CInlineStorage::uninit();
+
+        let heap = if Self::fits_inline() {
+            unsafe { (storage.data.as_mut_ptr() as *mut T).write(this) };
+            None
+        } else {
+            Some(unsafe { NonNull::new_unchecked(Box::into_raw(Box::new(this))) })
+        };
+
+        Self {
+            heap,
+            drop_fn: Some(cglue_drop_inline::<T>),
+            storage,
+            _phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<T, const N: usize> From<(T, NoContext)> for CInline<'_, T, N> {
+    fn from((this, _): (T, NoContext)) -> Self {
+        Self::from(this)
+    }
+}
+
+impl<T, const N: usize> Drop for CInline<'_, T, N> {
+    fn drop(&mut self) {
+        if let Some(drop_fn) = self.drop_fn.take() {
+            unsafe { drop_fn(self.instance(), self.heap.is_some()) };
+        }
+    }
+}
+
+// Opaque inline boxes are `Send`, thus only `Send` objects can be placed in them.
+unsafe impl<'a, T: Send, const N: usize> Opaquable for CInline<'a, T, N> {
+    type OpaqueTarget = CInline<'a, c_void, N>;
+}
+
+unsafe extern "C" fn cglue_drop_inline<T>(this: NonNull<T>, boxed: bool) {
+    if boxed {
+        let _ = Box::from_raw(this.as_ptr());
+    } else {
+        core::ptr::drop_in_place(this.as_ptr());
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize, const N: usize> serde::Serialize for CInline<'_, T, N> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        (**self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>, const N: usize> serde::Deserialize<'de>
+    for CInline<'_, T, N>
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Self::from)
+    }
+}
@@ -1,8 +1,27 @@
 //! # FFI compatible iterators
 //!
 //! It is a simple interface that allows to pass streams into functions.
+//!
+//! There are 4 kinds of iterators:
+//!
+//! - [`CIterator`], which borrows an iterator mutably.
+//! - [`CIntoIter`], which owns a boxed iterator, and can be returned from functions.
+//! - [`CTryIterator`], which borrows an iterator that yields `Result`s.
+//! - [`CLendingIterator`], which borrows an iterator, whose items borrow from the iterator.
+//!
+//! All of them contain optional `next_back` and `size_hint` functions. They are filled in when
+//! the iterator is constructed with `new_double_ended`, or in case of `size_hint` - always when
+//! constructed from Rust. Iterators with `next_back` can be turned into [`CDoubleEnded`], which
+//! implements `DoubleEndedIterator`. Iterators, whose `size_hint` reports an exact length, can be
+//! turned into [`CExactSize`], which implements `ExactSizeIterator`. The lending iterator only
+//! supports forward iteration.
 
-use crate::trait_group::c_void;
+use crate::boxed::CBox;
+use crate::option::COption;
+use crate::result::CResult;
+use crate::slice::{CSliceMut, CSliceRef};
+use crate::trait_group::{c_void, Opaquable};
+use core::marker::PhantomData;
 use core::mem::MaybeUninit;
 use std::prelude::v1::*;
 
@@ -12,6 +31,9 @@ use std::prelude::v1::*;
 ///
 /// `CIterator<T>` implements `Iterator<Item = T>`.
 ///
+/// Iterators built with `new_double_ended` can be iterated from the back after being converted
+/// into [`CDoubleEnded`].
+///
 /// # Examples
 ///
 /// Using [`AsCIterator`](AsCIterator) helper:
@@ -41,11 +63,28 @@ use std::prelude::v1::*;
 ///
 /// assert_eq!(sum_all((&mut iter).into()), 385);
 /// ```
+///
+/// Double-ended iteration:
+///
+/// ```
+/// use cglue::iter::CIterator;
+///
+/// let mut iter = 0..4;
+/// let citer = CIterator::new_double_ended(&mut iter);
+///
+/// assert_eq!(citer.size_hint(), (4, Some(4)));
+///
+/// let citer = citer.into_double_ended().ok().unwrap();
+///
+/// assert_eq!(citer.rev().collect::<Vec<_>>(), [3, 2, 1, 0]);
+/// ```
 #[repr(C)]
 #[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
 pub struct CIterator<'a, T> {
     iter: &'a mut c_void,
     func: extern "C" fn(&mut c_void, out: &mut MaybeUninit<T>) -> i32,
+    next_back: Option<extern "C" fn(&mut c_void, out: &mut MaybeUninit<T>) -> i32>,
+    size_hint: Option<extern "C" fn(&c_void, upper: &mut COption<usize>) -> usize>,
 }
 
 impl<'a, I: Iterator<Item = T>, T> From<&'a mut I> for CIterator<'a, T> {
@@ -56,26 +95,43 @@ impl<'a, I: Iterator<Item = T>, T> From<&'a mut I> for CIterator<'a, T> {
 
 impl<'a, T> CIterator<'a, T> {
     pub fn new<I: Iterator<Item = T>>(iter: &'a mut I) -> Self {
-        extern "C" fn func<I: Iterator<Item = T>, T>(
-            iter: &mut I,
-            out: &mut MaybeUninit<T>,
-        ) -> i32 {
-            match iter.next() {
-                Some(e) => {
-                    unsafe { out.as_mut_ptr().write(e) };
-                    0
-                }
-                None => 1,
-            }
-        }
-
         // SAFETY: type erasure is safe here, because the values are encapsulated and always in
         // a pair.
-        let iter = unsafe { (iter as *mut _ as *mut c_void).as_mut().unwrap() };
-        let func = func::<I, T> as extern "C" fn(_, _) -> _;
-        let func = unsafe { std::mem::transmute::<_, _>(func) };
+        let iter = unsafe { (iter as *mut I as *mut c_void).as_mut().unwrap() };
 
-        Self { iter, func }
+        Self {
+            iter,
+            func: unsafe { erase_next(cglue_iter_next::<I, T>) },
+            next_back: None,
+            size_hint: Some(unsafe { erase_size_hint(cglue_iter_size_hint::<I>) }),
+        }
+    }
+
+    /// Create a new iterator that can also be iterated from the back.
+    pub fn new_double_ended<I: DoubleEndedIterator<Item = T>>(iter: &'a mut I) -> Self {
+        Self {
+            next_back: Some(unsafe { erase_next(cglue_iter_next_back::<I, T>) }),
+            ..Self::new(iter)
+        }
+    }
+
+    /// Check whether the iterator supports iterating from the back.
+    pub fn is_double_ended(&self) -> bool {
+        self.next_back.is_some()
+    }
+
+    /// Convert into a double-ended iterator.
+    ///
+    /// The iterator is returned back, if it does not support iterating from the back.
+    pub fn into_double_ended(self) -> Result<CDoubleEnded<Self>, Self> {
+        CDoubleEnded::new(self)
+    }
+
+    /// Convert into an exact size iterator.
+    ///
+    /// The iterator is returned back, if its `size_hint` does not report an exact length.
+    pub fn into_exact_size(self) -> Result<CExactSize<Self>, Self> {
+        CExactSize::new(self)
     }
 }
 
@@ -83,12 +139,22 @@ impl<'a, T> Iterator for CIterator<'a, T> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut out = MaybeUninit::uninit();
-        if (self.func)(self.iter, &mut out) == 0 {
-            Some(unsafe { out.assume_init() })
-        } else {
-            None
-        }
+        iter_next(self.func, self.iter)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        iter_size_hint(self.size_hint, self.iter)
+    }
+}
+
+impl<'a, T> DoubleEnded for CIterator<'a, T> {
+    fn is_double_ended(&self) -> bool {
+        CIterator::is_double_ended(self)
+    }
+
+    fn try_next_back(&mut self) -> Option<Self::Item> {
+        let next_back = self.next_back?;
+        iter_next(next_back, self.iter)
     }
 }
 
@@ -99,3 +165,590 @@ pub trait AsCIterator: Iterator + Sized {
 }
 
 impl<T: Iterator> AsCIterator for T {}
+
+/// Owned FFI compatible iterator.
+///
+/// Unlike [`CIterator`], this type owns a boxed iterator, thus it can be returned from functions.
+///
+/// # Examples
+///
+/// ```
+/// use cglue::iter::CIntoIter;
+///
+/// extern "C" fn squares(count: usize) -> CIntoIter<'static, usize> {
+///     CIntoIter::new((0..count).map(|v| v * v))
+/// }
+///
+/// let iter = squares(4);
+///
+/// assert_eq!(iter.size_hint(), (4, Some(4)));
+/// assert_eq!(iter.collect::<Vec<_>>(), [0, 1, 4, 9]);
+/// ```
+#[repr(C)]
+#[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+pub struct CIntoIter<'a, T> {
+    iter: CBox<'a, c_void>,
+    func: extern "C" fn(&mut c_void, out: &mut MaybeUninit<T>) -> i32,
+    next_back: Option<extern "C" fn(&mut c_void, out: &mut MaybeUninit<T>) -> i32>,
+    size_hint: Option<extern "C" fn(&c_void, upper: &mut COption<usize>) -> usize>,
+    // The iterator may not be `Sync`.
+    _phantom: PhantomData<*const T>,
+}
+
+unsafe impl<T: Send> Send for CIntoIter<'_, T> {}
+
+impl<'a, T> CIntoIter<'a, T> {
+    pub fn new<I: Iterator<Item = T> + Send + 'a>(iter: I) -> Self {
+        Self {
+            iter: CBox::from(iter).into_opaque(),
+            func: unsafe { erase_next(cglue_iter_next::<I, T>) },
+            next_back: None,
+            size_hint: Some(unsafe { erase_size_hint(cglue_iter_size_hint::<I>) }),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Create a new owned iterator that can also be iterated from the back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::iter::CIntoIter;
+    ///
+    /// let iter = CIntoIter::new_double_ended(vec![1, 2, 3].into_iter());
+    /// let iter = iter.into_double_ended().ok().unwrap();
+    ///
+    /// assert_eq!(iter.rev().collect::<Vec<_>>(), [3, 2, 1]);
+    /// ```
+    pub fn new_double_ended<I: DoubleEndedIterator<Item = T> + Send + 'a>(iter: I) -> Self {
+        Self {
+            next_back: Some(unsafe { erase_next(cglue_iter_next_back::<I, T>) }),
+            ..Self::new(iter)
+        }
+    }
+
+    /// Check whether the iterator supports iterating from the back.
+    pub fn is_double_ended(&self) -> bool {
+        self.next_back.is_some()
+    }
+
+    /// Convert into a double-ended iterator.
+    ///
+    /// The iterator is returned back, if it does not support iterating from the back.
+    pub fn into_double_ended(self) -> Result<CDoubleEnded<Self>, Self> {
+        CDoubleEnded::new(self)
+    }
+
+    /// Convert into an exact size iterator.
+    ///
+    /// The iterator is returned back, if its `size_hint` does not report an exact length.
+    pub fn into_exact_size(self) -> Result<CExactSize<Self>, Self> {
+        CExactSize::new(self)
+    }
+
+    /// Borrow the iterator as a [`CIterator`].
+    pub fn as_citer(&mut self) -> CIterator<'_, T> {
+        CIterator {
+            iter: &mut self.iter,
+            func: self.func,
+            next_back: self.next_back,
+            size_hint: self.size_hint,
+        }
+    }
+}
+
+impl<'a, T> Iterator for CIntoIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        iter_next(self.func, &mut self.iter)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        iter_size_hint(self.size_hint, &self.iter)
+    }
+}
+
+impl<'a, T> DoubleEnded for CIntoIter<'a, T> {
+    fn is_double_ended(&self) -> bool {
+        CIntoIter::is_double_ended(self)
+    }
+
+    fn try_next_back(&mut self) -> Option<Self::Item> {
+        let next_back = self.next_back?;
+        iter_next(next_back, &mut self.iter)
+    }
+}
+
+/// FFI compatible fallible iterator.
+///
+/// This is a [`CIterator`] that yields `Result<T, E>`, where the elements are passed through
+/// [`CResult`].
+///
+/// Errors implementing [`IntError`](crate::result::IntError) can be passed as compact
+/// `NonZeroI32` values.
+///
+/// # Examples
+///
+/// ```
+/// use cglue::iter::CTryIterator;
+/// use cglue::result::IntError;
+/// use core::num::NonZeroI32;
+///
+/// extern "C" fn sum_all(iter: CTryIterator<usize, NonZeroI32>) -> i32 {
+///     match iter.sum::<Result<usize, _>>() {
+///         Ok(_) => 0,
+///         Err(e) => e.get(),
+///     }
+/// }
+///
+/// let mut iter = vec![Ok(1), Err(()), Ok(2)]
+///     .into_iter()
+///     .map(|v| v.map_err(IntError::into_int_err));
+///
+/// assert_eq!(sum_all((&mut iter).into()), 1);
+/// ```
+#[repr(transparent)]
+#[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+pub struct CTryIterator<'a, T, E> {
+    iter: CIterator<'a, CResult<T, E>>,
+}
+
+impl<'a, I: Iterator<Item = Result<T, E>>, T, E> From<&'a mut I> for CTryIterator<'a, T, E> {
+    fn from(iter: &'a mut I) -> Self {
+        CTryIterator::new(iter)
+    }
+}
+
+impl<'a, T, E> CTryIterator<'a, T, E> {
+    pub fn new<I: Iterator<Item = Result<T, E>>>(iter: &'a mut I) -> Self {
+        // SAFETY: CResult and Result are interchangeable as return values, thus we can type
+        // erase the iterator with CResult, and convert the results inside the functions.
+        let iter = unsafe { (iter as *mut I as *mut c_void).as_mut().unwrap() };
+
+        Self {
+            iter: CIterator {
+                iter,
+                func: unsafe { erase_next(cglue_try_iter_next::<I, T, E>) },
+                next_back: None,
+                size_hint: Some(unsafe { erase_size_hint(cglue_iter_size_hint::<I>) }),
+            },
+        }
+    }
+
+    /// Create a new fallible iterator that can also be iterated from the back.
+    pub fn new_double_ended<I: DoubleEndedIterator<Item = Result<T, E>>>(iter: &'a mut I) -> Self {
+        let mut ret = Self::new(iter);
+        ret.iter.next_back = Some(unsafe { erase_next(cglue_try_iter_next_back::<I, T, E>) });
+        ret
+    }
+
+    /// Check whether the iterator supports iterating from the back.
+    pub fn is_double_ended(&self) -> bool {
+        self.iter.is_double_ended()
+    }
+
+    /// Convert into a double-ended iterator.
+    ///
+    /// The iterator is returned back, if it does not support iterating from the back.
+    pub fn into_double_ended(self) -> Result<CDoubleEnded<Self>, Self> {
+        CDoubleEnded::new(self)
+    }
+
+    /// Convert into an exact size iterator.
+    ///
+    /// The iterator is returned back, if its `size_hint` does not report an exact length.
+    pub fn into_exact_size(self) -> Result<CExactSize<Self>, Self> {
+        CExactSize::new(self)
+    }
+}
+
+impl<'a, T, E> Iterator for CTryIterator<'a, T, E> {
+    type Item = Result<T, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(<_>::into)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T, E> DoubleEnded for CTryIterator<'a, T, E> {
+    fn is_double_ended(&self) -> bool {
+        CTryIterator::is_double_ended(self)
+    }
+
+    fn try_next_back(&mut self) -> Option<Self::Item> {
+        self.iter.try_next_back().map(<_>::into)
+    }
+}
+
+/// Iterators that may support iterating from the back.
+///
+/// This is implemented by [`CIterator`], [`CIntoIter`] and [`CTryIterator`], whose function to
+/// iterate from the back is optional.
+pub trait DoubleEnded: Iterator {
+    /// Check whether the iterator supports iterating from the back.
+    fn is_double_ended(&self) -> bool;
+
+    /// Get the next element from the back.
+    ///
+    /// Returns `None` if the iterator does not support iterating from the back.
+    fn try_next_back(&mut self) -> Option<Self::Item>;
+}
+
+/// Double-ended FFI compatible iterator.
+///
+/// This wraps an iterator that has been verified to support iterating from the back, and
+/// implements `DoubleEndedIterator` on top of it. It is built with `into_double_ended`.
+///
+/// # Examples
+///
+/// ```
+/// use cglue::iter::CIterator;
+///
+/// let mut iter = 0..4;
+///
+/// assert!(CIterator::new(&mut iter).into_double_ended().is_err());
+///
+/// let citer = CIterator::new_double_ended(&mut iter);
+/// let mut citer = citer.into_double_ended().ok().unwrap();
+///
+/// assert_eq!(citer.next_back(), Some(3));
+/// assert_eq!(citer.next(), Some(0));
+/// ```
+#[repr(transparent)]
+pub struct CDoubleEnded<I> {
+    iter: I,
+}
+
+impl<I: DoubleEnded> CDoubleEnded<I> {
+    /// Wrap the iterator, if it supports iterating from the back.
+    ///
+    /// Otherwise, the iterator is returned back.
+    pub fn new(iter: I) -> Result<Self, I> {
+        if iter.is_double_ended() {
+            Ok(Self { iter })
+        } else {
+            Err(iter)
+        }
+    }
+
+    /// Get the underlying iterator back.
+    pub fn into_inner(self) -> I {
+        self.iter
+    }
+}
+
+impl<I: DoubleEnded> Iterator for CDoubleEnded<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: DoubleEnded> DoubleEndedIterator for CDoubleEnded<I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.try_next_back()
+    }
+}
+
+/// Exact size FFI compatible iterator.
+///
+/// This wraps an iterator, whose `size_hint` has been verified to report an exact length, and
+/// implements `ExactSizeIterator` on top of it. It is built with `into_exact_size`.
+///
+/// # Examples
+///
+/// ```
+/// use cglue::iter::CIterator;
+///
+/// let mut iter = (0..4).filter(|_| true);
+///
+/// assert!(CIterator::new(&mut iter).into_exact_size().is_err());
+///
+/// let mut iter = 0..4;
+///
+/// let citer = CIterator::new_double_ended(&mut iter);
+/// let citer = citer.into_exact_size().ok().unwrap();
+///
+/// assert_eq!(citer.len(), 4);
+/// assert_eq!(citer.rev().collect::<Vec<_>>(), [3, 2, 1, 0]);
+/// ```
+#[repr(transparent)]
+pub struct CExactSize<I> {
+    iter: I,
+}
+
+impl<I: Iterator> CExactSize<I> {
+    /// Wrap the iterator, if its `size_hint` reports an exact length.
+    ///
+    /// Otherwise, the iterator is returned back.
+    pub fn new(iter: I) -> Result<Self, I> {
+        match iter.size_hint() {
+            (lower, Some(upper)) if lower == upper => Ok(Self { iter }),
+            _ => Err(iter),
+        }
+    }
+
+    /// Get the underlying iterator back.
+    pub fn into_inner(self) -> I {
+        self.iter
+    }
+}
+
+impl<I: Iterator> Iterator for CExactSize<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: Iterator> ExactSizeIterator for CExactSize<I> {}
+
+/// Iteration from the back, if the underlying iterator supports it.
+impl<I: DoubleEnded> DoubleEndedIterator for CExactSize<I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.try_next_back()
+    }
+}
+
+/// Item of a [`CLendingIterator`].
+///
+/// This trait is implemented on the `'static` form of the item, and `Lent` is the same type with
+/// its lifetime shortened to `'a`. This allows to express lending iterators without generic
+/// associated types.
+///
+/// # Safety
+///
+/// `Lent` must be the same type as `Self`, with the only difference being the lifetime.
+pub unsafe trait LendingItem<'a> {
+    type Lent: 'a;
+}
+
+unsafe impl<'a, T: 'static> LendingItem<'a> for CSliceRef<'static, T> {
+    type Lent = CSliceRef<'a, T>;
+}
+
+unsafe impl<'a, T: 'static> LendingItem<'a> for CSliceMut<'static, T> {
+    type Lent = CSliceMut<'a, T>;
+}
+
+unsafe impl<'a, T: ?Sized + 'static> LendingItem<'a> for &'static T {
+    type Lent = &'a T;
+}
+
+unsafe impl<'a, T: ?Sized + 'static> LendingItem<'a> for &'static mut T {
+    type Lent = &'a mut T;
+}
+
+/// Iterator, whose items borrow from the iterator itself.
+///
+/// Every item is only valid until the next call to `next`.
+pub trait LendingIterator {
+    type Item: for<'b> LendingItem<'b>;
+
+    fn next(&mut self) -> Option<<Self::Item as LendingItem<'_>>::Lent>;
+}
+
+/// FFI compatible lending iterator.
+///
+/// Items yielded by this iterator borrow from the underlying iterator, and are only valid until
+/// the next call to `next`. `T` is the `'static` form of the item, for instance
+/// `CSliceRef<'static, u8>` produces `CSliceRef<'_, u8>` items.
+///
+/// Any mutable reference to a [`LendingIterator`] can be converted to a `CLendingIterator`.
+///
+/// # Examples
+///
+/// ```
+/// use cglue::iter::{CLendingIterator, LendingItem, LendingIterator};
+/// use cglue::slice::CSliceRef;
+///
+/// struct Chunks {
+///     buf: [u8; 4],
+///     cnt: u8,
+/// }
+///
+/// impl LendingIterator for Chunks {
+///     type Item = CSliceRef<'static, u8>;
+///
+///     fn next(&mut self) -> Option<CSliceRef<'_, u8>> {
+///         if self.cnt == 3 {
+///             return None;
+///         }
+///         self.cnt += 1;
+///         self.buf = [self.cnt; 4];
+///         Some((&self.buf[..]).into())
+///     }
+/// }
+///
+/// extern "C" fn sum_all(mut iter: CLendingIterator<CSliceRef<'static, u8>>) -> usize {
+///     let mut sum = 0;
+///     while let Some(chunk) = iter.next() {
+///         sum += chunk.iter().map(|&v| v as usize).sum::<usize>();
+///     }
+///     sum
+/// }
+///
+/// let mut chunks = Chunks { buf: [0; 4], cnt: 0 };
+///
+/// assert_eq!(sum_all((&mut chunks).into()), 24);
+/// ```
+#[repr(C)]
+#[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+pub struct CLendingIterator<'a, T> {
+    iter: &'a mut c_void,
+    func: extern "C" fn(&mut c_void, out: &mut MaybeUninit<T>) -> i32,
+}
+
+impl<'a, I: LendingIterator<Item = T>, T: for<'b> LendingItem<'b>> From<&'a mut I>
+    for CLendingIterator<'a, T>
+{
+    fn from(iter: &'a mut I) -> Self {
+        CLendingIterator::new(iter)
+    }
+}
+
+impl<'a, T: for<'b> LendingItem<'b>> CLendingIterator<'a, T> {
+    pub fn new<I: LendingIterator<Item = T>>(iter: &'a mut I) -> Self {
+        // SAFETY: type erasure is safe here, because the values are encapsulated and always in
+        // a pair.
+        let iter = unsafe { (iter as *mut I as *mut c_void).as_mut().unwrap() };
+
+        Self {
+            iter,
+            func: unsafe { erase_next(cglue_lending_iter_next::<I, T>) },
+        }
+    }
+
+    /// Get the next item.
+    ///
+    /// The item borrows the iterator, thus it has to be released before calling `next` again.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<<T as LendingItem<'_>>::Lent> {
+        // SAFETY: the item is tied to the lifetime of the mutable borrow, which is how long it
+        // stays valid.
+        iter_next(self.func, self.iter).map(|v| unsafe { lend::<T>(v) })
+    }
+}
+
+impl<'a, T: for<'b> LendingItem<'b>> LendingIterator for CLendingIterator<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<<T as LendingItem<'_>>::Lent> {
+        CLendingIterator::next(self)
+    }
+}
+
+unsafe fn lend<'a, T: LendingItem<'a>>(val: T) -> T::Lent {
+    let val = core::mem::ManuallyDrop::new(val);
+    (&*val as *const T as *const T::Lent).read()
+}
+
+unsafe fn unlend<'a, T: LendingItem<'a>>(val: T::Lent) -> T {
+    let val = core::mem::ManuallyDrop::new(val);
+    (&*val as *const T::Lent as *const T).read()
+}
+
+fn iter_next<T>(
+    func: extern "C" fn(&mut c_void, out: &mut MaybeUninit<T>) -> i32,
+    iter: &mut c_void,
+) -> Option<T> {
+    let mut out = MaybeUninit::uninit();
+    if func(iter, &mut out) == 0 {
+        Some(unsafe { out.assume_init() })
+    } else {
+        None
+    }
+}
+
+fn iter_size_hint(
+    func: Option<extern "C" fn(&c_void, upper: &mut COption<usize>) -> usize>,
+    iter: &c_void,
+) -> (usize, Option<usize>) {
+    match func {
+        Some(func) => {
+            let mut upper = COption::None;
+            let lower = func(iter, &mut upper);
+            (lower, upper.into())
+        }
+        None => (0, None),
+    }
+}
+
+unsafe fn erase_next<I, T>(
+    func: extern "C" fn(&mut I, out: &mut MaybeUninit<T>) -> i32,
+) -> extern "C" fn(&mut c_void, out: &mut MaybeUninit<T>) -> i32 {
+    core::mem::transmute(func)
+}
+
+unsafe fn erase_size_hint<I>(
+    func: extern "C" fn(&I, upper: &mut COption<usize>) -> usize,
+) -> extern "C" fn(&c_void, upper: &mut COption<usize>) -> usize {
+    core::mem::transmute(func)
+}
+
+fn write_next<T>(val: Option<T>, out: &mut MaybeUninit<T>) -> i32 {
+    match val {
+        Some(e) => {
+            unsafe { out.as_mut_ptr().write(e) };
+            0
+        }
+        None => 1,
+    }
+}
+
+extern "C" fn cglue_iter_next<I: Iterator<Item = T>, T>(
+    iter: &mut I,
+    out: &mut MaybeUninit<T>,
+) -> i32 {
+    write_next(iter.next(), out)
+}
+
+extern "C" fn cglue_iter_next_back<I: DoubleEndedIterator<Item = T>, T>(
+    iter: &mut I,
+    out: &mut MaybeUninit<T>,
+) -> i32 {
+    write_next(iter.next_back(), out)
+}
+
+extern "C" fn cglue_iter_size_hint<I: Iterator>(iter: &I, upper: &mut COption<usize>) -> usize {
+    let (lower, up) = iter.size_hint();
+    *upper = up.into();
+    lower
+}
+
+extern "C" fn cglue_try_iter_next<I: Iterator<Item = Result<T, E>>, T, E>(
+    iter: &mut I,
+    out: &mut MaybeUninit<CResult<T, E>>,
+) -> i32 {
+    write_next(iter.next().map(<_>::into), out)
+}
+
+extern "C" fn cglue_try_iter_next_back<I: DoubleEndedIterator<Item = Result<T, E>>, T, E>(
+    iter: &mut I,
+    out: &mut MaybeUninit<CResult<T, E>>,
+) -> i32 {
+    write_next(iter.next_back().map(<_>::into), out)
+}
+
+extern "C" fn cglue_lending_iter_next<I: LendingIterator<Item = T>, T: for<'b> LendingItem<'b>>(
+    iter: &mut I,
+    out: &mut MaybeUninit<T>,
+) -> i32 {
+    // SAFETY: the caller is bound to the lifetime of the iterator borrow.
+    write_next(iter.next().map(|v| unsafe { unlend::<T>(v) }), out)
+}
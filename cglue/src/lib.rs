@@ -25,11 +25,17 @@
 //!   - [Name generation](#name-generation)
 //!   - [Generics in groups](#generics-in-groups)
 //!     - [Manully implementing groups](#manually-implementing-groups)
+//!   - [Derived groups](#derived-groups)
+//!   - [Regrouping](#regrouping)
+//!   - [Automatic trait detection](#automatic-trait-detection)
+//!   - [Shared vtable tables](#shared-vtable-tables)
+//!   - [Thread safety](#thread-safety)
 //!   - [External traits](#external-traits)
 //!   - [Type wrapping](#type-wrapping)
 //!   - [Associated type wrapping](#associated-type-wrapping)
 //!   - [Generic associated types](#generic-associated-types)
 //!   - [Plugin system](#plugin-system)
+//!     - [Loading plugins](#loading-plugins)
 //!   - [Working with cbindgen](#working-with-cbindgen)
 //!     - [Setup](#setup)
 //!     - [cglue-bindgen](#cglue-bindgen)
@@ -163,6 +169,51 @@
 //! }
 //! ```
 //!
+//! Consuming casts, such as `cast!` and `into!`, lose the object if it does not implement the
+//! requested traits. `try_into!` gives the original object back instead, allowing to try multiple
+//! sets of traits in order:
+//!
+//! ```
+//! # __export_abi_stable!();
+//! # use cglue::*;
+//! # #[cglue_trait]
+//! # pub trait InfoPrinter {
+//! #     fn print_info(&self);
+//! # }
+//! # struct Info {
+//! #     value: usize
+//! # }
+//! # impl InfoPrinter for Info {
+//! #     fn print_info(&self) {
+//! #         println!("Info struct: {}", self.value);
+//! #     }
+//! # }
+//! # #[cglue_trait]
+//! # pub trait InfoChanger {
+//! #     fn change_info(&mut self, new_val: usize);
+//! # }
+//! # impl InfoChanger for Info {
+//! #     fn change_info(&mut self, new_val: usize) {
+//! #         self.value = new_val;
+//! #     }
+//! # }
+//! # #[cglue_trait]
+//! # pub trait InfoDeleter {
+//! #     fn delete_info(&mut self);
+//! # }
+//! # cglue_trait_group!(InfoGroup, InfoPrinter, { InfoChanger, InfoDeleter });
+//! # #[cfg(not(feature = "unstable"))]
+//! # cglue_impl_group!(Info, InfoGroup, InfoChanger);
+//! # fn main() {
+//! let obj = group_obj!(Info { value: 5 } as InfoGroup);
+//!
+//! let obj = try_into!(obj impl InfoChanger + InfoDeleter).err().unwrap();
+//! let mut changer = try_into!(obj impl InfoChanger).ok().unwrap();
+//!
+//! changer.change_info(20);
+//! # }
+//! ```
+//!
 //! And there is much more! Here are some highlights:
 //!
 //! 1. Ability to use self-consuming trait functions.
@@ -206,6 +257,12 @@
 //! | `MyTraitBox` | Regular owned CGlue object. | [`CBox<c_void>`](crate::boxed::CBox) | [`NoContext`](crate::trait_group::NoContext) |
 //! | `MyTraitCtxBox<Ctx>` | Owned CGlue object with a [context](#plugin-system). | [`CBox<c_void>`](crate::boxed::CBox) | `Ctx` |
 //! | `MyTraitArcBox` | Owned CGlue object with a reference counted context. | [`CBox<c_void>`](crate::boxed::CBox) | [`CArc<c_void>`](crate::arc::CArc) |
+//! | `MyTraitUnsendBox` | Owned CGlue object that may hold objects that are not `Send`. | [`CUnsendBox<c_void>`](crate::boxed::CUnsendBox) | [`NoContext`](crate::trait_group::NoContext) |
+//! | `MyTraitCtxUnsendBox<Ctx>` | Owned CGlue object with a context that is not `Send`. | [`CUnsendBox<c_void>`](crate::boxed::CUnsendBox) | `Ctx` |
+//! | `MyTraitArcUnsendBox` | Owned CGlue object with a reference counted context that is not `Send`. | [`CUnsendBox<c_void>`](crate::boxed::CUnsendBox) | [`CArc<c_void>`](crate::arc::CArc) |
+//! | `MyTraitInline<N>` | Owned CGlue object stored in a small inline buffer. | [`CInline<c_void, N>`](crate::inline::CInline) | [`NoContext`](crate::trait_group::NoContext) |
+//! | `MyTraitCtxInline<Ctx, N>` | Inline CGlue object with a context. | [`CInline<c_void, N>`](crate::inline::CInline) | `Ctx` |
+//! | `MyTraitArcInline<N>` | Inline CGlue object with a reference counted context. | [`CInline<c_void, N>`](crate::inline::CInline) | [`CArc<c_void>`](crate::arc::CArc) |
 //! | `MyTraitMut` | By-mut-ref CGlue object. | `&mut c_void`. | [`NoContext`](crate::trait_group::NoContext) |
 //! | `MyTraitCtxMut<Ctx>` | By-mut-ref CGlue object with a context. | `&mut c_void`. | `Ctx` |
 //! | `MyTraitArcMut` | By-mut-ref CGlue object with a reference counted context. | `&mut c_void`. | [`CArc<c_void>`](crate::arc::CArc) |
@@ -223,6 +280,12 @@
 //! | `MyTraitBaseBox<T>` | Base owned CGlue object. | [`CBox<T>`](crate::boxed::CBox) | [`NoContext`](crate::trait_group::NoContext) |
 //! | `MyTraitBaseCtxBox<T, Ctx>` | Base owned CGlue object with [some context](#plugin-system). | [`CBox<T>`](crate::boxed::CBox) | `Ctx` |
 //! | `MyTraitBaseArcBox<T, Ctx>` | Base owned CGlue object with reference counted context. | [`CBox<T>`](crate::boxed::CBox) | [`CArc<Ctx>`](crate::arc::CArc) |
+//! | `MyTraitBaseUnsendBox<T>` | Base owned CGlue object that is not `Send`. | [`CUnsendBox<T>`](crate::boxed::CUnsendBox) | [`NoContext`](crate::trait_group::NoContext) |
+//! | `MyTraitBaseCtxUnsendBox<T, Ctx>` | Base owned CGlue object with some context that is not `Send`. | [`CUnsendBox<T>`](crate::boxed::CUnsendBox) | `Ctx` |
+//! | `MyTraitBaseArcUnsendBox<T, Ctx>` | Base owned CGlue object with reference counted context that is not `Send`. | [`CUnsendBox<T>`](crate::boxed::CUnsendBox) | [`CArc<Ctx>`](crate::arc::CArc) |
+//! | `MyTraitBaseInline<T, N>` | Base inline CGlue object. | [`CInline<T, N>`](crate::inline::CInline) | [`NoContext`](crate::trait_group::NoContext) |
+//! | `MyTraitBaseCtxInline<T, Ctx, N>` | Base inline CGlue object with [some context](#plugin-system). | [`CInline<T, N>`](crate::inline::CInline) | `Ctx` |
+//! | `MyTraitBaseArcInline<T, Ctx, N>` | Base inline CGlue object with reference counted context. | [`CInline<T, N>`](crate::inline::CInline) | [`CArc<Ctx>`](crate::arc::CArc) |
 //! | `MyTraitBaseMut<T>` | Base by-mut-ref CGlue object. | `&mut T`. | [`NoContext`](crate::trait_group::NoContext) |
 //! | `MyTraitBaseRef<T>` | Typedef for generic by-ref (const) CGlue object. | `&T`. | [`NoContext`](crate::trait_group::NoContext) |
 //! | `MyTraitBase<Inst, Ctx>` | Base (non-opaque) CGlue object. It can have any compatible instance and context | `Inst` | `Ctx` |
@@ -233,6 +296,7 @@
 //! --- | ---
 //! | `MyTraitVtbl<C>` | Table of all functions of the trait. Should be opaque to the user. |
 //! | `MyTraitRetTmp<Ctx>` | Structure for temporary return values. It should be opaque to the user. |
+//! | `MyTraitFactory` | Table of associated functions of the trait. Only generated if the trait has any. |
 //!
 //! Instead, every opaque CGlue object implements `MyTraitOpaqueObj` trait, which contains the type
 //! of the vtable.
@@ -244,6 +308,15 @@
 //! | `MyGroupBox` | Owned CGlue trait group. | [`CBox<c_void>`](crate::boxed::CBox) | [`NoContext`](crate::trait_group::NoContext) |
 //! | `MyGroupCtxBox<Ctx>` | Owned CGlue trait group with [some context](#plugin-system). | [`CBox<c_void>`](crate::boxed::CBox) | `Ctx` |
 //! | `MyGroupArcBox` | Typedef for opaque owned CGlue trait group with reference counted context. | [`CBox<c_void>`](crate::boxed::CBox) | [`CArc<c_void>`](crate::arc::CArc) |
+//! | `MyGroupUnsendBox` | Owned CGlue trait group that may hold objects that are not `Send`. | [`CUnsendBox<c_void>`](crate::boxed::CUnsendBox) | [`NoContext`](crate::trait_group::NoContext) |
+//! | `MyGroupCtxUnsendBox<Ctx>` | Owned CGlue trait group with some context that is not `Send`. | [`CUnsendBox<c_void>`](crate::boxed::CUnsendBox) | `Ctx` |
+//! | `MyGroupArcUnsendBox` | Owned CGlue trait group with a reference counted context that is not `Send`. | [`CUnsendBox<c_void>`](crate::boxed::CUnsendBox) | [`CArc<c_void>`](crate::arc::CArc) |
+//! | `MyGroupSyncBox` | Owned CGlue trait group that is both `Send` and `Sync`. | [`CSyncBox<c_void>`](crate::boxed::CSyncBox) | [`NoContext`](crate::trait_group::NoContext) |
+//! | `MyGroupCtxSyncBox<Ctx>` | Owned CGlue trait group with some context that is both `Send` and `Sync`. | [`CSyncBox<c_void>`](crate::boxed::CSyncBox) | `Ctx` |
+//! | `MyGroupArcSyncBox` | Owned CGlue trait group with a reference counted context that is both `Send` and `Sync`. | [`CSyncBox<c_void>`](crate::boxed::CSyncBox) | [`CArc<c_void>`](crate::arc::CArc) |
+//! | `MyGroupInline<N>` | Owned CGlue trait group stored in a small inline buffer. | [`CInline<c_void, N>`](crate::inline::CInline) | [`NoContext`](crate::trait_group::NoContext) |
+//! | `MyGroupCtxInline<Ctx, N>` | Inline CGlue trait group with some context. | [`CInline<c_void, N>`](crate::inline::CInline) | `Ctx` |
+//! | `MyGroupArcInline<N>` | Inline CGlue trait group with a reference counted context. | [`CInline<c_void, N>`](crate::inline::CInline) | [`CArc<c_void>`](crate::arc::CArc) |
 //! | `MyGroupMut` | Typedef for opaque by-mut-ref CGlue trait group. | `&mut c_void`. | [`NoContext`](crate::trait_group::NoContext) |
 //! | `MyGroupCtxMut<Ctx>` | Typedef for opaque by-mut-ref CGlue trait group with a custom context. | `&mut c_void`. | `Ctx` |
 //! | `MyGroupArcMut` | Typedef for opaque by-mut-ref CGlue trait group with a reference counted context. | `&mut c_void`. | [`CArc<c_void>`](crate::arc::CArc) |
@@ -258,6 +331,15 @@
 //! | `MyGroupBaseBox<T>` | Base owned CGlue trait group. Its container is a [`CBox<T>`](crate::boxed::CBox) |
 //! | `MyGroupBaseCtxBox<T, Ctx>` | Base owned CGlue trait group with [some context](#plugin-system). | [`CBox<T>`](crate::boxed::CBox) | `Ctx` |
 //! | `MyGroupBaseArcBox<T, Ctx>` | Base owned CGlue trait group with reference counted context. | [`CBox<T>`](crate::boxed::CBox) | [`CArc<Ctx>`](crate::arc::CArc) |
+//! | `MyGroupBaseUnsendBox<T>` | Base owned CGlue trait group that is not `Send`. | [`CUnsendBox<T>`](crate::boxed::CUnsendBox) | [`NoContext`](crate::trait_group::NoContext) |
+//! | `MyGroupBaseCtxUnsendBox<T, Ctx>` | Base owned CGlue trait group with some context that is not `Send`. | [`CUnsendBox<T>`](crate::boxed::CUnsendBox) | `Ctx` |
+//! | `MyGroupBaseArcUnsendBox<T, Ctx>` | Base owned CGlue trait group with a reference counted context that is not `Send`. | [`CUnsendBox<T>`](crate::boxed::CUnsendBox) | [`CArc<Ctx>`](crate::arc::CArc) |
+//! | `MyGroupBaseSyncBox<T>` | Base owned CGlue trait group that is both `Send` and `Sync`. | [`CSyncBox<T>`](crate::boxed::CSyncBox) | [`NoContext`](crate::trait_group::NoContext) |
+//! | `MyGroupBaseCtxSyncBox<T, Ctx>` | Base owned CGlue trait group with some context that is both `Send` and `Sync`. | [`CSyncBox<T>`](crate::boxed::CSyncBox) | `Ctx` |
+//! | `MyGroupBaseArcSyncBox<T, Ctx>` | Base owned CGlue trait group with a reference counted context that is both `Send` and `Sync`. | [`CSyncBox<T>`](crate::boxed::CSyncBox) | [`CArc<Ctx>`](crate::arc::CArc) |
+//! | `MyGroupBaseInline<T, N>` | Base inline CGlue trait group. | [`CInline<T, N>`](crate::inline::CInline) | [`NoContext`](crate::trait_group::NoContext) |
+//! | `MyGroupBaseCtxInline<T, Ctx, N>` | Base inline CGlue trait group with some context. | [`CInline<T, N>`](crate::inline::CInline) | `Ctx` |
+//! | `MyGroupBaseArcInline<T, Ctx, N>` | Base inline CGlue trait group with a reference counted context. | [`CInline<T, N>`](crate::inline::CInline) | [`CArc<Ctx>`](crate::arc::CArc) |
 //! | `MyGroupBaseMut<T>` | Base by-mut-ref CGlue trait group. | `&mut T`. | [`NoContext`](crate::trait_group::NoContext) |
 //! | `MyGroupBaseCtxMut<T, Ctx>` | Base by-mut-ref CGlue trait group with a context. | `&mut T`. | `Ctx` |
 //! | `MyGroupBaseArcMut<T, Ctx>` | Base by-mut-ref CGlue trait group with a reference counted context. | `&mut T`. | [`CArc<Ctx>`](crate::arc::CArc) |
@@ -276,7 +358,7 @@
 //!
 //! | Name | Purpose |
 //! --- | ---
-//! | `MyGroupVtableFiller` | Trait that allows an object to specify which optional traits are available, through the use of `enable_trait` functions. |
+//! | `MyGroupVtableFiller` | Trait that allows an object to specify which optional traits are available, through constants holding their vtables. |
 //!
 //! The macro generation will also generate structures for all combinations of optional traits
 //! being used. For more convenient by-macro usage, the names of optional traits inside are sorted
@@ -526,17 +608,15 @@
 //!         T: Eq,
 //!     > GenGroupVtableFiller<'cglue_a, CGlueInst, CGlueCtx, T> for GA<T>
 //! where
+//!     GenGroupContainer<CGlueInst, CGlueCtx, T>: 'cglue_a + cglue::trait_group::CGlueObjBase,
 //!     Self: TA,
-//!     &'cglue_a TAVtbl<'cglue_a, GenGroupContainer<CGlueInst, CGlueCtx, T>,
-//!     >:
-//!         'cglue_a + Default,
+//!     &'cglue_a TAVtbl<'cglue_a, GenGroupContainer<CGlueInst, CGlueCtx, T>>:
+//!         'cglue_a + cglue::trait_group::StaticVtbl,
 //!     T: cglue::trait_group::GenericTypeBounds,
 //! {
-//!     fn fill_table(
-//!         table: GenGroupVtables<'cglue_a, CGlueInst, CGlueCtx, T>,
-//!     ) -> GenGroupVtables<'cglue_a, CGlueInst, CGlueCtx, T> {
-//!         table.enable_ta()
-//!     }
+//!     const CGLUE_VTBL_TA: Option<
+//!         &'cglue_a TAVtbl<'cglue_a, GenGroupContainer<CGlueInst, CGlueCtx, T>>,
+//!     > = Some(cglue::trait_group::StaticVtbl::CGLUE_VTBL);
 //! }
 //! # #[cfg(not(feature = "unstable"))]
 //! impl<
@@ -544,16 +624,531 @@
 //!         CGlueInst: cglue::trait_group::InstanceBounds<InstanceObjType = GA<u64>>,
 //!         CGlueCtx: cglue::trait_group::ContextBounds,
 //!     > GenGroupVtableFiller<'cglue_a, CGlueInst, CGlueCtx, u64> for GA<u64>
+//! where
+//!     GenGroupContainer<CGlueInst, CGlueCtx, u64>: 'cglue_a + cglue::trait_group::CGlueObjBase,
 //! {
-//!     fn fill_table(
-//!         table: GenGroupVtables<'cglue_a, CGlueInst, CGlueCtx, u64>,
-//!     ) -> GenGroupVtables<'cglue_a, CGlueInst, CGlueCtx, u64> {
-//!         table
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! #### Const generics
+//!
+//! Traits and groups may also take const generic parameters. This allows expressing fixed-width
+//! interfaces without a copy of the trait per width:
+//!
+//! ```
+//! # __export_abi_stable!();
+//! # use cglue::*;
+//! # #[cglue_trait]
+//! # pub trait Clear<const N: usize> {
+//! #     fn clear(&mut self);
+//! # }
+//! # pub struct Buf<const N: usize> {
+//! #     data: [u8; N],
+//! # }
+//! # impl<const N: usize> Register<N> for Buf<N> {
+//! #     fn read(&self, idx: usize) -> u8 {
+//! #         self.data[idx]
+//! #     }
+//! # }
+//! # impl<const N: usize> Clear<N> for Buf<N> {
+//! #     fn clear(&mut self) {
+//! #         self.data = [0; N];
+//! #     }
+//! # }
+//! # pub struct Word(u32);
+//! # impl Register<4> for Word {
+//! #     fn read(&self, idx: usize) -> u8 {
+//! #         self.0.to_le_bytes()[idx]
+//! #     }
+//! # }
+//! #[cglue_trait]
+//! pub trait Register<const N: usize> {
+//!     fn read(&self, idx: usize) -> u8;
+//!
+//!     fn width(&self) -> usize {
+//!         N
 //!     }
 //! }
+//!
+//! cglue_trait_group!(RegisterGroup<const N: usize>, Register<N>, { Clear<N> });
+//! cglue_impl_group!(Buf<const N: usize>, RegisterGroup<N>, { Clear<N> });
+//! cglue_impl_group!(Word, RegisterGroup<4>);
+//! # fn main() {}
+//! ```
+//!
+//! Note that with `layout_checks` enabled, abi_stable 0.10 only implements `StableAbi` for arrays of
+//! concrete sizes, thus `[T; N]` can not be used in function signatures of const generic traits.
+//!
+//! ### Derived groups
+//!
+//! Groups may be derived from other groups, by specifying the base group after the name. Derived
+//! group inherits mandatory traits of the base group, and then lists optional traits of the base
+//! group that it makes mandatory, and the ones that it keeps optional:
+//!
+//! ```
+//! # __export_abi_stable!();
+//! # use cglue::*;
+//! # #[cglue_trait]
+//! # pub trait Dev {
+//! #     fn name(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Net {
+//! #     fn mtu(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Stats {
+//! #     fn packets(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Power {
+//! #     fn off(&mut self);
+//! # }
+//! # pub struct Eth;
+//! # impl Dev for Eth {
+//! #     fn name(&self) -> usize { 0 }
+//! # }
+//! # impl Net for Eth {
+//! #     fn mtu(&self) -> usize { 1500 }
+//! # }
+//! # impl Stats for Eth {
+//! #     fn packets(&self) -> usize { 0 }
+//! # }
+//! cglue_trait_group!(Device, Dev, { Net, Stats, Power });
+//! cglue_trait_group!(NetDevice: Device, { Net }, { Stats });
+//!
+//! cglue_impl_group!(Eth, Device, { Net, Stats });
+//! cglue_impl_group!(Eth, NetDevice, { Stats });
+//! # fn main() {}
+//! ```
+//!
+//! Both groups share the same container, thus converting between them merely moves the vtables
+//! over. `From` implementation upcasts `NetDevice` into `Device` without losing any information,
+//! while `TryFrom` checks whether the `Device` implements `Net`, and gives the object back if it
+//! does not:
+//!
+//! ```
+//! # __export_abi_stable!();
+//! # use cglue::*;
+//! # #[cglue_trait]
+//! # pub trait Dev {
+//! #     fn name(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Net {
+//! #     fn mtu(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Stats {
+//! #     fn packets(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Power {
+//! #     fn off(&mut self);
+//! # }
+//! # pub struct Eth;
+//! # impl Dev for Eth {
+//! #     fn name(&self) -> usize { 0 }
+//! # }
+//! # impl Net for Eth {
+//! #     fn mtu(&self) -> usize { 1500 }
+//! # }
+//! # impl Stats for Eth {
+//! #     fn packets(&self) -> usize { 0 }
+//! # }
+//! # use core::convert::TryFrom;
+//! # cglue_trait_group!(Device, Dev, { Net, Stats, Power });
+//! # cglue_trait_group!(NetDevice: Device, { Net }, { Stats });
+//! # cglue_impl_group!(Eth, Device, { Net, Stats });
+//! # cglue_impl_group!(Eth, NetDevice, { Stats });
+//! # fn main() {
+//! let dev = DeviceBox::from(group_obj!(Eth as NetDevice));
+//! let net = NetDeviceBox::try_from(dev).ok().unwrap();
+//! # assert_eq!(net.mtu(), 1500);
+//! # }
+//! ```
+//!
+//! Since the container is shared, derived group can not add traits that are not part of the base
+//! group, and it must pass its generic parameters to the base group as is. Traits of the base group
+//! need to be in scope where the derived group is defined, and both groups need to be in the same
+//! crate.
+//!
+//! ### Regrouping
+//!
+//! Unrelated groups that share traits can be converted between one another with `regroup!`. The
+//! container and context are moved into the target group, alongside the vtables of matching traits:
+//!
+//! ```
+//! # __export_abi_stable!();
+//! # use cglue::*;
+//! # #[cglue_trait]
+//! # pub trait Dev {
+//! #     fn name(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Net {
+//! #     fn mtu(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Stats {
+//! #     fn packets(&self) -> usize;
+//! # }
+//! # pub struct Eth;
+//! # impl Dev for Eth {
+//! #     fn name(&self) -> usize { 0 }
+//! # }
+//! # impl Stats for Eth {
+//! #     fn packets(&self) -> usize { 0 }
+//! # }
+//! cglue_trait_group!(Features, Dev, { Net, Stats });
+//! cglue_trait_group!(ReadOnly, Dev, { Stats });
+//! cglue_trait_group!(Networked, { Dev, Net }, {});
+//!
+//! cglue_impl_group!(Eth, Features, { Stats });
+//! # fn main() {}
+//! ```
+//!
+//! ```
+//! # __export_abi_stable!();
+//! # use cglue::*;
+//! # #[cglue_trait]
+//! # pub trait Dev {
+//! #     fn name(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Net {
+//! #     fn mtu(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Stats {
+//! #     fn packets(&self) -> usize;
+//! # }
+//! # pub struct Eth;
+//! # impl Dev for Eth {
+//! #     fn name(&self) -> usize { 0 }
+//! # }
+//! # impl Stats for Eth {
+//! #     fn packets(&self) -> usize { 0 }
+//! # }
+//! # cglue_trait_group!(Features, Dev, { Net, Stats });
+//! # cglue_trait_group!(ReadOnly, Dev, { Stats });
+//! # cglue_trait_group!(Networked, { Dev, Net }, {});
+//! # cglue_impl_group!(Eth, Features, { Stats });
+//! # fn main() {
+//! let features = group_obj!(Eth as Features);
+//! // `Eth` does not implement `Net`, thus the original object is given back.
+//! let features = regroup!(features as Networked).err().unwrap();
+//! let ro = regroup!(features as ReadOnly).ok().unwrap();
+//! # assert_eq!(ro.name(), 0);
+//! # }
+//! ```
+//!
+//! Every trait of the target group needs to be part of the source group, be it as a mandatory, or an
+//! optional trait. Conversion succeeds if all mandatory traits of the target group are implemented by
+//! the object, and gives the original object back otherwise.
+//!
+//! Vtables are built for a specific container type, thus they can only be moved over if neither of
+//! the groups need temporary return storage in the container. `regroup!` fails for groups with traits
+//! that return wrapped references, such as ones using `wrap_with_obj_ref`.
+//!
+//! ### Automatic trait detection
+//!
+//! Instead of listing optional traits, `cglue_impl_group!` can be told to detect the implemented ones
+//! with `auto`. This also allows blanket implementations for all types with the given bounds:
+//!
+//! ```
+//! # __export_abi_stable!();
+//! # use cglue::*;
+//! # #[cglue_trait]
+//! # pub trait Dev {
+//! #     fn name(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Net {
+//! #     fn mtu(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Stats {
+//! #     fn packets(&self) -> usize;
+//! # }
+//! # pub struct Eth;
+//! # impl Dev for Eth {
+//! #     fn name(&self) -> usize { 0 }
+//! # }
+//! # impl Stats for Eth {
+//! #     fn packets(&self) -> usize { 0 }
+//! # }
+//! cglue_trait_group!(Features, Dev, { Net, Stats });
+//! cglue_trait_group!(Monitored, Dev, { Net, Stats });
+//!
+//! cglue_impl_group!(Eth, Features, auto);
+//! cglue_impl_group!(T: Dev + Stats, Monitored, auto);
+//! # fn main() {}
+//! ```
+//!
+//! ```
+//! # __export_abi_stable!();
+//! # use cglue::*;
+//! # #[cglue_trait]
+//! # pub trait Dev {
+//! #     fn name(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Net {
+//! #     fn mtu(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Stats {
+//! #     fn packets(&self) -> usize;
+//! # }
+//! # pub struct Eth;
+//! # impl Dev for Eth {
+//! #     fn name(&self) -> usize { 0 }
+//! # }
+//! # impl Stats for Eth {
+//! #     fn packets(&self) -> usize { 0 }
+//! # }
+//! # cglue_trait_group!(Features, Dev, { Net, Stats });
+//! # cglue_trait_group!(Monitored, Dev, { Net, Stats });
+//! # cglue_impl_group!(Eth, Features, auto);
+//! # cglue_impl_group!(T: Dev + Stats, Monitored, auto);
+//! # fn main() {
+//! let features = group_obj!(Eth as Features);
+//! assert!(as_ref!(&features impl Stats).is_some());
+//! assert!(as_ref!(&features impl Net).is_none());
+//!
+//! let monitored = group_obj!(Eth as Monitored);
+//! assert!(as_ref!(&monitored impl Stats).is_some());
+//! # }
+//! ```
+//!
+//! Detection is done for each of the built-in instance types (`CBox`, `CUnsendBox`, `CSyncBox`,
+//! `CInline`, references and mutable references), thus traits with `&mut self` functions are not
+//! enabled on objects built from shared references, and custom instance types can not be used with
+//! `auto`. Groups with more than 16 optional traits can not be used with `auto`, and blanket
+//! implementations only enable the traits that follow from their bounds.
+//!
+//! ### Shared vtable tables
+//!
+//! Group objects hold a reference to every vtable of the group, thus objects of groups with many
+//! optional traits get rather large. Groups marked with `#[vtbl_table]` hold a single reference to a
+//! table of vtables instead:
+//!
+//! ```
+//! # __export_abi_stable!();
+//! # use cglue::*;
+//! # #[cglue_trait]
+//! # pub trait Dev {
+//! #     fn name(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Net {
+//! #     fn mtu(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Stats {
+//! #     fn packets(&self) -> usize;
+//! # }
+//! # pub struct Eth;
+//! # impl Dev for Eth {
+//! #     fn name(&self) -> usize { 0 }
+//! # }
+//! # impl Stats for Eth {
+//! #     fn packets(&self) -> usize { 0 }
+//! # }
+//! # #[cfg(not(feature = "unstable"))]
+//! cglue_trait_group!(#[vtbl_table] Compact, Dev, { Net, Stats });
+//! # #[cfg(not(feature = "unstable"))]
+//! cglue_impl_group!(Eth, Compact, { Stats });
+//! # fn main() {}
+//! ```
+//!
+//! ```
+//! # __export_abi_stable!();
+//! # use cglue::*;
+//! # #[cglue_trait]
+//! # pub trait Dev {
+//! #     fn name(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Net {
+//! #     fn mtu(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Stats {
+//! #     fn packets(&self) -> usize;
+//! # }
+//! # pub struct Eth;
+//! # impl Dev for Eth {
+//! #     fn name(&self) -> usize { 0 }
+//! # }
+//! # impl Stats for Eth {
+//! #     fn packets(&self) -> usize { 0 }
+//! # }
+//! # #[cfg(not(feature = "unstable"))]
+//! # cglue_trait_group!(#[vtbl_table] Compact, Dev, { Net, Stats });
+//! # #[cfg(not(feature = "unstable"))]
+//! # cglue_impl_group!(Eth, Compact, { Stats });
+//! # #[cfg(not(feature = "unstable"))]
+//! # fn main() {
+//! let compact = group_obj!(Eth as Compact);
+//! assert!(as_ref!(&compact impl Stats).is_some());
+//! assert!(as_ref!(&compact impl Net).is_none());
+//! # }
+//! # #[cfg(feature = "unstable")]
 //! # fn main() {}
 //! ```
 //!
+//! The table also holds a mask of the available optional traits, thus checks and casts only need to
+//! test the mask. One table is shared by all objects of the same type, and it is built at compile
+//! time, as a constant of `CompactVtblTableFiller`.
+//!
+//! Since tables can not be built at runtime, objects of these groups can only be created out of types
+//! the group is implemented for. Thus, they can not be built from individual vtables, have their
+//! optional traits enabled, or be the target of `regroup!`. Derived groups can only be upcast into
+//! base groups without the attribute, and downcast if neither of the groups has it. Shared tables are
+//! not supported with the [`unstable`](#unstable-feature) feature.
+//!
+//! Layout of the objects differs from regular groups, and `cglue-bindgen` does not generate C/C++
+//! wrappers for these groups. Groups without the attribute are not affected.
+//!
+//! ### Thread safety
+//!
+//! Owned group objects (`FeaturesBox`) can only be built out of `Send` types, and are thus `Send`
+//! themselves. They are never `Sync`, because the opaque box can not tell whether the object it
+//! holds is:
+//!
+//! ```compile_fail
+//! # __export_abi_stable!();
+//! # use cglue::*;
+//! # #[cglue_trait]
+//! # pub trait Dev {
+//! #     fn name(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Net {
+//! #     fn mtu(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Stats {
+//! #     fn packets(&self) -> usize;
+//! # }
+//! # pub struct Eth;
+//! # impl Dev for Eth {
+//! #     fn name(&self) -> usize { 0 }
+//! # }
+//! # impl Stats for Eth {
+//! #     fn packets(&self) -> usize { 0 }
+//! # }
+//! # cglue_trait_group!(Features, Dev, { Net, Stats });
+//! # cglue_impl_group!(Eth, Features, auto);
+//! # use std::cell::Cell;
+//! struct Flag(Cell<usize>);
+//!
+//! impl Dev for Flag {
+//!     fn name(&self) -> usize {
+//!         self.0.get()
+//!     }
+//! }
+//!
+//! cglue_impl_group!(Flag, Features, auto);
+//!
+//! fn assert_sync<T: Sync>(_: &T) {}
+//!
+//! # fn main() {
+//! let features: FeaturesBox = group_obj!(Flag(Cell::new(0)) as Features);
+//! assert_sync(&features);
+//! # }
+//! ```
+//!
+//! Objects that also need to be shared across threads can be placed in a
+//! [`CSyncBox`](crate::boxed::CSyncBox), which only accepts types that are both `Send` and `Sync`.
+//! Every group has `SyncBox` aliases for such objects:
+//!
+//! ```
+//! # __export_abi_stable!();
+//! # use cglue::*;
+//! # #[cglue_trait]
+//! # pub trait Dev {
+//! #     fn name(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Net {
+//! #     fn mtu(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Stats {
+//! #     fn packets(&self) -> usize;
+//! # }
+//! # pub struct Eth;
+//! # impl Dev for Eth {
+//! #     fn name(&self) -> usize { 0 }
+//! # }
+//! # impl Stats for Eth {
+//! #     fn packets(&self) -> usize { 0 }
+//! # }
+//! # cglue_trait_group!(Features, Dev, { Net, Stats });
+//! # cglue_impl_group!(Eth, Features, auto);
+//! # use cglue::boxed::CSyncBox;
+//! # fn main() {
+//! let features: FeaturesSyncBox = group_obj!(CSyncBox::from(Eth) as Features);
+//! let features = std::sync::Arc::new(features);
+//!
+//! let remote = features.clone();
+//! std::thread::spawn(move || remote.name()).join().unwrap();
+//! assert!(as_ref!(&*features impl Stats).is_some());
+//! # }
+//! ```
+//!
+//! The bounds are checked when the object is built, thus types with `Rc` based state are rejected at
+//! compile time. Such objects need to be placed in a [`CUnsendBox`](crate::boxed::CUnsendBox)
+//! instead:
+//!
+//! ```compile_fail
+//! # __export_abi_stable!();
+//! # use cglue::*;
+//! # #[cglue_trait]
+//! # pub trait Dev {
+//! #     fn name(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Net {
+//! #     fn mtu(&self) -> usize;
+//! # }
+//! # #[cglue_trait]
+//! # pub trait Stats {
+//! #     fn packets(&self) -> usize;
+//! # }
+//! # pub struct Eth;
+//! # impl Dev for Eth {
+//! #     fn name(&self) -> usize { 0 }
+//! # }
+//! # impl Stats for Eth {
+//! #     fn packets(&self) -> usize { 0 }
+//! # }
+//! # cglue_trait_group!(Features, Dev, { Net, Stats });
+//! # cglue_impl_group!(Eth, Features, auto);
+//! # use std::{cell::Cell, rc::Rc};
+//! struct Counter(Rc<Cell<usize>>);
+//!
+//! impl Dev for Counter {
+//!     fn name(&self) -> usize {
+//!         self.0.get()
+//!     }
+//! }
+//!
+//! cglue_impl_group!(Counter, Features, auto);
+//!
+//! # fn main() {
+//! let counter = Counter(Rc::new(Cell::new(0)));
+//! let features: FeaturesBox = group_obj!(counter as Features);
+//! # }
+//! ```
+//!
+//! `CtxSyncBox` and `ArcSyncBox` aliases exist for objects with a context. The contexts themselves
+//! are always required to be `Send` and `Sync`.
+//!
 //! ### External traits
 //!
 //! Certain traits may not be available for `#[cglue_trait]` annotation. Thus, there are mechanisms
@@ -671,6 +1266,48 @@
 //! with_int_result: extern "C" fn(&CGlueC, ok_out: &mut MaybeUninit<usize>) -> i32,
 //! ```
 //!
+//! Closure and iterator arguments are lowered to [OpaqueCallback](crate::callback::OpaqueCallback),
+//! [OpaqueCallbackRet](crate::callback::OpaqueCallbackRet), and [CIterator](crate::iter::CIterator):
+//!
+//! ```ignore
+//! fn with_callback(&self, cb: impl FnMut(usize) -> bool) {}
+//! fn with_iter(&self, iter: &mut dyn Iterator<Item = usize>) {}
+//!
+//! // Generated vtable entries:
+//!
+//! with_callback: extern "C" fn(&CGlueC, cb: OpaqueCallback<usize>),
+//! with_iter: extern "C" fn(&CGlueC, iter: CIterator<usize>),
+//! ```
+//!
+//! Owned `Vec`, `String`, `Box`, and `Arc` types are converted to [CVec](crate::vec::CVec),
+//! [ReprCString](crate::repr_cstring::ReprCString), [CBox](crate::boxed::CBox) (or
+//! [CSliceBox](crate::boxed::CSliceBox) for boxed slices), and [CArcSome](crate::arc::CArcSome).
+//! The implementor still works with the standard types. Arcs keep being shared within the module
+//! that created them, but `Arc<T>` requires `T: Clone`, since a shared arc from a different module
+//! can only be cloned:
+//!
+//! ```ignore
+//! fn with_vec(&self, vec: Vec<usize>) -> String {}
+//!
+//! // Generated vtable entry:
+//!
+//! with_vec: extern "C" fn(&CGlueC, vec: CVec<usize>) -> ReprCString,
+//! ```
+//!
+//! `impl Trait`, `&dyn Trait`, and `&mut dyn Trait` arguments of CGlue traits can be marked with
+//! `#[wrap_with_obj]`, and they get converted to opaque objects through `trait_obj!`. Owned
+//! `impl Trait` arguments are moved into a `TraitUnsendBox`, so the callee takes ownership of them.
+//! Trait object references become `TraitMut` or `TraitRef` objects, and are passed through
+//! [Fwd](crate::forward::Fwd), thus such traits also need `#[cglue_forward]`:
+//!
+//! ```ignore
+//! fn with_sink(&self, #[wrap_with_obj] sink: &mut dyn LogSink) {}
+//!
+//! // Generated vtable entry:
+//!
+//! with_sink: extern "C" fn(&CGlueC, sink: LogSinkMut),
+//! ```
+//!
 //! All wrapping and conversion is handled transparently behind the scenes, with user's control.
 //!
 //! ### Associated type wrapping
@@ -831,14 +1468,180 @@
 //! # }
 //! ```
 //!
+//! ### Custom receivers
+//!
+//! In addition to `self`, `&self`, `&mut self` and their pinned variants, functions may take
+//! `self: Box<Self>` and `self: Arc<Self>`. Just like with self-consuming functions, the object is
+//! checked to have a compatible container at compile time:
+//!
+//! - `Box<Self>` consumes the object, thus it requires an owned container, such as `MyTraitBox`.
+//!
+//! - `Arc<Self>` requires the object to be built on top of a
+//!   [`CArcSome`](crate::arc::CArcSome). Such object can be shared by wrapping it in an `Arc`, and
+//!   every call passes a new reference to the underlying arc.
+//!
+//! ```
+//! # __export_abi_stable!();
+//! use cglue::prelude::v1::*;
+//! use cglue::trait_group::NoContext;
+//! use std::sync::Arc;
+//!
+//! #[cglue_trait]
+//! pub trait Finish {
+//!     fn finish(self: Box<Self>) -> usize;
+//! }
+//!
+//! #[cglue_trait]
+//! pub trait Spawn {
+//!     fn spawn(self: Arc<Self>) -> usize;
+//! }
+//!
+//! struct Job(usize);
+//!
+//! impl Finish for Job {
+//!     fn finish(self: Box<Self>) -> usize {
+//!         self.0
+//!     }
+//! }
+//!
+//! impl Spawn for Job {
+//!     fn spawn(self: Arc<Self>) -> usize {
+//!         Arc::strong_count(&self)
+//!     }
+//! }
+//!
+//! # fn main() -> () {
+//! let obj = trait_obj!(Job(4) as Finish);
+//! assert_eq!(Box::new(obj).finish(), 4);
+//!
+//! let obj = SpawnBase::<_, NoContext>::from(CArcSome::from(Job(4))).into_opaque();
+//! assert_eq!(Arc::new(obj).spawn(), 2);
+//! # }
+//! ```
+//!
+//! `CArc<Self>` receivers are out of scope, because they require the unstable
+//! `arbitrary_self_types` feature, and are rejected by the macro. Use `Arc<Self>` instead, which is
+//! passed as `CArc` across FFI.
+//!
+//! `Rc<Self>` receivers are out of scope too, because CGlue has no FFI-safe counterpart of `Rc`
+//! to pass across FFI. They are rejected by the macro as well. Use `Arc<Self>` instead, which also
+//! accepts types that are not `Send`, such as ones with `Rc` based state.
+//!
+//! ### Associated functions
+//!
+//! Functions that do not take `self`, such as constructors, can not be placed in the object's
+//! vtable. Instead, they are exposed through `MyTraitFactory` table, which can be built for any
+//! type implementing the trait, and exported by a plugin. Returned `Self`, also when wrapped in
+//! `Option` or `Result`, gets converted to the opaque `MyTraitBox` object. Such functions must be
+//! bounded by `where Self: Sized`:
+//!
+//! ```
+//! # __export_abi_stable!();
+//! use cglue::prelude::v1::*;
+//!
+//! #[cglue_trait]
+//! pub trait Counter {
+//!     fn version() -> u32;
+//!     fn new(value: usize) -> Self
+//!     where
+//!         Self: Sized;
+//!
+//!     fn count(&self) -> usize;
+//! }
+//!
+//! struct Count(usize);
+//!
+//! impl Counter for Count {
+//!     fn version() -> u32 {
+//!         1
+//!     }
+//!
+//!     fn new(value: usize) -> Self {
+//!         Self(value)
+//!     }
+//!
+//!     fn count(&self) -> usize {
+//!         self.0
+//!     }
+//! }
+//!
+//! # fn main() -> () {
+//! let factory: &CounterFactory = CounterFactory::of_type::<Count>();
+//! assert_eq!(factory.version(), 1);
+//! let obj: CounterBox = factory.new(4);
+//! assert_eq!(obj.count(), 4);
+//! # }
+//! ```
+//!
+//! Associated functions without a default implementation can not be called on the CGlue objects
+//! themselves. Doing so fails to compile:
+//!
+//! ```compile_fail
+//! # __export_abi_stable!();
+//! # use cglue::prelude::v1::*;
+//! # #[cglue_trait]
+//! # pub trait Counter {
+//! #     fn version() -> u32;
+//! #     fn count(&self) -> usize;
+//! # }
+//! # struct Count(usize);
+//! # impl Counter for Count {
+//! #     fn version() -> u32 { 1 }
+//! #     fn count(&self) -> usize { self.0 }
+//! # }
+//! # fn main() -> () {
+//! let version = <CounterBox as Counter>::version();
+//! # }
+//! ```
+//!
+//! ### Associated constants
+//!
+//! Associated constants are stored in the vtable as data, with `&str` and slice references being
+//! converted to [`CSliceRef`](crate::slice::CSliceRef). CGlue objects expose them through accessor
+//! functions, named after the constant in lowercase, with `const_` prefix:
+//!
+//! ```
+//! # __export_abi_stable!();
+//! use cglue::prelude::v1::*;
+//!
+//! #[cglue_trait]
+//! pub trait PluginInfo {
+//!     const NAME: &'static str;
+//!     const MAX_BATCH: usize;
+//!
+//!     fn batch(&self) -> usize;
+//! }
+//!
+//! struct Plugin;
+//!
+//! impl PluginInfo for Plugin {
+//!     const NAME: &'static str = "plugin";
+//!     const MAX_BATCH: usize = 16;
+//!
+//!     fn batch(&self) -> usize {
+//!         Self::MAX_BATCH
+//!     }
+//! }
+//!
+//! # fn main() {
+//! let obj = trait_obj!(Plugin as PluginInfo);
+//! assert_eq!(obj.const_name(), "plugin");
+//! assert_eq!(obj.const_max_batch(), 16);
+//! # }
+//! ```
+//!
+//! Constant values of CGlue objects can not be known at compile time, thus, unless there is a
+//! default value, using the constant on the object type (such as `PluginInfoBox::NAME`) fails to
+//! compile.
+//!
 //! ### Plugin system
 //!
 //! A full example is available in the repo's `examples` subdirectory.
 //!
-//! CGlue currently does not provide an out-of-the box plugin system, but there are primitives in
-//! place for relatively safe trait usage using dynamically loaded libraries. The core primitive is
-//! a cloneable context, such as a libloading::Library` Arc, which will keep the library opened
-//! until all of the CGlue objects are dropped.
+//! There are primitives in place for relatively safe trait usage using dynamically loaded
+//! libraries, and a [loader](#loading-plugins) built upon them. The core primitive is a cloneable
+//! context, such as a `libloading::Library` Arc, which will keep the library opened until all of the
+//! CGlue objects are dropped.
 //!
 //! ```
 //! # __export_abi_stable!();
@@ -919,6 +1722,58 @@
 //! `IntError` types, and mark the trait with `#[int_result]`, which would prevent this particular
 //! issue from happening.
 //!
+//! #### Loading plugins
+//!
+//! The `plugin` feature enables the [`plugin`](crate::plugin) module with a loader for such libraries.
+//! Plugins declare a header that describes the object they build:
+//!
+//! ```ignore
+//! extern "C" fn create_plugin(lib: &CArc<c_void>) -> PluginRootArcBox<'static> {
+//!     trait_obj!((Root::default(), lib.clone()) as PluginRoot)
+//! }
+//!
+//! // Export the header, with version 1 of the plugin interface.
+//! cglue::declare_plugin!(PluginRootArcBox<'static>, 1, create_plugin);
+//! ```
+//!
+//! The host looks for the library in the directory of the executable, and any added search paths.
+//! Version and layout of the object are verified before it gets built:
+//!
+//! ```ignore
+//! use cglue::plugin::Loader;
+//!
+//! let loader = Loader::new(1).search_path("plugins");
+//! let root: PluginRootArcBox<'static> = unsafe { loader.load("my_plugin") }?;
+//! ```
+//!
+//! The library is placed in the `CArc` context that is passed to the plugin, and failures are
+//! returned as [`plugin::Error`](crate::plugin::Error).
+//!
+//! A single library may also ship multiple plugins, possibly of different interfaces. These are
+//! listed in a manifest, where every plugin has a name and an interface identifier:
+//!
+//! ```ignore
+//! cglue::declare_plugin_manifest!(
+//!     ("eth", "net_driver", NetDriverArcBox<'static>, 1, create_eth),
+//!     ("wifi", "net_driver", NetDriverArcBox<'static>, 1, create_wifi),
+//!     ("stats", "monitor", MonitorArcBox<'static>, 1, create_stats),
+//! );
+//! ```
+//!
+//! The host opens the manifest, and builds the plugins by name:
+//!
+//! ```ignore
+//! let lib = unsafe { Loader::new(1).open("drivers") }?;
+//!
+//! for entry in lib.entries_of("net_driver") {
+//!     let driver: NetDriverArcBox<'static> = lib.create(entry.name())?;
+//!     // ...
+//! }
+//! ```
+//!
+//! Version and layout of each plugin are verified when it gets built, same as with single plugin
+//! libraries.
+//!
 //! ### Working with cbindgen
 //!
 //! [cbindgen](https://github.com/eqrion/cbindgen) can be used to generate C and C++ bindings.
@@ -1036,6 +1891,31 @@ macro_rules! __export_abi_stable {
 
 __export_abi_stable!();
 
+/// Fail const evaluation of the enclosing constant.
+///
+/// Panicking in constants is only stable since Rust 1.57, older compilers get an out of bounds
+/// index instead, with the message visible in the error's source snippet.
+#[cfg(const_panic_on_stable)]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cglue_const_panic {
+    ($msg:literal) => {
+        panic!($msg)
+    };
+}
+
+#[cfg(not(const_panic_on_stable))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cglue_const_panic {
+    ($msg:literal) => {{
+        #[allow(unconditional_panic)]
+        let _msg = [$msg][1];
+        #[allow(unreachable_code)]
+        loop {}
+    }};
+}
+
 #[cfg(feature = "abi_stable")]
 #[doc(hidden)]
 pub mod __sabi {
@@ -1045,11 +1925,13 @@ pub mod __sabi {
 #[cfg(feature = "futures")]
 extern crate _futures as futures;
 
+pub mod alloc;
 pub mod arc;
 pub mod boxed;
 pub mod callback;
 pub mod forward;
 pub mod from2;
+pub mod inline;
 pub mod iter;
 pub mod option;
 pub mod repr_cstring;
@@ -1059,6 +1941,10 @@ pub mod trait_group;
 pub mod tuple;
 pub mod vec;
 
+#[cfg(feature = "plugin")]
+#[cfg_attr(docsrs, doc(cfg(feature = "plugin")))]
+pub mod plugin;
+
 #[cfg(feature = "task")]
 #[cfg_attr(docsrs, doc(cfg(feature = "task")))]
 pub mod task;
@@ -1066,8 +1952,8 @@ pub mod task;
 pub use ::cglue_macro::{
     as_mut, as_ref, cast, cglue_forward, cglue_forward_ext, cglue_impl_group, cglue_trait,
     cglue_trait_ext, cglue_trait_group, custom_impl, group_obj, int_result, into, no_int_result,
-    return_wrap, skip_func, trait_obj, vtbl_only, wrap_with, wrap_with_group, wrap_with_group_mut,
-    wrap_with_group_ref, wrap_with_obj, wrap_with_obj_mut, wrap_with_obj_ref,
+    regroup, return_wrap, skip_func, trait_obj, try_into, vtbl_only, wrap_with, wrap_with_group,
+    wrap_with_group_mut, wrap_with_group_ref, wrap_with_obj, wrap_with_obj_mut, wrap_with_obj_ref,
 };
 
 #[cfg(feature = "unstable")]
@@ -1085,11 +1971,16 @@ pub mod prelude {
 
     pub mod v1 {
         pub use crate::{
-            arc::{CArc, CArcSome},
-            boxed::{CBox, CSliceBox},
-            callback::{Callback, Callbackable, FeedCallback, FromExtend, OpaqueCallback},
+            alloc::CAllocator,
+            arc::{CArc, CArcSome, CWeak},
+            boxed::{CBox, CSliceBox, CSyncBox, CUnsendBox},
+            callback::{
+                CBoxCallback, COnceCallback, Callback, CallbackRet, Callbackable, FeedCallback,
+                FromExtend, OpaqueCallback, OpaqueCallbackRet,
+            },
             forward::{Forward, ForwardMut, Fwd},
-            iter::CIterator,
+            inline::CInline,
+            iter::{CDoubleEnded, CIntoIter, CIterator, CLendingIterator, CTryIterator},
             option::COption,
             repr_cstring::{ReprCStr, ReprCString},
             result::{CResult, IntError, IntResult},
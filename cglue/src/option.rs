@@ -1,14 +1,41 @@
 //! # FFI safe option.
 
+use crate::result::CResult;
+
 /// FFI-safe Option.
 ///
 /// This type is not really meant for general use, but rather as a last-resort conversion for type
 /// wrapping.
 ///
-/// Typical workflow would include temporarily converting into/from COption.
+/// Typical workflow would include temporarily converting into/from COption. However, most of the
+/// commonly used methods of `Option` are available directly, for instance:
+///
+/// ```
+/// use cglue::option::COption;
+///
+/// let opt = COption::Some(2u8);
+///
+/// assert_eq!(opt.map(|v| v * 2), COption::Some(4));
+/// assert_eq!(opt.and_then(|v| v.checked_sub(3).into()), COption::None);
+/// assert_eq!(opt.ok_or("none").unwrap(), 2);
+/// assert_eq!(opt.into_iter().sum::<u8>(), 2);
+/// ```
+///
+/// The `?` operator is available by converting into `Option`:
+///
+/// ```
+/// use cglue::option::COption;
+///
+/// fn add(a: COption<u8>, b: COption<u8>) -> Option<u8> {
+///     Option::<u8>::from(a)?.checked_add(Option::<u8>::from(b)?)
+/// }
+///
+/// assert_eq!(add(COption::Some(1), COption::Some(2)), Some(3));
+/// assert_eq!(add(COption::Some(1), COption::None), None);
+/// ```
 #[repr(C)]
 #[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum COption<T> {
     None,
     Some(T),
@@ -38,11 +65,42 @@ impl<T> From<COption<T>> for Option<T> {
     }
 }
 
+impl<T> IntoIterator for COption<T> {
+    type Item = T;
+    type IntoIter = core::option::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Option::from(self).into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a COption<T> {
+    type Item = &'a T;
+    type IntoIter = core::option::IntoIter<&'a T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut COption<T> {
+    type Item = &'a mut T;
+    type IntoIter = core::option::IntoIter<&'a mut T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
 impl<T> COption<T> {
     pub fn is_some(&self) -> bool {
         matches!(*self, COption::Some(_))
     }
 
+    pub fn is_none(&self) -> bool {
+        !self.is_some()
+    }
+
     pub fn unwrap(self) -> T {
         match self {
             COption::Some(val) => val,
@@ -50,6 +108,112 @@ impl<T> COption<T> {
         }
     }
 
+    pub fn expect(self, msg: &str) -> T {
+        match self {
+            COption::Some(val) => val,
+            COption::None => panic!("{}", msg),
+        }
+    }
+
+    pub fn unwrap_or(self, default: T) -> T {
+        match self {
+            COption::Some(val) => val,
+            COption::None => default,
+        }
+    }
+
+    pub fn unwrap_or_else<F: FnOnce() -> T>(self, f: F) -> T {
+        match self {
+            COption::Some(val) => val,
+            COption::None => f(),
+        }
+    }
+
+    pub fn unwrap_or_default(self) -> T
+    where
+        T: Default,
+    {
+        self.unwrap_or_else(T::default)
+    }
+
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> COption<U> {
+        match self {
+            COption::Some(val) => COption::Some(f(val)),
+            COption::None => COption::None,
+        }
+    }
+
+    pub fn map_or<U, F: FnOnce(T) -> U>(self, default: U, f: F) -> U {
+        match self {
+            COption::Some(val) => f(val),
+            COption::None => default,
+        }
+    }
+
+    pub fn map_or_else<U, D: FnOnce() -> U, F: FnOnce(T) -> U>(self, default: D, f: F) -> U {
+        match self {
+            COption::Some(val) => f(val),
+            COption::None => default(),
+        }
+    }
+
+    pub fn and<U>(self, optb: COption<U>) -> COption<U> {
+        match self {
+            COption::Some(_) => optb,
+            COption::None => COption::None,
+        }
+    }
+
+    pub fn and_then<U, F: FnOnce(T) -> COption<U>>(self, f: F) -> COption<U> {
+        match self {
+            COption::Some(val) => f(val),
+            COption::None => COption::None,
+        }
+    }
+
+    pub fn or(self, optb: COption<T>) -> COption<T> {
+        match self {
+            COption::Some(val) => COption::Some(val),
+            COption::None => optb,
+        }
+    }
+
+    pub fn or_else<F: FnOnce() -> COption<T>>(self, f: F) -> COption<T> {
+        match self {
+            COption::Some(val) => COption::Some(val),
+            COption::None => f(),
+        }
+    }
+
+    pub fn filter<P: FnOnce(&T) -> bool>(self, predicate: P) -> COption<T> {
+        match self {
+            COption::Some(val) if predicate(&val) => COption::Some(val),
+            _ => COption::None,
+        }
+    }
+
+    pub fn ok_or<E>(self, err: E) -> CResult<T, E> {
+        match self {
+            COption::Some(val) => CResult::Ok(val),
+            COption::None => CResult::Err(err),
+        }
+    }
+
+    pub fn ok_or_else<E, F: FnOnce() -> E>(self, err: F) -> CResult<T, E> {
+        match self {
+            COption::Some(val) => CResult::Ok(val),
+            COption::None => CResult::Err(err()),
+        }
+    }
+
+    pub fn iter(&self) -> core::option::IntoIter<&T> {
+        self.as_ref().into_iter()
+    }
+
+    pub fn iter_mut(&mut self) -> core::option::IntoIter<&mut T> {
+        self.as_mut().into_iter()
+    }
+
     pub fn as_ref(&self) -> Option<&T> {
         match *self {
             COption::Some(ref x) => Some(x),
@@ -67,6 +231,55 @@ impl<T> COption<T> {
     pub fn take(&mut self) -> Option<T> {
         core::mem::take(self).into()
     }
+
+    pub fn replace(&mut self, value: T) -> Option<T> {
+        core::mem::replace(self, COption::Some(value)).into()
+    }
+
+    pub fn get_or_insert_with<F: FnOnce() -> T>(&mut self, f: F) -> &mut T {
+        if let COption::None = self {
+            *self = COption::Some(f());
+        }
+
+        match self {
+            COption::Some(val) => val,
+            COption::None => unreachable!(),
+        }
+    }
+}
+
+impl<T: Clone> COption<&T> {
+    pub fn cloned(self) -> COption<T> {
+        self.map(T::clone)
+    }
+}
+
+impl<T: Copy> COption<&T> {
+    pub fn copied(self) -> COption<T> {
+        self.map(|v| *v)
+    }
+}
+
+impl<T, E> COption<CResult<T, E>> {
+    /// Transpose an option of a result into a result of an option.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::option::COption;
+    /// use cglue::result::CResult;
+    ///
+    /// let x: COption<CResult<u8, ()>> = COption::Some(CResult::Ok(5));
+    ///
+    /// assert_eq!(x.transpose(), CResult::Ok(COption::Some(5)));
+    /// ```
+    pub fn transpose(self) -> CResult<COption<T>, E> {
+        match self {
+            COption::Some(CResult::Ok(val)) => CResult::Ok(COption::Some(val)),
+            COption::Some(CResult::Err(err)) => CResult::Err(err),
+            COption::None => CResult::Ok(COption::None),
+        }
+    }
 }
 
 #[cfg(feature = "serde")]
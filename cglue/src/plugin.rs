@@ -0,0 +1,583 @@
+//! # Dynamically loaded plugins.
+//!
+//! Plugin libraries export a [`PluginHeader`] under the [`PLUGIN_HEADER`] symbol, usually by
+//! calling [`declare_plugin!`](crate::declare_plugin). The header describes the interface of the
+//! object the plugin builds, alongside a function that builds it.
+//!
+//! Libraries that ship multiple plugins export a [`PluginManifest`] under the [`PLUGIN_MANIFEST`]
+//! symbol instead, with [`declare_plugin_manifest!`](crate::declare_plugin_manifest). Every
+//! [`PluginEntry`] of the manifest is a named plugin of some interface.
+//!
+//! The host loads such libraries with a [`Loader`]. It finds the library in its search paths,
+//! verifies that the plugin was built against the same interface, and creates the object. The
+//! library is placed in a [`CArc`] context that gets passed to the plugin, thus it stays loaded
+//! until all objects built out of it are dropped.
+
+use crate::arc::CArc;
+use crate::slice::CSliceRef;
+use crate::trait_group::{c_void, compare_layouts, Opaquable};
+use libloading::{library_filename, Library};
+use std::path::{Path, PathBuf};
+use std::string::String;
+use std::vec::Vec;
+
+#[doc(no_inline)]
+pub use abi_stable::{type_layout::TypeLayout, StableAbi};
+
+/// Version of the [`PluginHeader`] structure.
+///
+/// It gets bumped every time the layout of the header changes.
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Name of the symbol plugins export their header under.
+pub const PLUGIN_HEADER: &str = "PLUGIN_HEADER";
+
+/// Name of the symbol plugins export their manifest under.
+pub const PLUGIN_MANIFEST: &str = "PLUGIN_MANIFEST";
+
+/// Plugin header that the [`Loader`] looks for.
+///
+/// `T` is the type of the object the plugin builds. It is usually an opaque CGlue object with a
+/// [`CArc`] context, such as `MyTraitArcBox<'static>`.
+#[repr(C)]
+pub struct PluginHeader<T> {
+    /// Version of the header itself, must be equal to [`PLUGIN_ABI_VERSION`].
+    pub abi_version: u32,
+    /// Version of the plugin interface.
+    ///
+    /// This is chosen by the API crate, and should be bumped on changes to the interface that do
+    /// not change its layout.
+    pub version: u32,
+    /// Layout of `T`.
+    pub layout: &'static TypeLayout,
+    /// Build the object.
+    ///
+    /// The context holds the library. It should be cloned into the object.
+    pub create: extern "C" fn(&CArc<c_void>) -> T,
+}
+
+/// Declare a plugin header in a plugin library.
+///
+/// # Arguments
+///
+/// 1. Type of the object the plugin builds.
+///
+/// 2. Version of the plugin interface.
+///
+/// 3. Function that builds the object.
+///
+/// # Examples
+///
+/// ```
+/// # __export_abi_stable!();
+/// use cglue::prelude::v1::*;
+///
+/// #[cglue_trait]
+/// pub trait Greeter {
+///     fn greet(&self) -> usize;
+/// }
+///
+/// #[derive(Default)]
+/// struct Hello;
+///
+/// impl Greeter for Hello {
+///     fn greet(&self) -> usize {
+///         42
+///     }
+/// }
+///
+/// extern "C" fn create(lib: &CArc<cglue::trait_group::c_void>) -> GreeterArcBox<'static> {
+///     trait_obj!((Hello, lib.clone()) as Greeter)
+/// }
+///
+/// cglue::declare_plugin!(GreeterArcBox<'static>, 1, create);
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! declare_plugin {
+    ($ty:ty, $version:expr, $create:expr $(,)?) => {
+        #[no_mangle]
+        pub static PLUGIN_HEADER: $crate::plugin::PluginHeader<$ty> =
+            $crate::plugin::PluginHeader {
+                abi_version: $crate::plugin::PLUGIN_ABI_VERSION,
+                version: $version,
+                layout: <$ty as $crate::plugin::StableAbi>::LAYOUT,
+                create: $create,
+            };
+    };
+}
+
+/// Manifest of a library with multiple plugins.
+#[repr(C)]
+pub struct PluginManifest {
+    abi_version: u32,
+    entries: CSliceRef<'static, PluginEntry>,
+}
+
+impl PluginManifest {
+    /// Create a manifest out of the given plugins.
+    pub const fn new(entries: &'static [PluginEntry]) -> Self {
+        Self {
+            abi_version: PLUGIN_ABI_VERSION,
+            entries: CSliceRef::from_slice(entries),
+        }
+    }
+
+    /// Get the version of the manifest.
+    ///
+    /// It must be equal to [`PLUGIN_ABI_VERSION`] for the manifest to be opened.
+    pub fn abi_version(&self) -> u32 {
+        self.abi_version
+    }
+
+    /// Get the plugins of the manifest.
+    pub fn entries(&self) -> &[PluginEntry] {
+        &self.entries
+    }
+
+    fn verify(&self) -> Result<(), Error> {
+        Loader::verify_abi(self.abi_version)?;
+
+        for entry in self.entries() {
+            core::str::from_utf8(&entry.name).map_err(Error::InvalidEntry)?;
+            core::str::from_utf8(&entry.interface).map_err(Error::InvalidEntry)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Named plugin within a [`PluginManifest`].
+#[repr(C)]
+pub struct PluginEntry {
+    name: CSliceRef<'static, u8>,
+    interface: CSliceRef<'static, u8>,
+    version: u32,
+    layout: &'static TypeLayout,
+    // This is an `extern "C" fn(&CArc<c_void>) -> T` function, where `T` is described by
+    // `layout`. The type is erased, so that plugins of different interfaces fit in one table.
+    create: extern "C" fn(),
+}
+
+impl PluginEntry {
+    /// Create a plugin entry.
+    ///
+    /// This is what [`declare_plugin_manifest!`](crate::declare_plugin_manifest) builds entries
+    /// with.
+    ///
+    /// # Safety
+    ///
+    /// `layout` must be the layout of `T`.
+    pub const unsafe fn new<T>(
+        name: &'static str,
+        interface: &'static str,
+        version: u32,
+        layout: &'static TypeLayout,
+        create: extern "C" fn(&CArc<c_void>) -> T,
+    ) -> Self {
+        Self {
+            name: CSliceRef::from_str(name),
+            interface: CSliceRef::from_str(interface),
+            version,
+            layout,
+            create: core::mem::transmute::<extern "C" fn(&CArc<c_void>) -> T, extern "C" fn()>(
+                create,
+            ),
+        }
+    }
+
+    /// Get the name of the plugin, unique within the manifest.
+    pub fn name(&self) -> &str {
+        // Entries are either built out of strings, or verified when the library gets opened.
+        unsafe { self.name.into_str() }
+    }
+
+    /// Get the identifier of the interface the plugin implements.
+    pub fn interface(&self) -> &str {
+        unsafe { self.interface.into_str() }
+    }
+
+    /// Get the version of the plugin interface.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Get the layout of the object the plugin builds.
+    pub fn layout(&self) -> &TypeLayout {
+        self.layout
+    }
+}
+
+/// Declare a manifest of plugins in a plugin library.
+///
+/// Every entry is a tuple of the following:
+///
+/// 1. Name of the plugin.
+///
+/// 2. Identifier of the interface the plugin implements.
+///
+/// 3. Type of the object the plugin builds.
+///
+/// 4. Version of the plugin interface.
+///
+/// 5. Function that builds the object.
+///
+/// # Examples
+///
+/// ```
+/// # __export_abi_stable!();
+/// use cglue::prelude::v1::*;
+///
+/// #[cglue_trait]
+/// pub trait Greeter {
+///     fn greet(&self) -> usize;
+/// }
+///
+/// struct Hello;
+///
+/// impl Greeter for Hello {
+///     fn greet(&self) -> usize {
+///         42
+///     }
+/// }
+///
+/// struct Bye;
+///
+/// impl Greeter for Bye {
+///     fn greet(&self) -> usize {
+///         43
+///     }
+/// }
+///
+/// extern "C" fn create_hello(lib: &CArc<cglue::trait_group::c_void>) -> GreeterArcBox<'static> {
+///     trait_obj!((Hello, lib.clone()) as Greeter)
+/// }
+///
+/// extern "C" fn create_bye(lib: &CArc<cglue::trait_group::c_void>) -> GreeterArcBox<'static> {
+///     trait_obj!((Bye, lib.clone()) as Greeter)
+/// }
+///
+/// cglue::declare_plugin_manifest!(
+///     ("hello", "greeter", GreeterArcBox<'static>, 1, create_hello),
+///     ("bye", "greeter", GreeterArcBox<'static>, 1, create_bye),
+/// );
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! declare_plugin_manifest {
+    ($(($name:expr, $interface:expr, $ty:ty, $version:expr, $create:expr $(,)?)),* $(,)?) => {
+        #[no_mangle]
+        pub static PLUGIN_MANIFEST: $crate::plugin::PluginManifest = {
+            const ENTRIES: &[$crate::plugin::PluginEntry] = &[$({
+                type Create = extern "C" fn(
+                    &$crate::arc::CArc<$crate::trait_group::c_void>,
+                ) -> $ty;
+                const NAME: &str = $name;
+                const INTERFACE: &str = $interface;
+                const VERSION: u32 = $version;
+                const LAYOUT: &$crate::plugin::TypeLayout =
+                    <$ty as $crate::plugin::StableAbi>::LAYOUT;
+                const CREATE: Create = $create;
+                // The layout is taken from the return type of `CREATE`.
+                unsafe { $crate::plugin::PluginEntry::new(NAME, INTERFACE, VERSION, LAYOUT, CREATE) }
+            }),*];
+
+            $crate::plugin::PluginManifest::new(ENTRIES)
+        };
+    };
+}
+
+/// Errors that can occur while loading a plugin.
+#[derive(Debug)]
+pub enum Error {
+    /// The library was not found in any of the search paths.
+    NotFound(String),
+    /// The library could not be loaded.
+    Loading(libloading::Error),
+    /// The library does not export the plugin header, or manifest.
+    Symbol(libloading::Error),
+    /// The manifest has no plugin with the given name.
+    Entry(String),
+    /// Name or interface of a manifest entry is not valid UTF-8.
+    InvalidEntry(core::str::Utf8Error),
+    /// The header was built by an incompatible version of CGlue.
+    AbiVersion { expected: u32, found: u32 },
+    /// The plugin was built for a different version of the interface.
+    Version { expected: u32, found: u32 },
+    /// Layout of the plugin object does not match the expected one.
+    Layout,
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Error::NotFound(name) => write!(f, "plugin library {} not found", name),
+            Error::Loading(e) => write!(f, "unable to load plugin library: {}", e),
+            Error::Symbol(e) => write!(f, "plugin symbol not found: {}", e),
+            Error::Entry(name) => write!(f, "plugin {} not found in the manifest", name),
+            Error::InvalidEntry(e) => write!(f, "invalid plugin manifest entry: {}", e),
+            Error::AbiVersion { expected, found } => write!(
+                f,
+                "plugin header version mismatch (expected {}, found {})",
+                expected, found
+            ),
+            Error::Version { expected, found } => write!(
+                f,
+                "plugin interface version mismatch (expected {}, found {})",
+                expected, found
+            ),
+            Error::Layout => write!(f, "plugin layout mismatch"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Loading(e) | Error::Symbol(e) => Some(e),
+            Error::InvalidEntry(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Loads plugin libraries.
+///
+/// # Examples
+///
+/// ```
+/// # __export_abi_stable!();
+/// use cglue::plugin::{Error, Loader};
+/// use cglue::prelude::v1::*;
+///
+/// #[cglue_trait]
+/// pub trait Greeter {
+///     fn greet(&self) -> usize;
+/// }
+///
+/// # fn main() {
+/// let loader = Loader::new(1).search_path("plugins");
+///
+/// let res = unsafe { loader.load::<GreeterArcBox<'static>>("greeter") };
+/// assert!(matches!(res, Err(Error::NotFound(_))));
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Loader {
+    paths: Vec<PathBuf>,
+    version: u32,
+}
+
+impl Loader {
+    /// Create a loader for plugins of the given interface version.
+    ///
+    /// Directory of the current executable is searched by default.
+    pub fn new(version: u32) -> Self {
+        let paths = std::env::current_exe()
+            .ok()
+            .and_then(|p| p.parent().map(Path::to_path_buf))
+            .into_iter()
+            .collect();
+
+        Self { paths, version }
+    }
+
+    /// Add a directory to search plugins in.
+    ///
+    /// Directories are searched in the order they were added.
+    pub fn search_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.paths.push(path.into());
+        self
+    }
+
+    /// Remove all search paths, including the default one.
+    pub fn clear_search_paths(mut self) -> Self {
+        self.paths.clear();
+        self
+    }
+
+    /// Get the directories plugins are searched in.
+    pub fn search_paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Find the library of a plugin.
+    ///
+    /// `name` is the name of the library without platform specific prefix or extension, for
+    /// instance, `plugin_lib` for `libplugin_lib.so`.
+    pub fn find(&self, name: &str) -> Result<PathBuf, Error> {
+        let file_name = library_filename(name);
+
+        self.paths
+            .iter()
+            .map(|p| p.join(&file_name))
+            .find(|p| p.is_file())
+            .ok_or_else(|| Error::NotFound(name.into()))
+    }
+
+    /// Find a plugin library and load the plugin.
+    ///
+    /// # Safety
+    ///
+    /// Loading a library runs its initialization routines. Besides, the library must export a
+    /// valid [`PluginHeader`] under the [`PLUGIN_HEADER`] symbol. Layout of the object is verified,
+    /// but types of the symbols themselves can not be.
+    pub unsafe fn load<T: StableAbi>(&self, name: &str) -> Result<T, Error> {
+        self.load_path(self.find(name)?)
+    }
+
+    /// Load a plugin from the library at the given path.
+    ///
+    /// # Safety
+    ///
+    /// Same rules as in [`load`](Self::load) apply.
+    pub unsafe fn load_path<T: StableAbi>(&self, path: impl AsRef<Path>) -> Result<T, Error> {
+        let lib = Library::new(path.as_ref()).map_err(Error::Loading)?;
+
+        let header = *lib
+            .get::<*const PluginHeader<T>>(PLUGIN_HEADER.as_bytes())
+            .map_err(Error::Symbol)?;
+        // The header stays valid for as long as the library is loaded.
+        let header = &*header;
+
+        self.verify(header)?;
+
+        let lib = CArc::from(lib).into_opaque();
+        Ok((header.create)(&lib))
+    }
+
+    /// Find a plugin library and open its manifest.
+    ///
+    /// # Safety
+    ///
+    /// Loading a library runs its initialization routines. Besides, the library must export a
+    /// valid [`PluginManifest`] under the [`PLUGIN_MANIFEST`] symbol.
+    pub unsafe fn open(&self, name: &str) -> Result<PluginLibrary, Error> {
+        self.open_path(self.find(name)?)
+    }
+
+    /// Open the manifest of the library at the given path.
+    ///
+    /// # Safety
+    ///
+    /// Same rules as in [`open`](Self::open) apply.
+    pub unsafe fn open_path(&self, path: impl AsRef<Path>) -> Result<PluginLibrary, Error> {
+        let lib = Library::new(path.as_ref()).map_err(Error::Loading)?;
+
+        let manifest = *lib
+            .get::<*const PluginManifest>(PLUGIN_MANIFEST.as_bytes())
+            .map_err(Error::Symbol)?;
+
+        // The manifest stays valid for as long as the library is loaded, which is kept alive
+        // by the returned object.
+        self.open_with(&*manifest, CArc::from(lib).into_opaque())
+    }
+
+    /// Open a manifest that is linked into the executable.
+    ///
+    /// This allows built-in plugins to be used the same way as the loaded ones.
+    pub fn open_static(&self, manifest: &'static PluginManifest) -> Result<PluginLibrary, Error> {
+        self.open_with(manifest, CArc::default())
+    }
+
+    fn open_with(
+        &self,
+        manifest: &'static PluginManifest,
+        lib: CArc<c_void>,
+    ) -> Result<PluginLibrary, Error> {
+        manifest.verify()?;
+
+        Ok(PluginLibrary {
+            lib,
+            manifest,
+            version: self.version,
+        })
+    }
+
+    /// Verify that the header describes a plugin compatible with the loader.
+    pub fn verify<T: StableAbi>(&self, header: &PluginHeader<T>) -> Result<(), Error> {
+        Self::verify_abi(header.abi_version)?;
+        Self::verify_object::<T>(self.version, header.version, header.layout)
+    }
+
+    fn verify_abi(abi_version: u32) -> Result<(), Error> {
+        if abi_version != PLUGIN_ABI_VERSION {
+            return Err(Error::AbiVersion {
+                expected: PLUGIN_ABI_VERSION,
+                found: abi_version,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn verify_object<T: StableAbi>(
+        expected: u32,
+        version: u32,
+        layout: &'static TypeLayout,
+    ) -> Result<(), Error> {
+        if version != expected {
+            return Err(Error::Version {
+                expected,
+                found: version,
+            });
+        }
+
+        if !compare_layouts(Some(T::LAYOUT), Some(layout)).is_valid_strict() {
+            return Err(Error::Layout);
+        }
+
+        Ok(())
+    }
+}
+
+/// Library with a manifest of plugins.
+///
+/// The library stays loaded for as long as this object, or any of the objects built out of it
+/// are alive.
+pub struct PluginLibrary {
+    lib: CArc<c_void>,
+    // Only valid while `lib` is loaded, thus never handed out past the borrow of `self`.
+    manifest: &'static PluginManifest,
+    version: u32,
+}
+
+impl PluginLibrary {
+    /// Get the manifest of the library.
+    pub fn manifest(&self) -> &PluginManifest {
+        self.manifest
+    }
+
+    /// Get all plugins of the library.
+    pub fn entries(&self) -> &[PluginEntry] {
+        self.manifest.entries()
+    }
+
+    /// Get the plugins that implement the given interface.
+    pub fn entries_of<'b>(&'b self, interface: &'b str) -> impl Iterator<Item = &'b PluginEntry> {
+        self.entries()
+            .iter()
+            .filter(move |e| e.interface() == interface)
+    }
+
+    /// Find a plugin by name.
+    pub fn entry(&self, name: &str) -> Option<&PluginEntry> {
+        self.entries().iter().find(|e| e.name() == name)
+    }
+
+    /// Build the object of the named plugin.
+    ///
+    /// Version of the plugin must match the version of the loader that opened the library, and
+    /// layout of its object must match `T`.
+    pub fn create<T: StableAbi>(&self, name: &str) -> Result<T, Error> {
+        let entry = self.entry(name).ok_or_else(|| Error::Entry(name.into()))?;
+
+        Loader::verify_object::<T>(self.version, entry.version, entry.layout)?;
+
+        // Layout of the return value has been verified above.
+        let create: extern "C" fn(&CArc<c_void>) -> T =
+            unsafe { core::mem::transmute(entry.create) };
+
+        Ok(create(&self.lib))
+    }
+}
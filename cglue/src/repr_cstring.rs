@@ -60,6 +60,23 @@ impl From<String> for ReprCString {
     }
 }
 
+/// Convert back into a `String`.
+///
+/// # Examples
+///
+/// ```
+/// use cglue::repr_cstring::ReprCString;
+///
+/// let s = ReprCString::from("hello");
+///
+/// assert_eq!(String::from(s), "hello");
+/// ```
+impl From<ReprCString> for String {
+    fn from(from: ReprCString) -> Self {
+        from.as_ref().into()
+    }
+}
+
 impl<'a> std::borrow::Borrow<ReprCStr<'a>> for ReprCString {
     fn borrow(&self) -> &ReprCStr<'a> {
         unsafe { &*(self as *const _ as *const _) }
@@ -245,3 +262,65 @@ impl<'a> serde::Serialize for ReprCStr<'a> {
         serializer.serialize_str(self.as_ref())
     }
 }
+
+/// Borrowed deserialization of C strings.
+///
+/// Since the string data is borrowed, the input must already contain the null-terminator. This
+/// only works with formats that are able to lend out strings from the input, and that do not
+/// escape the null character.
+///
+/// # Examples
+///
+/// ```
+/// use cglue::repr_cstring::ReprCStr;
+/// use serde::de::{value::BorrowedStrDeserializer, value::Error, Deserialize};
+///
+/// let deserializer = BorrowedStrDeserializer::<Error>::new("hello\0");
+/// let s = ReprCStr::deserialize(deserializer).unwrap();
+///
+/// assert_eq!(s.as_ref(), "hello");
+///
+/// let deserializer = BorrowedStrDeserializer::<Error>::new("hello");
+/// assert!(ReprCStr::deserialize(deserializer).is_err());
+/// ```
+#[cfg(feature = "serde")]
+impl<'de: 'a, 'a> serde::Deserialize<'de> for ReprCStr<'a> {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct ReprCStrVisitor;
+
+        impl<'de> ::serde::de::Visitor<'de> for ReprCStrVisitor {
+            type Value = ReprCStr<'de>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a borrowed null-terminated string")
+            }
+
+            fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+            where
+                E: ::serde::de::Error,
+            {
+                match v.find('\0') {
+                    Some(pos) if pos == v.len() - 1 => {
+                        Ok(ReprCStr(unsafe { &*(v.as_ptr() as *const c_char) }))
+                    }
+                    _ => Err(E::invalid_value(::serde::de::Unexpected::Str(v), &self)),
+                }
+            }
+
+            fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+            where
+                E: ::serde::de::Error,
+            {
+                match core::str::from_utf8(v) {
+                    Ok(v) => self.visit_borrowed_str(v),
+                    Err(_) => Err(E::invalid_value(::serde::de::Unexpected::Bytes(v), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_str(ReprCStrVisitor)
+    }
+}
@@ -19,6 +19,7 @@
 //! It is a helper trait that is implemented on all `Result<T, E>` types where `E` implements
 //! [IntError](crate::result::IntError).
 //!
+use crate::option::COption;
 use core::mem::MaybeUninit;
 use core::num::NonZeroI32;
 
@@ -30,8 +31,34 @@ use core::num::NonZeroI32;
 ///
 /// But preferred way to pass results efficiently would be to implement `IntError` trait on the `E`
 /// type.
+///
+/// Most of the commonly used methods of `Result` are available directly, for instance:
+///
+/// ```
+/// use cglue::result::CResult;
+///
+/// let res: CResult<u8, &str> = CResult::Ok(2);
+///
+/// assert_eq!(res.map(|v| v * 2), CResult::Ok(4));
+/// assert_eq!(res.and_then(|v| v.checked_sub(3).ok_or("underflow").into()), CResult::Err("underflow"));
+/// assert_eq!(CResult::<u8, _>::Err(1).map_err(|e| e + 1).unwrap_err(), 2);
+/// ```
+///
+/// The `?` operator is available by converting into `Result`:
+///
+/// ```
+/// use cglue::result::CResult;
+///
+/// fn add(a: CResult<u8, ()>, b: CResult<u8, ()>) -> Result<u8, ()> {
+///     Ok(Result::<u8, ()>::from(a)? + Result::<u8, ()>::from(b)?)
+/// }
+///
+/// assert_eq!(add(CResult::Ok(1), CResult::Ok(2)), Ok(3));
+/// assert_eq!(add(CResult::Ok(1), CResult::Err(())), Err(()));
+/// ```
 #[repr(C)]
 #[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum CResult<T, E> {
     Ok(T),
     Err(E),
@@ -55,6 +82,33 @@ impl<T, E> From<CResult<T, E>> for Result<T, E> {
     }
 }
 
+impl<T, E> IntoIterator for CResult<T, E> {
+    type Item = T;
+    type IntoIter = core::option::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ok().into_iter()
+    }
+}
+
+impl<'a, T, E> IntoIterator for &'a CResult<T, E> {
+    type Item = &'a T;
+    type IntoIter = core::option::IntoIter<&'a T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, E> IntoIterator for &'a mut CResult<T, E> {
+    type Item = &'a mut T;
+    type IntoIter = core::option::IntoIter<&'a mut T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
 impl<T, E> CResult<T, E> {
     pub fn is_ok(&self) -> bool {
         matches!(*self, CResult::Ok(_))
@@ -71,6 +125,48 @@ impl<T, E> CResult<T, E> {
         Result::from(self).unwrap()
     }
 
+    pub fn expect(self, msg: &str) -> T
+    where
+        E: core::fmt::Debug,
+    {
+        Result::from(self).expect(msg)
+    }
+
+    pub fn unwrap_err(self) -> E
+    where
+        T: core::fmt::Debug,
+    {
+        Result::from(self).unwrap_err()
+    }
+
+    pub fn expect_err(self, msg: &str) -> E
+    where
+        T: core::fmt::Debug,
+    {
+        Result::from(self).expect_err(msg)
+    }
+
+    pub fn unwrap_or(self, default: T) -> T {
+        match self {
+            CResult::Ok(x) => x,
+            CResult::Err(_) => default,
+        }
+    }
+
+    pub fn unwrap_or_else<F: FnOnce(E) -> T>(self, op: F) -> T {
+        match self {
+            CResult::Ok(x) => x,
+            CResult::Err(e) => op(e),
+        }
+    }
+
+    pub fn unwrap_or_default(self) -> T
+    where
+        T: Default,
+    {
+        self.unwrap_or_else(|_| T::default())
+    }
+
     pub fn ok(self) -> Option<T> {
         match self {
             CResult::Ok(x) => Some(x),
@@ -78,6 +174,77 @@ impl<T, E> CResult<T, E> {
         }
     }
 
+    pub fn err(self) -> Option<E> {
+        match self {
+            CResult::Err(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    pub fn map<U, F: FnOnce(T) -> U>(self, op: F) -> CResult<U, E> {
+        match self {
+            CResult::Ok(x) => CResult::Ok(op(x)),
+            CResult::Err(e) => CResult::Err(e),
+        }
+    }
+
+    pub fn map_err<F, O: FnOnce(E) -> F>(self, op: O) -> CResult<T, F> {
+        match self {
+            CResult::Ok(x) => CResult::Ok(x),
+            CResult::Err(e) => CResult::Err(op(e)),
+        }
+    }
+
+    pub fn map_or<U, F: FnOnce(T) -> U>(self, default: U, f: F) -> U {
+        match self {
+            CResult::Ok(x) => f(x),
+            CResult::Err(_) => default,
+        }
+    }
+
+    pub fn map_or_else<U, D: FnOnce(E) -> U, F: FnOnce(T) -> U>(self, default: D, f: F) -> U {
+        match self {
+            CResult::Ok(x) => f(x),
+            CResult::Err(e) => default(e),
+        }
+    }
+
+    pub fn and<U>(self, res: CResult<U, E>) -> CResult<U, E> {
+        match self {
+            CResult::Ok(_) => res,
+            CResult::Err(e) => CResult::Err(e),
+        }
+    }
+
+    pub fn and_then<U, F: FnOnce(T) -> CResult<U, E>>(self, op: F) -> CResult<U, E> {
+        match self {
+            CResult::Ok(x) => op(x),
+            CResult::Err(e) => CResult::Err(e),
+        }
+    }
+
+    pub fn or<F>(self, res: CResult<T, F>) -> CResult<T, F> {
+        match self {
+            CResult::Ok(x) => CResult::Ok(x),
+            CResult::Err(_) => res,
+        }
+    }
+
+    pub fn or_else<F, O: FnOnce(E) -> CResult<T, F>>(self, op: O) -> CResult<T, F> {
+        match self {
+            CResult::Ok(x) => CResult::Ok(x),
+            CResult::Err(e) => op(e),
+        }
+    }
+
+    pub fn iter(&self) -> core::option::IntoIter<&T> {
+        self.as_ref().ok().into_iter()
+    }
+
+    pub fn iter_mut(&mut self) -> core::option::IntoIter<&mut T> {
+        self.as_mut().ok().into_iter()
+    }
+
     pub fn as_ref(&self) -> Result<&T, &E> {
         match *self {
             CResult::Ok(ref x) => Ok(x),
@@ -93,6 +260,50 @@ impl<T, E> CResult<T, E> {
     }
 }
 
+impl<T, E> CResult<COption<T>, E> {
+    /// Transpose a result of an option into an option of a result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::option::COption;
+    /// use cglue::result::CResult;
+    ///
+    /// let x: CResult<COption<u8>, ()> = CResult::Ok(COption::Some(5));
+    ///
+    /// assert_eq!(x.transpose(), COption::Some(CResult::Ok(5)));
+    /// ```
+    pub fn transpose(self) -> COption<CResult<T, E>> {
+        match self {
+            CResult::Ok(COption::Some(x)) => COption::Some(CResult::Ok(x)),
+            CResult::Ok(COption::None) => COption::None,
+            CResult::Err(e) => COption::Some(CResult::Err(e)),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize, E: serde::Serialize> serde::Serialize for CResult<T, E> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.as_ref().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>, E: serde::Deserialize<'de>> serde::Deserialize<'de>
+    for CResult<T, E>
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Result::deserialize(deserializer).map(Self::from)
+    }
+}
+
 /// Helper trait for integer errors.
 ///
 /// This trait essentially forwards [`into_int_result`](crate::result::into_int_result), and
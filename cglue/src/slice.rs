@@ -307,3 +307,70 @@ impl<'a, T> core::ops::DerefMut for CSliceMut<'a, T> {
         unsafe { core::slice::from_raw_parts_mut(self.data, self.len) }
     }
 }
+
+#[cfg(feature = "serde")]
+impl<'a, T: serde::Serialize> serde::Serialize for CSliceRef<'a, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(&**self)
+    }
+}
+
+/// Borrowed deserialization of byte slices.
+///
+/// This only works with formats that are able to lend out bytes or strings from the input.
+///
+/// # Examples
+///
+/// ```
+/// use cglue::slice::CSliceRef;
+///
+/// let slice: CSliceRef<u8> = serde_json::from_str("\"hello\"").unwrap();
+///
+/// assert_eq!(slice.as_slice(), b"hello");
+/// ```
+#[cfg(feature = "serde")]
+impl<'de: 'a, 'a> serde::Deserialize<'de> for CSliceRef<'a, u8> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct CSliceRefVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for CSliceRefVisitor {
+            type Value = CSliceRef<'de, u8>;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                formatter.write_str("borrowed bytes")
+            }
+
+            fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(v.into())
+            }
+
+            fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(v.into())
+            }
+        }
+
+        deserializer.deserialize_bytes(CSliceRefVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a, T: serde::Serialize> serde::Serialize for CSliceMut<'a, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(&**self)
+    }
+}
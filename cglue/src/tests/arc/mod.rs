@@ -110,3 +110,52 @@ fn use_clone_obj() {
 
     assert_eq!(Arc::strong_count(&arc), 1);
 }
+
+#[test]
+fn use_weak_ctx_obj() {
+    let sa = SA {};
+
+    let arc = CArc::<()>::from(());
+
+    assert_eq!(arc.weak_count(), Some(0));
+
+    let weak = arc.downgrade();
+
+    assert_eq!(arc.weak_count(), Some(1));
+
+    let getter = trait_obj!((sa, weak) as DoerGetter);
+
+    let doer = getter.dget_1();
+
+    assert_eq!(arc.weak_count(), Some(2));
+    assert_eq!(arc.strong_count(), Some(1));
+
+    std::mem::drop(getter);
+
+    assert_eq!(arc.weak_count(), Some(1));
+
+    std::mem::drop(doer);
+
+    assert_eq!(arc.weak_count(), Some(0));
+}
+
+#[test]
+fn upgrade_weak_ctx() {
+    let arc = CArc::<u64>::from(42);
+    let weak = arc.downgrade();
+
+    let upgraded = weak.upgrade().unwrap();
+
+    assert_eq!(*upgraded, 42);
+    assert_eq!(weak.strong_count(), 2);
+
+    let arc = arc.transpose().unwrap();
+
+    assert!(CArcSome::ptr_eq(&upgraded, &arc));
+
+    std::mem::drop(upgraded);
+    std::mem::drop(arc);
+
+    assert_eq!(weak.strong_count(), 0);
+    assert!(weak.upgrade().is_none());
+}
@@ -1,4 +1,5 @@
 use super::super::simple::structs::*;
+use crate::boxed::CSyncBox;
 use cglue_macro::*;
 
 pub struct ExtraFeatureWrap<T> {
@@ -49,6 +50,6 @@ impl Basic for SA {}
 
 #[test]
 fn test_wrap() {
-    let basic = trait_obj!(SA {} as Basic);
+    let basic = trait_obj!(CSyncBox::from(SA {}) as Basic);
     assert_eq!(basic.b_1().ef_1(), 42);
 }
@@ -0,0 +1,105 @@
+//! These tests check traits and groups with const generic parameters.
+use super::super::simple::structs::*;
+use cglue_macro::*;
+
+#[cglue_trait]
+pub trait Register<const N: usize> {
+    fn read(&self, idx: usize) -> u8;
+
+    fn width(&self) -> usize {
+        N
+    }
+}
+
+#[cglue_trait]
+pub trait Clear<const N: usize> {
+    fn clear(&mut self) -> usize;
+}
+
+impl Register<4> for SA {
+    fn read(&self, idx: usize) -> u8 {
+        42u32.to_le_bytes()[idx]
+    }
+}
+
+pub struct Buf<const N: usize> {
+    data: [u8; N],
+}
+
+impl<const N: usize> Register<N> for Buf<N> {
+    fn read(&self, idx: usize) -> u8 {
+        self.data[idx]
+    }
+}
+
+impl<const N: usize> Clear<N> for Buf<N> {
+    fn clear(&mut self) -> usize {
+        self.data = [0; N];
+        N
+    }
+}
+
+cglue_trait_group!(RegisterGroup<const N: usize>, Register<N>, { Clear<N> });
+
+cglue_impl_group!(Buf<const N: usize>, RegisterGroup<N>, { Clear<N> });
+
+cglue_impl_group!(SA, RegisterGroup<4>);
+
+cglue_trait_group!(WordGroup, Register<4>, { Clear<4> });
+
+cglue_impl_group!(Buf<4>, WordGroup, { Clear<4> });
+
+#[test]
+fn use_const_trait() {
+    let sa = SA {};
+
+    let obj = trait_obj!(sa as Register);
+
+    let out = [obj.read(0), obj.read(1), obj.read(2), obj.read(3)];
+
+    assert_eq!(u32::from_le_bytes(out), 42);
+    assert_eq!(obj.width(), 4);
+}
+
+#[test]
+fn use_const_trait_explicit() {
+    let buf = Buf { data: [1, 2] };
+
+    let obj = trait_obj!(buf as Register<2>);
+
+    assert_eq!([obj.read(0), obj.read(1)], [1, 2]);
+    assert_eq!(obj.width(), 2);
+}
+
+#[test]
+fn use_const_group() {
+    let buf = Buf { data: [1, 2, 3] };
+
+    let obj = group_obj!(buf as RegisterGroup<3>);
+
+    let mut obj = cast!(obj impl Clear).unwrap();
+
+    assert_eq!(obj.read(2), 3);
+    assert_eq!(obj.clear(), 3);
+    assert_eq!(obj.read(2), 0);
+}
+
+#[test]
+fn use_const_group_no_opt() {
+    let sa = SA {};
+
+    let obj = group_obj!(sa as RegisterGroup);
+
+    assert!(!check!(obj impl Clear));
+    assert_eq!(obj.width(), 4);
+}
+
+#[test]
+fn use_concrete_group() {
+    let buf = Buf { data: [1, 2, 3, 4] };
+
+    let mut obj = group_obj!(buf as WordGroup);
+
+    assert_eq!(as_mut!(obj impl Clear).unwrap().clear(), 4);
+    assert_eq!(obj.read(3), 0);
+}
@@ -45,3 +45,12 @@ fn use_mixed_group_explicit() {
 
     println!("Val: {}", obj.gt_1());
 }
+
+#[test]
+fn use_group_inline() {
+    let sa = crate::inline::CInline::<_, 8>::from(SA {});
+
+    let obj: MixedGenericGroupInline<usize, usize, 8> = group_obj!(sa as MixedGenericGroup);
+
+    println!("Val: {}", obj.gt_1());
+}
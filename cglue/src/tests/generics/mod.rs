@@ -1,5 +1,6 @@
 pub mod associated;
 pub mod associated_ref;
+pub mod consts;
 #[cfg(gats_on_stable)]
 pub mod generic_associated;
 pub mod generic_structs;
@@ -2,4 +2,8 @@ pub mod arc;
 pub mod ext;
 pub mod extra;
 pub mod generics;
+#[cfg(feature = "plugin")]
+pub mod plugin;
+#[cfg(feature = "serde")]
+pub mod serde;
 pub mod simple;
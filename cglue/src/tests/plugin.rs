@@ -0,0 +1,169 @@
+//! These tests check verification and lookup of plugins
+use super::simple::structs::*;
+use super::simple::trait_defs::*;
+use crate::arc::CArc;
+use crate::plugin::*;
+use crate::trait_group::c_void;
+use cglue_macro::*;
+use std::path::PathBuf;
+
+extern "C" fn create(lib: &CArc<c_void>) -> TAArcBox<'static> {
+    trait_obj!((SA {}, lib.clone()) as TA)
+}
+
+extern "C" fn create_sb(lib: &CArc<c_void>) -> TAArcBox<'static> {
+    trait_obj!((SB {}, lib.clone()) as TA)
+}
+
+extern "C" fn create_tb(lib: &CArc<c_void>) -> TBArcBox<'static> {
+    trait_obj!((SB {}, lib.clone()) as TB)
+}
+
+crate::declare_plugin_manifest!(
+    ("sa", "ta", TAArcBox<'static>, 2, create),
+    ("sb", "ta", TAArcBox<'static>, 2, create_sb),
+    ("sb_tb", "tb", TBArcBox<'static>, 2, create_tb),
+);
+
+fn header(
+    abi_version: u32,
+    version: u32,
+    layout: &'static TypeLayout,
+) -> PluginHeader<TAArcBox<'static>> {
+    PluginHeader {
+        abi_version,
+        version,
+        layout,
+        create,
+    }
+}
+
+#[test]
+fn verify_header() {
+    let loader = Loader::new(2);
+    let layout = <TAArcBox<'static> as StableAbi>::LAYOUT;
+
+    assert!(loader
+        .verify(&header(PLUGIN_ABI_VERSION, 2, layout))
+        .is_ok());
+
+    assert!(matches!(
+        loader.verify(&header(PLUGIN_ABI_VERSION + 1, 2, layout)),
+        Err(Error::AbiVersion { .. })
+    ));
+
+    assert!(matches!(
+        loader.verify(&header(PLUGIN_ABI_VERSION, 1, layout)),
+        Err(Error::Version {
+            expected: 2,
+            found: 1
+        })
+    ));
+
+    assert!(matches!(
+        loader.verify(&header(PLUGIN_ABI_VERSION, 2, <u64 as StableAbi>::LAYOUT)),
+        Err(Error::Layout)
+    ));
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("cglue-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn find_library() {
+    let dir = temp_dir("find");
+    let path = dir.join(libloading::library_filename("dummy"));
+    std::fs::write(&path, b"").unwrap();
+
+    let loader = Loader::new(1).clear_search_paths();
+    assert!(loader.search_paths().is_empty());
+    assert!(matches!(loader.find("dummy"), Err(Error::NotFound(_))));
+
+    let loader = loader.search_path("/nonexistent").search_path(&dir);
+    assert_eq!(loader.find("dummy").unwrap(), path);
+    assert!(matches!(loader.find("other"), Err(Error::NotFound(_))));
+
+    let res = unsafe { loader.load::<TAArcBox<'static>>("dummy") };
+    assert!(matches!(res, Err(Error::Loading(_))));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn manifest_entries() {
+    let lib = Loader::new(2).open_static(&PLUGIN_MANIFEST).unwrap();
+
+    let names = lib
+        .entries()
+        .iter()
+        .map(PluginEntry::name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["sa", "sb", "sb_tb"]);
+
+    let names = lib
+        .entries_of("ta")
+        .map(PluginEntry::name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["sa", "sb"]);
+
+    assert_eq!(lib.entry("sb_tb").unwrap().interface(), "tb");
+    assert!(lib.entry("sc").is_none());
+}
+
+#[test]
+fn manifest_create() {
+    let lib = Loader::new(2).open_static(&PLUGIN_MANIFEST).unwrap();
+
+    let obj: TAArcBox = lib.create("sa").unwrap();
+    assert_eq!(obj.ta_1(), 5);
+
+    let obj: TAArcBox = lib.create("sb").unwrap();
+    assert_eq!(obj.ta_1(), 6);
+
+    let obj: TBArcBox = lib.create("sb_tb").unwrap();
+    assert_eq!(obj.tb_1(2), 4);
+
+    assert!(matches!(lib.create::<TAArcBox>("sc"), Err(Error::Entry(_))));
+    assert!(matches!(lib.create::<TBArcBox>("sa"), Err(Error::Layout)));
+
+    let lib = Loader::new(1).open_static(&PLUGIN_MANIFEST).unwrap();
+    assert!(matches!(
+        lib.create::<TAArcBox>("sa"),
+        Err(Error::Version { .. })
+    ));
+}
+
+#[test]
+fn manifest_abi_version() {
+    let manifest = Box::leak(Box::new(PluginManifest::new(&[])));
+    assert_eq!(manifest.abi_version(), PLUGIN_ABI_VERSION);
+
+    // Simulate a manifest built by an incompatible version, the version is the first field.
+    unsafe { *(manifest as *mut PluginManifest).cast::<u32>() = PLUGIN_ABI_VERSION + 1 };
+
+    assert!(matches!(
+        Loader::new(2).open_static(manifest),
+        Err(Error::AbiVersion { .. })
+    ));
+}
+
+#[test]
+fn manifest_invalid_entry() {
+    let name: &[u8] = Box::leak(Box::new([0xffu8]));
+    // Simulate an entry built by a foreign library.
+    let name = unsafe { core::str::from_utf8_unchecked(name) };
+    let layout = <TAArcBox<'static> as StableAbi>::LAYOUT;
+
+    let entries = Box::leak(Box::new([unsafe {
+        PluginEntry::new(name, "ta", 2, layout, create)
+    }]));
+    let manifest = Box::leak(Box::new(PluginManifest::new(entries)));
+
+    assert!(matches!(
+        Loader::new(2).open_static(manifest),
+        Err(Error::InvalidEntry(_))
+    ));
+}
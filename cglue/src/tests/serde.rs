@@ -0,0 +1,68 @@
+//! These tests check serialization of FFI-safe types
+use crate::arc::{CArc, CArcSome};
+use crate::boxed::{CBox, CSliceBox};
+use crate::option::COption;
+use crate::repr_cstring::ReprCString;
+use crate::result::CResult;
+use crate::slice::CSliceRef;
+use crate::tuple::CTup2;
+use crate::vec::CVec;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Config {
+    name: ReprCString,
+    values: CVec<u32>,
+    limits: CSliceBox<'static, u8>,
+    parent: COption<CBox<'static, u64>>,
+    shared: CArc<u64>,
+    shared_some: CArcSome<u64>,
+    status: CResult<u8, ReprCString>,
+    pair: CTup2<u8, u16>,
+}
+
+#[test]
+fn roundtrip_config() {
+    let config = Config {
+        name: "config".into(),
+        values: vec![1, 2, 3].into(),
+        limits: vec![4, 5].into_boxed_slice().into(),
+        parent: COption::Some(CBox::from(6)),
+        shared: CArc::default(),
+        shared_some: CArcSome::from(7),
+        status: CResult::Err("failed".into()),
+        pair: CTup2(8, 9),
+    };
+
+    let json = serde_json::to_string(&config).unwrap();
+
+    assert_eq!(
+        json,
+        r#"{"name":"config","values":[1,2,3],"limits":[4,5],"parent":6,"shared":null,"shared_some":7,"status":{"Err":"failed"},"pair":[8,9]}"#
+    );
+
+    let config: Config = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(config.name.as_ref(), "config");
+    assert_eq!(&*config.values, &[1, 2, 3]);
+    assert_eq!(&*config.limits, &[4, 5]);
+    assert_eq!(config.parent.map(|v| *v), COption::Some(6));
+    assert!(config.shared.as_ref().is_none());
+    assert_eq!(*config.shared_some, 7);
+    assert_eq!(
+        config.status.map_err(|e| e.as_ref().to_string()),
+        CResult::Err("failed".to_string())
+    );
+    assert_eq!(config.pair, CTup2(8, 9));
+}
+
+#[test]
+fn borrow_slice() {
+    let json = String::from(r#"["hello","world"]"#);
+
+    let slices: Vec<CSliceRef<u8>> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(slices[0].as_slice(), b"hello");
+    assert_eq!(slices[1].as_slice(), b"world");
+    assert!(serde_json::from_str::<CSliceRef<u8>>(r#""escaped\n""#).is_err());
+}
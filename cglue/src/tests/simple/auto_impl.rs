@@ -0,0 +1,97 @@
+//! These tests check automatic detection of optional traits in group implementations.
+use super::structs::*;
+use super::trait_defs::*;
+use crate::boxed::CUnsendBox;
+use cglue_macro::*;
+use std::rc::Rc;
+
+cglue_trait_group!(AutoGroup, TA, { TB, TC, TT<usize> });
+cglue_impl_group!(SA, AutoGroup, auto);
+cglue_impl_group!(SB, AutoGroup, auto);
+
+#[derive(Default)]
+struct RcState {
+    val: Rc<usize>,
+}
+
+impl TA for RcState {
+    extern "C" fn ta_1(&self) -> usize {
+        *self.val
+    }
+}
+
+impl TB for RcState {
+    extern "C" fn tb_1(&self, val: usize) -> usize {
+        *self.val + val
+    }
+
+    fn tb_2(&self, val: usize) -> usize {
+        val
+    }
+}
+
+cglue_impl_group!(RcState, AutoGroup, auto);
+
+cglue_trait_group!(BoundGroup, TA, { TB, TC });
+cglue_impl_group!(T: TA + TC, BoundGroup, auto);
+
+#[test]
+fn auto_detect() {
+    let a = SA {};
+
+    let obj = group_obj!(a as AutoGroup);
+
+    assert!(!check!(obj impl TB));
+    assert!(check!(obj impl TC));
+    assert!(check!(obj impl TT<usize>));
+
+    let obj = cast!(obj impl TT<usize>).unwrap();
+    assert_eq!(obj.tt_1(21), 21);
+}
+
+#[test]
+fn auto_detect_other() {
+    let b = SB {};
+
+    let obj = group_obj!(b as AutoGroup);
+
+    assert!(check!(obj impl TB));
+    assert!(!check!(obj impl TC));
+    assert!(!check!(obj impl TT<usize>));
+}
+
+#[test]
+fn auto_detect_blanket() {
+    let a = SA {};
+
+    let mut obj = group_obj!(a as BoundGroup);
+
+    assert!(!check!(obj impl TB));
+
+    as_mut!(obj impl TC).unwrap().tc_2();
+}
+
+#[test]
+fn auto_detect_ref() {
+    let mut a = SA {};
+
+    let obj = AutoGroupBaseRef::<SA>::from(&a);
+    // Mutable functions of TC can not be called through a shared reference
+    assert!(!check!(obj impl TC));
+    assert!(check!(obj impl TT<usize>));
+
+    let obj: AutoGroupMut = group_obj!(&mut a as AutoGroup);
+    assert!(check!(obj impl TC));
+}
+
+#[test]
+fn auto_detect_unsend() {
+    let state = RcState::default();
+
+    let obj = group_obj!(CUnsendBox::from(state) as AutoGroup);
+
+    assert!(!check!(obj impl TC));
+
+    let obj = cast!(obj impl TB).unwrap();
+    assert_eq!(obj.tb_1(5), 5);
+}
@@ -0,0 +1,77 @@
+//! These tests check associated constants stored within vtables.
+use cglue_macro::*;
+
+#[cglue_trait]
+#[cglue_forward]
+pub trait PluginInfo {
+    const NAME: &'static str;
+    const MAX_BATCH: usize;
+    const IDS: &'static [u32];
+    const REVISION: u8 = 1;
+
+    fn batch(&self) -> usize;
+}
+
+#[cglue_trait]
+pub trait Resettable {
+    const RESET_VALUE: usize;
+
+    fn reset(&mut self);
+}
+
+struct Plugin {
+    value: usize,
+}
+
+impl PluginInfo for Plugin {
+    const NAME: &'static str = "plugin";
+    const MAX_BATCH: usize = 16;
+    const IDS: &'static [u32] = &[1, 2, 3];
+
+    fn batch(&self) -> usize {
+        Self::MAX_BATCH / 2
+    }
+}
+
+impl Resettable for Plugin {
+    const RESET_VALUE: usize = 3;
+
+    fn reset(&mut self) {
+        self.value = Self::RESET_VALUE;
+    }
+}
+
+cglue_trait_group!(PluginGroup, PluginInfo, Resettable);
+
+cglue_impl_group!(Plugin, PluginGroup, Resettable);
+
+#[test]
+fn obj_consts() {
+    let obj = trait_obj!(Plugin { value: 0 } as PluginInfo);
+
+    assert_eq!(obj.const_name(), "plugin");
+    assert_eq!(obj.const_max_batch(), 16);
+    assert_eq!(obj.const_ids(), &[1, 2, 3]);
+    assert_eq!(obj.const_revision(), 1);
+    assert_eq!(obj.batch(), 8);
+}
+
+#[test]
+fn group_consts() {
+    let group = group_obj!(Plugin { value: 0 } as PluginGroup);
+
+    assert_eq!(group.const_name(), "plugin");
+
+    let mut group = cast!(group impl Resettable).unwrap();
+
+    assert_eq!(group.const_reset_value(), 3);
+    group.reset();
+}
+
+#[test]
+fn forward_consts() {
+    type Fwd<'a> = crate::forward::Fwd<&'a Plugin>;
+
+    assert_eq!(<Fwd as PluginInfo>::NAME, "plugin");
+    assert_eq!(<Fwd as PluginInfo>::IDS, &[1, 2, 3]);
+}
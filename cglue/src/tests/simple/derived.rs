@@ -0,0 +1,79 @@
+//! These tests check groups derived from other groups.
+use super::structs::*;
+use super::trait_defs::*;
+use cglue_macro::*;
+use core::convert::TryFrom;
+
+cglue_trait_group!(BaseGroup, TA, { TB, TC });
+cglue_impl_group!(SA, BaseGroup, { TC });
+cglue_impl_group!(SB, BaseGroup, { TB });
+
+cglue_trait_group!(DerivedGroup: BaseGroup, { TB }, { TC });
+cglue_impl_group!(SB, DerivedGroup, {});
+
+cglue_trait_group!(LeafGroup: DerivedGroup, {}, {});
+cglue_impl_group!(SB, LeafGroup);
+
+pub mod other {
+    use super::super::trait_defs::*;
+    use cglue_macro::*;
+
+    cglue_trait_group!(OtherGroup: super::BaseGroup, { TC }, {});
+}
+
+#[test]
+fn upcast() {
+    let b = SB {};
+
+    let obj = group_obj!(b as DerivedGroup);
+    assert_eq!(obj.tb_1(2), 4);
+
+    let obj = BaseGroupBox::from(obj);
+
+    assert!(check!(obj impl TB));
+    assert_eq!(obj.ta_1(), 6);
+    assert_eq!(as_ref!(obj impl TB).unwrap().tb_2(3), 9);
+}
+
+#[test]
+fn downcast() {
+    let b = SB {};
+
+    let obj = group_obj!(b as BaseGroup);
+
+    let obj = DerivedGroupBox::try_from(obj).ok().unwrap();
+
+    assert_eq!(obj.tb_2(4), 16);
+    assert!(!check!(obj impl TC));
+}
+
+#[test]
+fn downcast_fail() {
+    let a = SA {};
+
+    let obj = group_obj!(a as BaseGroup);
+
+    let obj = DerivedGroupBox::try_from(obj).err().unwrap();
+
+    assert_eq!(obj.ta_1(), 5);
+    assert!(check!(obj impl TC));
+
+    let obj = other::OtherGroupBox::try_from(obj).ok().unwrap();
+
+    obj.tc_1();
+}
+
+#[test]
+fn leaf_roundtrip() {
+    let b = SB {};
+
+    let obj = group_obj!(b as LeafGroup);
+
+    let obj = BaseGroupBox::from(DerivedGroupBox::from(obj));
+
+    let obj = LeafGroupBox::try_from(DerivedGroupBox::try_from(obj).ok().unwrap())
+        .ok()
+        .unwrap();
+
+    assert_eq!(obj.tb_1(3), 6);
+}
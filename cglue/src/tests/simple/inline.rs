@@ -0,0 +1,61 @@
+//! These tests check usage of trait objects with inline (small-buffer) containers
+use super::structs::*;
+use super::trait_defs::*;
+use super::trait_groups::*;
+use crate::inline::CInline;
+use cglue_macro::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+#[repr(C)]
+struct Large {
+    val: [usize; 8],
+}
+
+impl TA for Large {
+    extern "C" fn ta_1(&self) -> usize {
+        self.val.iter().sum()
+    }
+}
+
+impl Drop for Large {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn use_inline_obj() {
+    let sa = CInline::<_, 16>::from(SA {});
+    assert!(sa.is_inline());
+
+    let obj: TAInline<16> = trait_obj!(sa as TA);
+    assert_eq!(obj.ta_1(), 5);
+}
+
+#[test]
+fn use_inline_heap_fallback() {
+    let large = CInline::<_, 16>::from(Large { val: [1; 8] });
+    assert!(!large.is_inline());
+
+    let before = DROPPED.load(Ordering::Relaxed);
+
+    let obj: TAInline<16> = trait_obj!(large as TA);
+    assert_eq!(obj.ta_1(), 8);
+
+    std::mem::drop(obj);
+
+    assert_eq!(DROPPED.load(Ordering::Relaxed), before + 1);
+}
+
+#[test]
+fn use_inline_group() {
+    let sa = CInline::<_, 8>::from(SA {});
+
+    let group: TestGroupInline<8> = group_obj!(sa as TestGroup);
+    assert_eq!(group.ta_1(), 5);
+
+    let cast = cast!(group impl TC).unwrap();
+    cast.tc_1();
+}
@@ -0,0 +1,100 @@
+use super::structs::*;
+use crate::iter::{CLendingIterator, LendingIterator};
+use crate::slice::CSliceRef;
+use crate::*;
+
+#[cglue_trait]
+pub trait MemScanner {
+    fn scan(&mut self, chunks: CLendingIterator<CSliceRef<'static, u8>>) -> usize;
+}
+
+#[cglue_trait]
+pub trait MemSource<'a> {
+    fn chunks(&'a mut self) -> CLendingIterator<'a, CSliceRef<'static, u8>>;
+}
+
+pub struct Chunks {
+    buf: [u8; 4],
+    cnt: u8,
+}
+
+impl LendingIterator for Chunks {
+    type Item = CSliceRef<'static, u8>;
+
+    fn next(&mut self) -> Option<CSliceRef<'_, u8>> {
+        if self.cnt >= 3 {
+            return None;
+        }
+        self.cnt += 1;
+        self.buf = [self.cnt; 4];
+        Some((&self.buf[..]).into())
+    }
+}
+
+pub struct ChunkSource {
+    chunks: Chunks,
+}
+
+impl<'a> MemSource<'a> for ChunkSource {
+    fn chunks(&'a mut self) -> CLendingIterator<'a, CSliceRef<'static, u8>> {
+        (&mut self.chunks).into()
+    }
+}
+
+impl MemScanner for SA {
+    fn scan(&mut self, mut chunks: CLendingIterator<CSliceRef<'static, u8>>) -> usize {
+        let mut sum = 0;
+        while let Some(chunk) = chunks.next() {
+            sum += chunk.iter().map(|&v| v as usize).sum::<usize>();
+        }
+        sum
+    }
+}
+
+#[test]
+fn use_lending_arg() {
+    let mut chunks = Chunks {
+        buf: [0; 4],
+        cnt: 0,
+    };
+
+    let mut obj = trait_obj!(SA {} as MemScanner);
+
+    assert_eq!(obj.scan((&mut chunks).into()), 24);
+}
+
+#[test]
+fn use_lending_ret() {
+    let source = ChunkSource {
+        chunks: Chunks {
+            buf: [0; 4],
+            cnt: 0,
+        },
+    };
+
+    let mut obj = trait_obj!(source as MemSource);
+
+    let mut chunks = obj.chunks();
+
+    let first = chunks.next().unwrap();
+    assert_eq!(&*first, &[1; 4]);
+    let second = chunks.next().unwrap();
+    assert_eq!(&*second, &[2; 4]);
+}
+
+#[test]
+fn use_lending_both() {
+    let source = ChunkSource {
+        chunks: Chunks {
+            buf: [0; 4],
+            cnt: 0,
+        },
+    };
+
+    let mut src = trait_obj!(source as MemSource);
+    let mut scanner = trait_obj!(SA {} as MemScanner);
+
+    let mut chunks = src.chunks();
+
+    assert_eq!(scanner.scan((&mut chunks).into()), 24);
+}
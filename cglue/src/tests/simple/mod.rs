@@ -1,7 +1,19 @@
+pub mod auto_impl;
 pub mod bounded;
+pub mod consts;
 pub mod consuming;
+pub mod derived;
 pub mod hrtb;
+pub mod inline;
+pub mod lending;
+pub mod receivers;
+pub mod regroup;
+pub mod statics;
 pub mod structs;
+pub mod sync;
 pub mod trait_defs;
 pub mod trait_groups;
 pub mod traits;
+pub mod unsend;
+#[cfg(not(feature = "unstable"))]
+pub mod vtbl_table;
@@ -0,0 +1,72 @@
+//! These tests check `Box<Self>` and `Arc<Self>` receivers.
+use super::structs::*;
+use crate::arc::CArcSome;
+use crate::trait_group::{NoContext, Opaquable};
+use cglue_macro::*;
+use std::sync::Arc;
+
+#[cglue_trait]
+pub trait TBoxed {
+    fn tb_1(self: Box<Self>) -> usize;
+    fn tb_2(&self) -> usize;
+}
+
+impl TBoxed for SA {
+    fn tb_1(self: Box<Self>) -> usize {
+        43
+    }
+
+    fn tb_2(&self) -> usize {
+        42
+    }
+}
+
+#[cglue_trait]
+pub trait TShared {
+    fn ts_1(self: Arc<Self>) -> usize;
+}
+
+pub struct Shared {
+    value: usize,
+}
+
+impl TShared for Shared {
+    fn ts_1(self: Arc<Self>) -> usize {
+        self.value * Arc::strong_count(&self)
+    }
+}
+
+cglue_trait_group!(BoxedGroup, TBoxed, { TShared });
+
+cglue_impl_group!(SA, BoxedGroup);
+
+#[test]
+fn use_boxed() {
+    let sa = SA {};
+
+    let obj = trait_obj!(sa as TBoxed);
+
+    assert_eq!(obj.tb_2(), 42);
+    assert_eq!(Box::new(obj).tb_1(), 43);
+}
+
+#[test]
+fn use_boxed_group() {
+    let sa = SA {};
+
+    let obj = group_obj!(sa as BoxedGroup);
+
+    assert_eq!(Box::new(obj).tb_1(), 43);
+}
+
+#[test]
+fn use_shared() {
+    let arc = CArcSome::from(Shared { value: 7 });
+
+    let obj = TSharedBase::<_, NoContext>::from(arc.clone()).into_opaque();
+    let obj = Arc::new(obj);
+
+    // The arc in the object, and its clone for the call.
+    assert_eq!(obj.clone().ts_1(), 7 * 3);
+    assert_eq!(CArcSome::strong_count(&arc), Some(2));
+}
@@ -0,0 +1,78 @@
+//! These tests check conversions between unrelated groups that share traits.
+use super::hrtb::*;
+use super::structs::*;
+use super::trait_defs::*;
+use cglue_macro::*;
+
+cglue_trait_group!(FeaturesGroup, TA, { TB, TC });
+cglue_impl_group!(SA, FeaturesGroup, { TC });
+cglue_impl_group!(SB, FeaturesGroup, { TB });
+
+cglue_trait_group!(ReadOnlyGroup, TA, { TC });
+cglue_impl_group!(SA, ReadOnlyGroup, { TC });
+
+cglue_trait_group!(ClearGroup, { TA, TC }, {});
+
+cglue_trait_group!(BareGroup, TA, {});
+
+cglue_trait_group!(WrappingGroup, TA, { AsSubThing });
+cglue_impl_group!(SA, WrappingGroup, {});
+
+#[test]
+fn regroup_optional() {
+    let a = SA {};
+
+    let obj = group_obj!(a as FeaturesGroup);
+
+    let obj: ReadOnlyGroupBox = regroup!(obj as ReadOnlyGroup).ok().unwrap();
+
+    assert_eq!(obj.ta_1(), 5);
+    assert!(check!(obj impl TC));
+}
+
+#[test]
+fn regroup_missing_optional() {
+    let b = SB {};
+
+    let obj = group_obj!(b as FeaturesGroup);
+
+    let obj: ReadOnlyGroupBox = regroup!(obj as ReadOnlyGroup).ok().unwrap();
+
+    assert_eq!(obj.ta_1(), 6);
+    assert!(!check!(obj impl TC));
+}
+
+#[test]
+fn regroup_mandatory() {
+    let a = SA {};
+
+    let obj = group_obj!(a as ReadOnlyGroup);
+
+    let mut obj: ClearGroupBox = regroup!(obj as ClearGroup).ok().unwrap();
+
+    obj.tc_2();
+    assert_eq!(obj.ta_1(), 5);
+}
+
+#[test]
+fn regroup_fail() {
+    let b = SB {};
+
+    let obj = group_obj!(b as FeaturesGroup);
+
+    let obj = regroup!(obj as ClearGroup).err().unwrap();
+
+    assert_eq!(obj.ta_1(), 6);
+    assert!(check!(obj impl TB));
+}
+
+#[test]
+fn regroup_ret_tmp_fail() {
+    let a = SA {};
+
+    let obj = group_obj!(a as WrappingGroup);
+
+    let obj = regroup!(obj as BareGroup).err().unwrap();
+
+    assert_eq!(obj.ta_1(), 5);
+}
@@ -0,0 +1,159 @@
+//! These tests check associated functions exposed through factory tables.
+use cglue_macro::*;
+
+#[repr(C)]
+#[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+pub struct Config {
+    pub value: usize,
+}
+
+#[cglue_trait]
+pub trait Counter {
+    fn version() -> u32;
+    fn new(config: &Config) -> Self
+    where
+        Self: Sized;
+    fn with_name(name: &str) -> Option<Self>
+    where
+        Self: Sized;
+    fn parse(value: &str) -> Result<Self, usize>
+    where
+        Self: Sized;
+    fn default_scale() -> usize {
+        1
+    }
+
+    fn count(&self) -> usize;
+}
+
+struct Count {
+    value: usize,
+}
+
+impl Counter for Count {
+    fn version() -> u32 {
+        42
+    }
+
+    fn new(config: &Config) -> Self {
+        Self {
+            value: config.value,
+        }
+    }
+
+    fn with_name(name: &str) -> Option<Self> {
+        Some(Self { value: name.len() }).filter(|_| !name.is_empty())
+    }
+
+    fn parse(value: &str) -> Result<Self, usize> {
+        value
+            .parse()
+            .map(|value| Self { value })
+            .map_err(|_| value.len())
+    }
+
+    fn default_scale() -> usize {
+        3
+    }
+
+    fn count(&self) -> usize {
+        self.value * Self::default_scale()
+    }
+}
+
+#[test]
+fn call_static() {
+    let factory = CounterFactory::of_type::<Count>();
+    assert_eq!(factory.version(), 42);
+    assert_eq!(factory.default_scale(), 3);
+}
+
+#[test]
+fn construct_obj() {
+    let factory = CounterFactory::of_type::<Count>();
+    let obj: CounterBox = factory.new(&Config { value: 5 });
+    assert_eq!(obj.count(), 15);
+}
+
+#[test]
+fn construct_optional() {
+    let factory = CounterFactory::of_type::<Count>();
+    assert_eq!(factory.with_name("four").map(|o| o.count()), Some(12));
+    assert!(factory.with_name("").is_none());
+}
+
+#[test]
+fn construct_fallible() {
+    let factory = CounterFactory::of_type::<Count>();
+    assert_eq!(factory.parse("7").map(|o| o.count()).ok(), Some(21));
+    assert_eq!(factory.parse("seven").err(), Some(5));
+}
+
+#[cglue_trait]
+#[cglue_forward]
+pub trait Versioned {
+    fn version() -> u32;
+    fn create() -> Self
+    where
+        Self: Sized;
+
+    fn describe(&self) -> u32 {
+        7
+    }
+}
+
+impl Versioned for Count {
+    fn version() -> u32 {
+        2
+    }
+
+    fn create() -> Self {
+        Self { value: 1 }
+    }
+}
+
+cglue_trait_group!(CounterGroup, Counter, Versioned);
+
+cglue_impl_group!(Count, CounterGroup, Versioned);
+
+#[test]
+fn forward_static() {
+    assert_eq!(<crate::forward::Fwd<&Count> as Versioned>::version(), 2);
+}
+
+#[test]
+fn group_with_statics() {
+    let factory = VersionedFactory::of_type::<Count>();
+    assert_eq!(factory.version(), 2);
+
+    let group = group_obj!(Count { value: 2 } as CounterGroup);
+    assert_eq!(group.count(), 6);
+    let group = cast!(group impl Versioned).unwrap();
+    assert_eq!(group.describe(), 7);
+}
+
+#[cglue_trait]
+pub trait Holder<T: Copy> {
+    fn hold(value: T) -> Self
+    where
+        Self: Sized;
+
+    fn held(&self) -> T;
+}
+
+impl Holder<usize> for Count {
+    fn hold(value: usize) -> Self {
+        Self { value }
+    }
+
+    fn held(&self) -> usize {
+        self.value
+    }
+}
+
+#[test]
+fn construct_generic() {
+    let factory = HolderFactory::<usize>::of_type::<Count>();
+    let obj = factory.hold(9);
+    assert_eq!(obj.held(), 9);
+}
@@ -0,0 +1,48 @@
+//! These tests check groups that are shared across threads
+use super::auto_impl::*;
+use super::structs::*;
+use super::trait_defs::*;
+use super::trait_groups::*;
+use crate::boxed::CSyncBox;
+use cglue_macro::*;
+use std::sync::Arc;
+
+fn assert_send<T: Send>(_: &T) {}
+fn assert_sync<T: Send + Sync>(_: &T) {}
+
+#[test]
+fn use_send_group() {
+    let group: TestGroupBox = group_obj!(SA {} as TestGroup);
+    assert_send(&group);
+
+    let ret = std::thread::spawn(move || group.ta_1()).join().unwrap();
+    assert_eq!(ret, 5);
+}
+
+#[test]
+fn use_sync_group() {
+    let group: TestGroupSyncBox = group_obj!(CSyncBox::from(SA {}) as TestGroup);
+    assert_sync(&group);
+    assert!(as_ref!(&group impl TC).is_some());
+
+    let group = Arc::new(group);
+
+    let handles = (0..4)
+        .map(|_| {
+            let group = group.clone();
+            std::thread::spawn(move || group.ta_1())
+        })
+        .collect::<Vec<_>>();
+
+    for h in handles {
+        assert_eq!(h.join().unwrap(), 5);
+    }
+}
+
+#[test]
+fn use_sync_auto_group() {
+    let group: AutoGroupSyncBox = group_obj!(CSyncBox::from(SB {}) as AutoGroup);
+    assert_sync(&group);
+    assert!(as_ref!(&group impl TB).is_some());
+    assert!(as_ref!(&group impl TC).is_none());
+}
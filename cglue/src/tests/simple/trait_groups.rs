@@ -1,6 +1,8 @@
 //! These tests check definition and usage of different trait groups
 use super::structs::*;
 use super::trait_defs::*;
+use crate::boxed::CBox;
+use crate::trait_group::NoContext;
 use cglue_macro::*;
 
 cglue_trait_group!(TestGroup, TA, { TB, TC });
@@ -81,3 +83,37 @@ fn test_group_3() {
 
     tusize.tt_1(1usize);
 }
+
+#[test]
+fn test_group_try_into() {
+    let a = SA {};
+
+    let group = group_obj!(a as TestGroup);
+
+    let group = try_into!(group impl TB + TC).err().unwrap();
+    let group = try_into!(group impl TB).err().unwrap();
+
+    let mut cast = try_into!(group impl TC).ok().unwrap();
+    cast.tc_2();
+    assert_eq!(cast.ta_1(), 5);
+}
+
+#[test]
+fn test_group_variants() {
+    let a = SA {};
+
+    let group = group_obj!(a as TestGroup);
+
+    let cast: TestGroupWithTC<_, _> = cast!(group impl TC).unwrap();
+    assert_eq!(cast.ta_1(), 5);
+
+    let group: TestGroupBox = cast.into();
+
+    let mut cast = into!(group impl TC).unwrap();
+    cast.tc_2();
+
+    assert_eq!(
+        core::mem::size_of::<TestGroupWithTC<CBox<SA>, NoContext>>(),
+        core::mem::size_of::<TestGroupBaseBox<SA>>()
+    );
+}
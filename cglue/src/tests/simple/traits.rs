@@ -1,6 +1,14 @@
 //! These tests are intended to check more complex casting behaviour.
+use crate::arc::CArcSome;
+use crate::boxed::{CBox, CSliceBox};
+use crate::callback::*;
+use crate::iter::*;
+use crate::repr_cstring::ReprCString;
 use crate::slice::*;
+use crate::trait_obj;
+use crate::vec::CVec;
 use cglue_macro::*;
+use std::sync::Arc;
 
 #[cglue_trait]
 pub trait WithSlice {
@@ -53,13 +61,107 @@ pub trait WithInto {
     fn winto_1(&self, _into: impl Into<usize>) {}
 }
 
+#[cglue_trait]
+pub trait WithClosures {
+    fn wclos_1(&self, mut cb: impl FnMut(usize) -> bool) -> bool {
+        cb(1) && cb(2)
+    }
+    fn wclos_2(&self, cb: &mut dyn FnMut(usize)) {
+        cb(1);
+        cb(2);
+    }
+    fn wclos_3(&self, mut cb: impl FnMut(usize) -> usize) -> usize {
+        cb(1) + cb(2)
+    }
+    fn wclos_4(&self, cb: &mut dyn FnMut(usize) -> bool) -> bool {
+        cb(3)
+    }
+}
+
+#[cglue_trait]
+pub trait WithIterators {
+    fn witer_1(&self, iter: impl Iterator<Item = usize>) -> usize {
+        iter.sum()
+    }
+    fn witer_2(&self, iter: &mut dyn Iterator<Item = usize>) -> Option<usize> {
+        iter.next()
+    }
+}
+
+#[cglue_trait]
+pub trait WithStdContainers {
+    fn wstd_1(&self, vec: Vec<usize>) -> Vec<usize> {
+        vec.into_iter().rev().collect()
+    }
+    fn wstd_2(&self, s: String) -> String {
+        s + " world"
+    }
+    fn wstd_3(&self, mut b: Box<u64>) -> Box<u64> {
+        *b += 1;
+        b
+    }
+    fn wstd_4(&self, mut b: Box<[u8]>) -> Box<[u8]> {
+        b.iter_mut().for_each(|v| *v *= 2);
+        b
+    }
+    fn wstd_5(&self, arc: Arc<usize>) -> Arc<usize> {
+        arc
+    }
+}
+
+#[cglue_trait]
+#[cglue_forward]
+pub trait ObjSink {
+    fn osink_push(&mut self, val: usize);
+}
+
+#[cglue_trait]
+#[cglue_forward]
+pub trait ObjSource {
+    fn osrc_get(&self) -> usize;
+}
+
+#[cglue_trait]
+pub trait WithObjects {
+    fn wobj_1(&self, #[wrap_with_obj] src: impl ObjSource) -> usize {
+        src.osrc_get()
+    }
+    fn wobj_2(&self, #[wrap_with_obj] sink: &mut dyn ObjSink) {
+        sink.osink_push(1);
+        sink.osink_push(2);
+    }
+    fn wobj_3(&self, #[wrap_with_obj] src: &dyn ObjSource) -> usize {
+        src.osrc_get() * 2
+    }
+}
+
 struct Implementor {}
 
+struct Sink(Vec<usize>);
+
+impl ObjSink for Sink {
+    fn osink_push(&mut self, val: usize) {
+        self.0.push(val)
+    }
+}
+
+struct Source(usize);
+
+impl ObjSource for Source {
+    fn osrc_get(&self) -> usize {
+        self.0
+    }
+}
+
 impl WithSlice for Implementor {}
 impl WithOptions for Implementor {}
 impl WithIntResult for Implementor {}
 impl WithAliasIntResult for Implementor {}
 impl WithInto for Implementor {}
+impl WithClosures for Implementor {}
+impl WithIterators for Implementor {}
+impl WithStdContainers for Implementor {}
+impl WithObjects for Implementor {}
 
 type ICont<I, C> = crate::trait_group::CGlueObjContainer<I, crate::trait_group::NoContext, C>;
 type IRefCont<C> = ICont<&'static Implementor, C>;
@@ -70,6 +172,10 @@ type WOCont = IMutCont<WithOptionsRetTmp<crate::trait_group::NoContext>>;
 type WIRCont = IRefCont<WithIntResultRetTmp<crate::trait_group::NoContext>>;
 type WAIRCont = IRefCont<WithAliasIntResultRetTmp<crate::trait_group::NoContext>>;
 type WINTOCont = IRefCont<WithIntoRetTmp<crate::trait_group::NoContext>>;
+type WCLOSCont = IRefCont<WithClosuresRetTmp<crate::trait_group::NoContext>>;
+type WITERCont = IRefCont<WithIteratorsRetTmp<crate::trait_group::NoContext>>;
+type WSTDCont = IRefCont<WithStdContainersRetTmp<crate::trait_group::NoContext>>;
+type WOBJCont = IRefCont<WithObjectsRetTmp<crate::trait_group::NoContext>>;
 
 #[cfg(all(feature = "unwind_abi_default", not(__cglue_force_no_unwind_abi)))]
 macro_rules! extern_fn {
@@ -139,3 +245,96 @@ fn into_t_wrapped() {
     let vtbl = <&WithIntoVtbl<WINTOCont>>::default();
     let _: extern_fn!((&WINTOCont, usize)) = vtbl.winto_1();
 }
+
+#[test]
+fn closures_wrapped() {
+    let vtbl = <&WithClosuresVtbl<WCLOSCont>>::default();
+    let _: extern_fn!((&WCLOSCont, OpaqueCallback<usize>) -> bool) = vtbl.wclos_1();
+    let _: extern_fn!((&WCLOSCont, OpaqueCallback<usize>)) = vtbl.wclos_2();
+    let _: extern_fn!((&WCLOSCont, OpaqueCallbackRet<usize, usize>) -> usize) = vtbl.wclos_3();
+    let _: extern_fn!((&WCLOSCont, OpaqueCallback<usize>) -> bool) = vtbl.wclos_4();
+}
+
+#[test]
+fn iterators_wrapped() {
+    let vtbl = <&WithIteratorsVtbl<WITERCont>>::default();
+    let _: extern_fn!((&WITERCont, CIterator<usize>) -> usize) = vtbl.witer_1();
+    let _: extern_fn!((&WITERCont, CIterator<usize>) -> crate::option::COption<usize>) =
+        vtbl.witer_2();
+}
+
+#[test]
+fn std_containers_wrapped() {
+    let vtbl = <&WithStdContainersVtbl<WSTDCont>>::default();
+    let _: extern_fn!((&WSTDCont, CVec<usize>) -> CVec<usize>) = vtbl.wstd_1();
+    let _: extern_fn!((&WSTDCont, ReprCString) -> ReprCString) = vtbl.wstd_2();
+    let _: extern_fn!((&WSTDCont, CBox<u64>) -> CBox<'static, u64>) = vtbl.wstd_3();
+    let _: extern_fn!((&WSTDCont, CSliceBox<u8>) -> CSliceBox<'static, u8>) = vtbl.wstd_4();
+    let _: extern_fn!((&WSTDCont, CArcSome<usize>) -> CArcSome<usize>) = vtbl.wstd_5();
+}
+
+#[test]
+fn objects_wrapped() {
+    let vtbl = <&WithObjectsVtbl<WOBJCont>>::default();
+    let _: extern_fn!((&WOBJCont, ObjSourceUnsendBox) -> usize) = vtbl.wobj_1();
+    let _: extern_fn!((&WOBJCont, ObjSinkMut)) = vtbl.wobj_2();
+    let _: extern_fn!((&WOBJCont, ObjSourceRef) -> usize) = vtbl.wobj_3();
+}
+
+#[test]
+fn call_closures() {
+    let obj = trait_obj!(Implementor {} as WithClosures);
+
+    let mut seen = vec![];
+    assert!(!obj.wclos_1(|v| {
+        seen.push(v);
+        false
+    }));
+    assert_eq!(seen, [1]);
+
+    let mut seen = vec![];
+    obj.wclos_2(&mut |v| seen.push(v));
+    assert_eq!(seen, [1, 2]);
+
+    assert_eq!(obj.wclos_3(|v| v * 10), 30);
+    assert!(obj.wclos_4(&mut |v| v == 3));
+}
+
+#[test]
+fn call_iterators() {
+    let obj = trait_obj!(Implementor {} as WithIterators);
+
+    assert_eq!(obj.witer_1(1..4), 6);
+
+    let mut iter = 5..;
+    assert_eq!(obj.witer_2(&mut iter), Some(5));
+    assert_eq!(iter.next(), Some(6));
+}
+
+#[test]
+fn call_std_containers() {
+    let obj = trait_obj!(Implementor {} as WithStdContainers);
+
+    assert_eq!(obj.wstd_1(vec![1, 2, 3]), [3, 2, 1]);
+    assert_eq!(obj.wstd_2("hello".to_string()), "hello world");
+    assert_eq!(*obj.wstd_3(Box::new(41)), 42);
+    assert_eq!(&*obj.wstd_4(vec![1, 2].into_boxed_slice()), &[2, 4]);
+
+    let arc = Arc::new(42);
+    let ret = obj.wstd_5(arc.clone());
+    assert!(Arc::ptr_eq(&ret, &arc));
+    assert_eq!(Arc::strong_count(&arc), 2);
+}
+
+#[test]
+fn call_objects() {
+    let obj = trait_obj!(Implementor {} as WithObjects);
+
+    assert_eq!(obj.wobj_1(Source(21)), 21);
+
+    let mut sink = Sink(vec![]);
+    obj.wobj_2(&mut sink);
+    assert_eq!(sink.0, [1, 2]);
+
+    assert_eq!(obj.wobj_3(&Source(21)), 42);
+}
@@ -0,0 +1,48 @@
+//! These tests check usage of objects that are not `Send`
+use super::trait_defs::*;
+use super::trait_groups::*;
+use crate::boxed::CUnsendBox;
+use cglue_macro::*;
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[derive(Default)]
+struct RcState {
+    calls: Rc<Cell<usize>>,
+}
+
+impl TA for RcState {
+    extern "C" fn ta_1(&self) -> usize {
+        self.calls.set(self.calls.get() + 1);
+        self.calls.get()
+    }
+}
+
+cglue_impl_group!(RcState, TestGroup, {});
+
+#[test]
+fn use_unsend_obj() {
+    let state = RcState::default();
+    let calls = state.calls.clone();
+
+    let obj: TAUnsendBox = trait_obj!(CUnsendBox::from(state) as TA);
+
+    assert_eq!(obj.ta_1(), 1);
+    assert_eq!(obj.ta_1(), 2);
+    assert_eq!(calls.get(), 2);
+}
+
+#[test]
+fn use_unsend_group() {
+    let state = RcState::default();
+    let calls = state.calls.clone();
+
+    let group: TestGroupUnsendBox = group_obj!(CUnsendBox::from(state) as TestGroup);
+
+    assert_eq!(group.ta_1(), 1);
+    assert!(!check!(group impl TC));
+
+    std::mem::drop(group);
+
+    assert_eq!(Rc::strong_count(&calls), 1);
+}
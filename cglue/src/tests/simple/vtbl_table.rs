@@ -0,0 +1,91 @@
+//! These tests check groups that keep their vtables in a shared table.
+use super::structs::*;
+use super::trait_defs::*;
+use cglue_macro::*;
+
+cglue_trait_group!(#[vtbl_table] TableGroup, TA, { TB, TC });
+cglue_impl_group!(SA, TableGroup, { TC });
+cglue_impl_group!(SB, TableGroup, auto);
+
+cglue_trait_group!(InlineGroup, TA, { TB, TC });
+cglue_impl_group!(SA, InlineGroup, { TC });
+
+cglue_trait_group!(#[vtbl_table] TableDerived: InlineGroup, { TB }, { TC });
+cglue_impl_group!(SB, TableDerived, {});
+
+#[test]
+fn table_layout() {
+    assert_eq!(
+        core::mem::size_of::<TableGroupBox>(),
+        core::mem::size_of::<InlineGroupBox>() - 2 * core::mem::size_of::<usize>()
+    );
+}
+
+/// Table pointer is the first field of the object, right before the container.
+fn table_ptr<T>(obj: &T) -> usize {
+    unsafe { *(obj as *const T as *const usize) }
+}
+
+#[test]
+fn table_shared() {
+    let a = group_obj!(SA {} as TableGroup);
+    let b = group_obj!(SA {} as TableGroup);
+    let c: TableGroupBox = group_obj!(SB {} as TableGroup);
+
+    assert_eq!(table_ptr(&a), table_ptr(&b));
+    assert_ne!(table_ptr(&a), table_ptr(&c));
+}
+
+#[test]
+fn table_casts() {
+    let a = SA {};
+
+    let mut obj = group_obj!(a as TableGroup);
+
+    assert_eq!(obj.ta_1(), 5);
+    assert!(!check!(obj impl TB));
+    assert!(check!(obj impl TC));
+    assert!(as_ref!(obj impl TB + TC).is_none());
+
+    as_mut!(obj impl TC).unwrap().tc_2();
+
+    let obj = try_into!(obj impl TB).err().unwrap();
+    let cast = cast!(obj impl TC).unwrap();
+    assert_eq!(cast.ta_1(), 5);
+
+    let obj = TableGroupBox::from(cast);
+    let obj = into!(obj impl TC).unwrap();
+    assert_eq!(obj.ta_1(), 5);
+}
+
+#[test]
+fn table_auto() {
+    let obj = group_obj!(SB {} as TableGroup);
+
+    assert!(check!(obj impl TB));
+    assert!(!check!(obj impl TC));
+    assert_eq!(as_ref!(obj impl TB).unwrap().tb_1(2), 4);
+}
+
+#[test]
+fn table_derived() {
+    let b = SB {};
+
+    let obj = group_obj!(b as TableDerived);
+    assert_eq!(obj.tb_1(2), 4);
+
+    let obj = InlineGroupBox::from(obj);
+    assert!(check!(obj impl TB));
+    assert_eq!(obj.ta_1(), 6);
+}
+
+#[test]
+fn table_regroup() {
+    let a = SA {};
+
+    let obj = group_obj!(a as TableGroup);
+
+    let obj: InlineGroupBox = regroup!(obj as InlineGroup).ok().unwrap();
+    assert!(check!(obj impl TC));
+    assert!(!check!(obj impl TB));
+}
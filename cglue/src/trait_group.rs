@@ -187,6 +187,84 @@ impl<T, V, C, R> GetVtblBase<V> for CGlueTraitObj<'_, T, V, C, R> {
     }
 }
 
+/// Retrieve a vtable of a trait group, if the trait is implemented by the object.
+///
+/// Trait groups implement this for every one of their traits, be it mandatory, or optional. This
+/// is what allows `regroup!` to move vtables from one group to another.
+pub trait GetVtblOpt<'a, V> {
+    fn get_vtbl_opt(&self) -> Option<&'a V>;
+}
+
+/// Vtable reference, that is known at compile time.
+///
+/// Trait vtables implement this on references to them, for every container whose object
+/// implements the trait. This allows trait groups to build their vtables, and shared tables of
+/// them, in constant context.
+pub trait StaticVtbl: Sized {
+    const CGLUE_VTBL: Self;
+}
+
+/// Vtable of an optional trait, that the probed type does not implement.
+///
+/// Trait groups define `CGLUE_VTBL` as an inherent constant of their probes, whenever the probed
+/// type implements the trait at the probed index. Inherent constants take precedence, which allows
+/// `cglue_impl_group!` to detect implemented optional traits. This is the fallback otherwise.
+#[doc(hidden)]
+pub trait AutoSkip<V> {
+    const CGLUE_VTBL: Option<V> = None;
+}
+
+/// Vtable slot of a trait group variant.
+///
+/// Group variants are generic over the slots of the group's optional vtables. A slot is either a
+/// vtable reference, an optional vtable reference, or `()`, if the variant does not expose the
+/// vtable.
+pub trait VtblSlot<'a, V>: Copy {
+    /// Whether the slot requires the vtable to be available.
+    const REQUIRED: bool;
+
+    /// Build the slot out of the group's optional vtable.
+    ///
+    /// Returns `None`, if the slot requires a vtable that is not available.
+    fn from_opt_vtbl(vtbl: Option<&'a V>) -> Option<Self>;
+}
+
+impl<'a, V> VtblSlot<'a, V> for &'a V {
+    const REQUIRED: bool = true;
+
+    fn from_opt_vtbl(vtbl: Option<&'a V>) -> Option<Self> {
+        vtbl
+    }
+}
+
+impl<'a, V> VtblSlot<'a, V> for Option<&'a V> {
+    const REQUIRED: bool = false;
+
+    fn from_opt_vtbl(vtbl: Option<&'a V>) -> Option<Self> {
+        Some(vtbl)
+    }
+}
+
+impl<'a, V> VtblSlot<'a, V> for () {
+    const REQUIRED: bool = false;
+
+    fn from_opt_vtbl(_: Option<&'a V>) -> Option<Self> {
+        Some(())
+    }
+}
+
+/// Vtable slot that has the same layout as the group's optional vtable.
+///
+/// # Safety
+///
+/// The slot must be safe to transmute from `Option<&'a V>`, if
+/// [`from_opt_vtbl`](VtblSlot::from_opt_vtbl) returns `Some`, and back into it.
+pub unsafe trait OptVtblSlot<'a, V>: VtblSlot<'a, V> {}
+
+unsafe impl<'a, V> OptVtblSlot<'a, V> for &'a V {}
+
+unsafe impl<'a, V> OptVtblSlot<'a, V> for Option<&'a V> {}
+
 // Conversions into container type itself.
 // Needed when generated code returns Self
 
@@ -393,6 +471,19 @@ impl<T: InstanceBounds, C: ContextBounds, R> CGlueObjBase for CGlueObjContainer<
     }
 }
 
+/// CGlue compatible object.
+///
+/// This trait allows to retrieve the instance housing the object, such as the arc it is in.
+pub trait CGlueObjInst: CGlueObjBase {
+    fn cobj_inst(&self) -> (&Self::InstType, &Self::Context);
+}
+
+impl<T: InstanceBounds, C: ContextBounds, R> CGlueObjInst for CGlueObjContainer<T, C, R> {
+    fn cobj_inst(&self) -> (&T, &Self::Context) {
+        (&self.instance, &self.context)
+    }
+}
+
 impl<T: InstanceBounds, C: ContextBounds, R> CGlueObjRef<R> for CGlueObjContainer<T, C, R> {
     fn cobj_ref(&self) -> (&T::Target, &R, &Self::Context) {
         (self.instance.deref(), &self.ret_tmp, &self.context)
@@ -460,6 +551,160 @@ impl<T: InstanceBounds, V, C: ContextBounds, R> GetContainer for CGlueTraitObj<'
     }
 }
 
+/// Trait group object, that holds its container next to the vtables.
+///
+/// Trait groups, and their variants implement this, which provides them with [`GetContainer`].
+///
+/// # Safety
+///
+/// The container accessors must return the object's own container field. The rest of the fields
+/// must not have any drop glue, and must stay valid when the container gets replaced.
+pub unsafe trait GroupObj: Sized {
+    type ContType: CGlueObjBase;
+
+    fn cglue_cont(&self) -> &Self::ContType;
+
+    fn cglue_cont_mut(&mut self) -> &mut Self::ContType;
+}
+
+impl<T: GroupObj> GetContainer for T {
+    type ContType = <T as GroupObj>::ContType;
+
+    fn ccont_ref(&self) -> &Self::ContType {
+        self.cglue_cont()
+    }
+
+    fn ccont_mut(&mut self) -> &mut Self::ContType {
+        self.cglue_cont_mut()
+    }
+
+    fn into_ccont(self) -> Self::ContType {
+        let this = ManuallyDrop::new(self);
+        // Safety: the object is never used again, and the rest of its fields need not be dropped
+        unsafe { core::ptr::read(this.cglue_cont()) }
+    }
+
+    fn build_with_ccont(&self, container: Self::ContType) -> Self {
+        // Safety: the bitwise copy of the old container gets overwritten without being dropped,
+        // while the rest of the fields are kept as is.
+        unsafe {
+            let mut ret = ManuallyDrop::new(core::ptr::read(self));
+            core::ptr::write(ret.cglue_cont_mut(), container);
+            ManuallyDrop::into_inner(ret)
+        }
+    }
+}
+
+/// Variant of trait group `G`, with a subset of its optional traits exposed.
+pub trait GroupVariant<G>: Sized {
+    /// Build the variant out of the group, if all of its required vtables are present.
+    fn cglue_from_group(group: G) -> Result<Self, G>;
+}
+
+/// Group variant that shares the layout of trait group `G`.
+///
+/// # Safety
+///
+/// It must be valid to reinterpret the group as the variant, if [`cglue_fits`](Self::cglue_fits)
+/// returns `true`, and to reinterpret the variant as the group at all times.
+pub unsafe trait GroupVariantRef<G>: GroupVariant<G> {
+    /// Check whether all of the variant's required vtables are present in the group.
+    fn cglue_fits(group: &G) -> bool;
+
+    /// Reinterpret the group as the variant, if all of its required vtables are present.
+    fn cglue_from_group_ref(group: &G) -> Option<&Self> {
+        if Self::cglue_fits(group) {
+            // Safety: layouts are compatible, and vtable presence was checked beforehand
+            Some(unsafe { &*(group as *const G as *const Self) })
+        } else {
+            None
+        }
+    }
+
+    /// Reinterpret the group as the variant, if all of its required vtables are present.
+    fn cglue_from_group_mut(group: &mut G) -> Option<&mut Self> {
+        if Self::cglue_fits(group) {
+            // Safety: layouts are compatible, and vtable presence was checked beforehand
+            Some(unsafe { &mut *(group as *mut G as *mut Self) })
+        } else {
+            None
+        }
+    }
+
+    /// Cast the variant back into the original group.
+    fn cglue_into_group(self) -> G {
+        let this = ManuallyDrop::new(self);
+        // Safety: layouts are compatible
+        unsafe { core::ptr::read(&*this as *const Self as *const G) }
+    }
+}
+
+/// Trait group that can be cast to a variant with a specific set of optional traits.
+///
+/// `CGLUE_ID` identifies the set of traits. Groups implement this for every combination of their
+/// optional traits, and the cast macros pick the implementation through [`CastGroupExt`].
+pub trait CastGroup<const CGLUE_ID: u64>: Sized {
+    /// Variant that can be cast back into the group.
+    type Variant: GroupVariantRef<Self>;
+    /// Variant that only keeps the requested vtables.
+    type Final: GroupVariant<Self>;
+}
+
+/// Casts of trait groups to their variants.
+///
+/// Use `cast!`, `as_ref!`, `as_mut!`, `into!`, `try_into!`, and `check!` macros instead of calling
+/// these directly.
+#[doc(hidden)]
+pub trait CastGroupExt: GroupObj {
+    fn cglue_check<const CGLUE_ID: u64>(&self) -> bool
+    where
+        Self: CastGroup<CGLUE_ID>,
+    {
+        <Self as CastGroup<CGLUE_ID>>::Variant::cglue_fits(self)
+    }
+
+    fn cglue_into<const CGLUE_ID: u64>(self) -> Option<<Self as CastGroup<CGLUE_ID>>::Final>
+    where
+        Self: CastGroup<CGLUE_ID>,
+    {
+        <Self as CastGroup<CGLUE_ID>>::Final::cglue_from_group(self).ok()
+    }
+
+    fn cglue_try_into<const CGLUE_ID: u64>(
+        self,
+    ) -> Result<<Self as CastGroup<CGLUE_ID>>::Final, Self>
+    where
+        Self: CastGroup<CGLUE_ID>,
+    {
+        <Self as CastGroup<CGLUE_ID>>::Final::cglue_from_group(self)
+    }
+
+    fn cglue_cast<const CGLUE_ID: u64>(self) -> Option<<Self as CastGroup<CGLUE_ID>>::Variant>
+    where
+        Self: CastGroup<CGLUE_ID>,
+    {
+        <Self as CastGroup<CGLUE_ID>>::Variant::cglue_from_group(self).ok()
+    }
+
+    fn cglue_as_ref<const CGLUE_ID: u64>(&self) -> Option<&<Self as CastGroup<CGLUE_ID>>::Variant>
+    where
+        Self: CastGroup<CGLUE_ID>,
+    {
+        <Self as CastGroup<CGLUE_ID>>::Variant::cglue_from_group_ref(self)
+    }
+
+    fn cglue_as_mut<const CGLUE_ID: u64>(
+        &mut self,
+    ) -> Option<&mut <Self as CastGroup<CGLUE_ID>>::Variant>
+    where
+        Self: CastGroup<CGLUE_ID>,
+    {
+        <Self as CastGroup<CGLUE_ID>>::Variant::cglue_from_group_mut(self)
+    }
+}
+
+impl<T: GroupObj> CastGroupExt for T {}
+
 /// Convert a container into inner type.
 pub trait IntoInner {
     type InnerTarget;
@@ -474,6 +719,21 @@ pub trait IntoInner {
     unsafe fn into_inner(self) -> Self::InnerTarget;
 }
 
+/// Create a new `Arc` of the inner type from a shared container.
+pub trait CloneArc {
+    type ArcTarget;
+
+    /// Clone the container and return it as a standard `Arc`.
+    ///
+    /// # Safety
+    ///
+    /// The resulting `Arc` is freed using the global allocator of the caller, thus the container
+    /// must have been created by the same module. CGlue code generator only invokes this from
+    /// vtable functions, which are instantiated alongside the object, however, the same care needs
+    /// to be taken when working manually with this trait.
+    unsafe fn clone_arc(&self) -> std::sync::Arc<Self::ArcTarget>;
+}
+
 /// Trait for CGlue vtables.
 pub trait CGlueVtbl<T>: CGlueBaseVtbl {}
 
@@ -482,6 +742,17 @@ pub trait CGlueVtblCont: Sized {
     type ContType: CGlueObjBase;
 }
 
+/// Marker of the container a vtable is built for.
+///
+/// Vtables only hold functions, thus they can be shared across threads regardless of the
+/// container. Thread safety of CGlue objects is solely determined by their containers.
+#[repr(transparent)]
+#[cfg_attr(feature = "abi_stable", derive(::abi_stable::StableAbi))]
+pub struct CGlueContMarker<'a, C>(core::marker::PhantomData<&'a C>);
+
+unsafe impl<C> Send for CGlueContMarker<'_, C> {}
+unsafe impl<C> Sync for CGlueContMarker<'_, C> {}
+
 /// Trait for CGlue vtables.
 ///
 /// # Safety
@@ -1,4 +1,6 @@
+use crate::alloc::{CAllocator, CLayout};
 use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
 use std::prelude::v1::*;
 
 #[repr(C)]
@@ -7,8 +9,9 @@ pub struct CVec<T> {
     data: *mut T,
     len: usize,
     capacity: usize,
-    drop_fn: Option<unsafe extern "C" fn(*mut T, usize, usize)>,
+    drop_fn: Option<unsafe extern "C" fn(*mut T, usize, usize, Option<&'static CAllocator>)>,
     reserve_fn: extern "C" fn(&mut CVec<T>, size: usize) -> usize,
+    alloc: Option<&'static CAllocator>,
 }
 
 unsafe impl<T: Send> Send for CVec<T> {}
@@ -26,13 +29,60 @@ impl<T> From<Vec<T>> for CVec<T> {
             capacity,
             drop_fn: Some(cglue_drop_vec::<T>),
             reserve_fn: cglue_reserve_vec::<T>,
+            alloc: None,
         }
     }
 }
 
+/// Convert back into a `Vec`.
+///
+/// Elements get moved into a newly allocated vector, while the original buffer gets freed by
+/// its own drop function. This makes the conversion safe, regardless of which module created
+/// the vector.
+///
+/// # Examples
+///
+/// ```
+/// use cglue::alloc::CAllocator;
+/// use cglue::vec::CVec;
+///
+/// let mut vec = CVec::new_in(CAllocator::global());
+/// vec.extend(0..4u8);
+///
+/// assert_eq!(Vec::from(vec), [0, 1, 2, 3]);
+/// ```
+impl<T> From<CVec<T>> for Vec<T> {
+    fn from(mut vec: CVec<T>) -> Self {
+        let mut ret = Vec::with_capacity(vec.len);
+        unsafe {
+            core::ptr::copy_nonoverlapping(vec.data, ret.as_mut_ptr(), vec.len);
+            ret.set_len(vec.len);
+        }
+        vec.len = 0;
+        ret
+    }
+}
+
 impl<T: Clone> Clone for CVec<T> {
     fn clone(&self) -> Self {
-        Self::from(Vec::from(&**self))
+        match self.alloc {
+            Some(alloc) => {
+                let mut vec = Self::with_capacity_in(self.len, alloc);
+                vec.extend(self.iter().cloned());
+                vec
+            }
+            None => Self::from(Vec::from(&**self)),
+        }
+    }
+}
+
+impl<T> Extend<T> for CVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for v in iter {
+            self.push(v);
+        }
     }
 }
 
@@ -45,7 +95,7 @@ impl<T> Default for CVec<T> {
 impl<T> Drop for CVec<T> {
     fn drop(&mut self) {
         if let Some(drop_fn) = self.drop_fn {
-            unsafe { drop_fn(self.data, self.len, self.capacity) }
+            unsafe { drop_fn(self.data, self.len, self.capacity, self.alloc) }
         }
     }
 }
@@ -71,6 +121,56 @@ impl<T> core::ops::DerefMut for CVec<T> {
 }
 
 impl<T> CVec<T> {
+    /// Create a new empty vector on the given allocator.
+    ///
+    /// The vector will not allocate until elements are pushed onto it.
+    pub fn new_in(alloc: &'static CAllocator) -> Self {
+        Self::with_capacity_in(0, alloc)
+    }
+
+    /// Create a new empty vector with at least the specified capacity on the given allocator.
+    ///
+    /// All future allocations of the vector will be performed on the same allocator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cglue::alloc::CAllocator;
+    /// use cglue::vec::CVec;
+    ///
+    /// let mut cvec = CVec::with_capacity_in(2, CAllocator::global());
+    ///
+    /// assert_eq!(cvec.capacity(), 2);
+    ///
+    /// cvec.extend(0..4u32);
+    ///
+    /// assert_eq!(&cvec[..], &[0, 1, 2, 3]);
+    /// ```
+    pub fn with_capacity_in(capacity: usize, alloc: &'static CAllocator) -> Self {
+        let (data, capacity) = if core::mem::size_of::<T>() == 0 {
+            (NonNull::dangling(), usize::MAX)
+        } else {
+            let layout = CLayout::array::<T>(capacity).expect("capacity overflow");
+            (alloc.allocate(layout).cast(), capacity)
+        };
+
+        Self {
+            data: data.as_ptr(),
+            len: 0,
+            capacity,
+            drop_fn: Some(cglue_drop_vec::<T>),
+            reserve_fn: cglue_reserve_vec::<T>,
+            alloc: Some(alloc),
+        }
+    }
+
+    /// Get the allocator the vector was created on.
+    ///
+    /// Returns `None` if the vector was created on the global allocator of a module.
+    pub fn allocator(&self) -> Option<&'static CAllocator> {
+        self.alloc
+    }
+
     pub fn len(&self) -> usize {
         self.len
     }
@@ -128,6 +228,18 @@ impl<T> CVec<T> {
         }
     }
 
+    /// Shrink the capacity of the vector to match its length.
+    ///
+    /// This is only performed on vectors created with a [`CAllocator`], because vectors created
+    /// on the global allocator may belong to a different module.
+    pub fn shrink_to_fit(&mut self) {
+        if let Some(alloc) = self.alloc {
+            if core::mem::size_of::<T>() != 0 && self.capacity != self.len {
+                unsafe { cglue_realloc_vec(self, alloc, self.len) };
+            }
+        }
+    }
+
     pub fn pop(&mut self) -> Option<T> {
         if self.len == 0 {
             None
@@ -223,12 +335,88 @@ where
     }
 }
 
-unsafe extern "C" fn cglue_drop_vec<T>(data: *mut T, len: usize, capacity: usize) {
-    let _ = Vec::from_raw_parts(data, len, capacity);
+unsafe extern "C" fn cglue_drop_vec<T>(
+    data: *mut T,
+    len: usize,
+    capacity: usize,
+    alloc: Option<&'static CAllocator>,
+) {
+    match alloc {
+        Some(alloc) => {
+            core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(data, len));
+            if core::mem::size_of::<T>() != 0 {
+                if let Some(layout) = CLayout::array::<T>(capacity) {
+                    alloc.deallocate(NonNull::new_unchecked(data).cast(), layout);
+                }
+            }
+        }
+        None => {
+            let _ = Vec::from_raw_parts(data, len, capacity);
+        }
+    }
 }
 
 extern "C" fn cglue_reserve_vec<T>(vec: &mut CVec<T>, size: usize) -> usize {
-    let mut vec = TempVec::from(vec);
-    vec.reserve(size);
-    vec.capacity()
+    // Overflows can not unwind out of this function, because it is called across FFI boundary.
+    let required = vec
+        .len
+        .checked_add(size)
+        .unwrap_or_else(|| capacity_overflow());
+
+    if required <= vec.capacity {
+        return vec.capacity;
+    }
+
+    let capacity = core::cmp::max(vec.capacity.saturating_mul(2), required);
+
+    if CLayout::array::<T>(capacity).is_none() {
+        capacity_overflow();
+    }
+
+    match vec.alloc {
+        Some(alloc) => {
+            unsafe { cglue_realloc_vec(vec, alloc, capacity) };
+            vec.capacity
+        }
+        None => {
+            let mut vec = TempVec::from(vec);
+            let additional = capacity - vec.len();
+            vec.reserve_exact(additional);
+            vec.capacity()
+        }
+    }
+}
+
+/// Abort on vector capacity overflow.
+///
+/// This is used instead of panicking in functions that are called across FFI boundary.
+#[cold]
+fn capacity_overflow() -> ! {
+    #[cfg(feature = "std")]
+    {
+        std::eprintln!("CVec capacity overflow");
+        std::process::abort()
+    }
+    // Without `std` there is no way to abort directly, thus report the largest possible layout.
+    #[cfg(not(feature = "std"))]
+    std::alloc::handle_alloc_error(
+        core::alloc::Layout::from_size_align(isize::MAX as usize, 1)
+            .unwrap_or_else(|_| core::alloc::Layout::new::<u8>()),
+    )
+}
+
+/// Reallocate vector's buffer to given capacity.
+///
+/// # Safety
+///
+/// `T` must not be zero-sized, and the vector must be allocated on `alloc`.
+unsafe fn cglue_realloc_vec<T>(vec: &mut CVec<T>, alloc: &CAllocator, capacity: usize) {
+    let old_layout = CLayout::array::<T>(vec.capacity).unwrap();
+    let new_layout = CLayout::array::<T>(capacity).unwrap_or_else(|| capacity_overflow());
+    let data = NonNull::new_unchecked(vec.data).cast();
+    vec.data = alloc
+        .reallocate(data, old_layout, new_layout.size)
+        .cast()
+        .as_ptr();
+    vec.capacity = capacity;
 }
@@ -135,5 +135,5 @@ extern "C" {
 	}
 
 	// Define the header and opaque cast the plugin creation function.
-	PluginHeader PLUGIN_HEADER { ROOT_LAYOUT, (decltype(PLUGIN_HEADER.create))create_plugin };
+	PluginHeader PLUGIN_HEADER { PLUGIN_ABI_VERSION, PLUGIN_VERSION, ROOT_LAYOUT, (decltype(PLUGIN_HEADER.create))create_plugin };
 }
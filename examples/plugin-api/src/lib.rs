@@ -10,10 +10,10 @@ extern crate _abi_stable11 as abi_stable;
 
 pub use abi_stable::type_layout::TypeLayout;
 use abi_stable::StableAbi;
-use cglue::prelude::v1::{trait_group::compare_layouts, *};
+use cglue::plugin;
+use cglue::prelude::v1::*;
 use core::mem::MaybeUninit;
 use core::num::NonZeroI32;
-use libloading::{library_filename, Library, Symbol};
 
 #[cglue_trait]
 pub trait PluginInner<'a> {
@@ -70,6 +70,14 @@ cglue_trait_group!(FeaturesGroup, {
     Clone
 });
 
+/// Version of the plugin interface.
+///
+/// Plugins built for a different version are rejected by the loader.
+pub const PLUGIN_VERSION: u32 = 1;
+
+/// Interface identifier of plugins in library manifests.
+pub const PLUGIN_INTERFACE: &str = "plugin_api::PluginInner";
+
 /// Describes possible errors that can occur loading the library
 #[repr(u8)]
 #[derive(Debug, Clone, Copy)]
@@ -96,6 +104,17 @@ impl IntError for Error {
     }
 }
 
+impl From<plugin::Error> for Error {
+    fn from(err: plugin::Error) -> Self {
+        match err {
+            plugin::Error::NotFound(_) => Self::Path,
+            plugin::Error::Loading(_) => Self::Loading,
+            plugin::Error::Symbol(_) => Self::Symbol,
+            _ => Self::Abi,
+        }
+    }
+}
+
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{:?}", self)
@@ -106,54 +125,26 @@ impl std::error::Error for Error {}
 
 /// Plugin header that the API looks for.
 ///
-/// Plugins should define the header with name `PLUGIN_HEADER` with no mangling.
-#[repr(C)]
-pub struct PluginHeader {
-    pub layout: &'static TypeLayout,
-    pub create: extern "C" fn(&CArc<cglue::trait_group::c_void>) -> PluginInnerArcBox<'static>,
-}
+/// Plugins should define the header with [`declare_plugin!`](cglue::declare_plugin).
+pub type PluginHeader = plugin::PluginHeader<PluginInnerArcBox<'static>>;
 
 /// Load a plugin from a given library.
 ///
-/// # Safety
-///
-/// Input library must implement a correct `create_plugin` and `get_root_layout()` functions.
-/// Its signatures must be as follows:
+/// The library is searched for next to the current executable.
 ///
-/// `extern "C" fn crate_plugin(&CArc<T>) -> PluginInnerArcBox<'static>`
-/// `extern "C" fn get_root_layout() -> Option<&'static TypeLayout>`
+/// # Safety
 ///
-/// Where `T` is any type, since it's opaque. Meanwhile, `get_root_layout` should simply
-/// [call the one in this crate](self::get_root_layout). It is used to verify
-/// version mismatches.
+/// Input library must export a correct `PLUGIN_HEADER`, as defined with
+/// [`declare_plugin!`](cglue::declare_plugin).
 #[no_mangle]
 pub unsafe extern "C" fn load_plugin(
     name: ReprCStr<'_>,
     ok_out: &mut MaybeUninit<PluginInnerArcBox<'static>>,
 ) -> i32 {
-    load_plugin_impl(name.as_ref()).into_int_out_result(ok_out)
-}
-
-unsafe fn load_plugin_impl(name: &str) -> Result<PluginInnerArcBox<'static>, Error> {
-    let mut current_exe = std::env::current_exe().map_err(|_| Error::Path)?;
-    current_exe.set_file_name(library_filename(name));
-    let lib = Library::new(current_exe).map_err(|e| {
-        println!("{}", e);
-        Error::Loading
-    })?;
-
-    let header: Symbol<&'static PluginHeader> = lib.get(b"PLUGIN_HEADER\0").map_err(|e| {
-        println!("{}", e);
-        Error::Symbol
-    })?;
-    let header = header.into_raw();
-
-    if !compare_layouts(Some(ROOT_LAYOUT), Some(header.layout)).is_valid_strict() {
-        return Err(Error::Abi);
-    }
-
-    let arc = CArc::from(lib);
-    Ok((header.create)(&arc.into_opaque()))
+    plugin::Loader::new(PLUGIN_VERSION)
+        .load(name.as_ref())
+        .map_err(Error::from)
+        .into_int_out_result(ok_out)
 }
 
 /// Layout for the root vtable.
@@ -81,8 +81,13 @@ extern "C" fn create_plugin(lib: &CArc<cglue::trait_group::c_void>) -> PluginInn
     trait_obj!((KvRoot::default(), lib.clone()) as PluginInner)
 }
 
-#[no_mangle]
-pub static PLUGIN_HEADER: PluginHeader = PluginHeader {
-    layout: ROOT_LAYOUT,
-    create: create_plugin,
-};
+cglue::declare_plugin!(PluginInnerArcBox<'static>, PLUGIN_VERSION, create_plugin);
+
+// The same plugin is also listed in a manifest, which may hold any number of plugins.
+cglue::declare_plugin_manifest!((
+    "kv_store",
+    PLUGIN_INTERFACE,
+    PluginInnerArcBox<'static>,
+    PLUGIN_VERSION,
+    create_plugin
+));
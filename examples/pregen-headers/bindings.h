@@ -66,6 +66,24 @@
     size_t name##_count = 0; \
     Callback_c_void__##ty name = CALLBACK(ty, &name##_count, cb_count_##ty)
 
+// Constructs a callback with a return value
+#define CALLBACK_RET(ty, ret, ctx, func) \
+    (struct CallbackRet_c_void__##ty##__##ret){(ctx), (ret (*)(void *, ty))(func)}
+
+// Calls an owned callback (`CBoxCallback`)
+#define BOX_CALLBACK_CALL(cb, arg) ((cb).func((cb).context.instance, (arg)))
+
+// Drops an owned callback (`CBoxCallback`), or a one-shot callback (`COnceCallback`) that was
+// not called
+#define BOX_CALLBACK_DROP(cb) \
+    do { \
+        if ((cb).context.drop_fn) \
+            (cb).context.drop_fn((cb).context.instance, (cb).context.alloc); \
+    } while (0)
+
+// Calls a one-shot callback (`COnceCallback`). The callback is consumed and must not be dropped
+#define ONCE_CALLBACK_CALL(cb, arg) ((cb).func((cb).context, (arg)))
+
 #define BUF_ITER_SPEC(ty, ty2, name, buf, len) \
     struct BufferIterator name##_base = (struct BufferIterator){(const void *)(const ty2 *)buf, len, 0, sizeof(ty2)}; \
     CIterator_##ty name = (CIterator_##ty){ &name##_base, (int32_t (*)(void *, ty2 *))buf_iter_next }
@@ -106,21 +124,70 @@ typedef struct TypeLayout TypeLayout;
  */
 typedef const char *ReprCStr;
 
+/**
+ * FFI-safe memory layout.
+ *
+ * This is an equivalent of [`Layout`], with the same invariants - `align` must be a non-zero
+ * power of 2, and `size` rounded up to `align` must not overflow `isize`.
+ */
+typedef struct CLayout {
+    uintptr_t size;
+    uintptr_t align;
+} CLayout;
+
+/**
+ * FFI-safe allocator.
+ *
+ * This is a function table with an opaque `ctx` pointer, that gets passed to every function.
+ * Containers store a `'static` reference to the allocator that was used to create them, and
+ * use it to grow and free their memory.
+ *
+ * The functions follow semantics of [`GlobalAlloc`](std::alloc::GlobalAlloc). Namely, they
+ * are never called with zero-sized layouts, `alloc` and `realloc` return null on failure, and
+ * `realloc` keeps the alignment of the passed layout.
+ *
+ * # Safety
+ *
+ * Since the allocator is shared between all containers created with it, the functions must
+ * be safe to call from multiple threads at once.
+ */
+typedef struct CAllocator {
+    /**
+     * Opaque allocator state, passed to all functions.
+     */
+    const void *ctx;
+    /**
+     * Allocate a block of memory with given layout.
+     */
+    uint8_t *(*alloc)(const void *ctx, struct CLayout layout);
+    /**
+     * Resize a previously allocated block of memory to `new_size` bytes.
+     */
+    uint8_t *(*realloc)(const void *ctx, uint8_t *ptr, struct CLayout layout, uintptr_t new_size);
+    /**
+     * Free a previously allocated block of memory.
+     */
+    void (*dealloc)(const void *ctx, uint8_t *ptr, struct CLayout layout);
+} CAllocator;
+
 /**
  * FFI-safe box
  *
  * This box has a static self reference, alongside a custom drop function.
  *
  * The drop function can be called from anywhere, it will free on correct allocator internally.
+ * Boxes created with [`CBox::new_in`] also record the [`CAllocator`] they were allocated on.
  */
 typedef struct CBox_c_void {
     void *instance;
-    void (*drop_fn)(void*);
+    void (*drop_fn)(void*, const struct CAllocator*);
+    const struct CAllocator *alloc;
 } CBox_c_void;
 typedef struct CArc_c_void {
     const void *instance;
     const void *(*clone_fn)(const void*);
     void (*drop_fn)(const void*);
+    const struct CArcVtbl_c_void *vtbl;
 } CArc_c_void;
 
 typedef struct FeaturesGroupContainer_CBox_c_void_____CArc_c_void {
@@ -184,6 +251,53 @@ typedef struct Callback_c_void__KeyValue OpaqueCallback_KeyValue;
 
 typedef OpaqueCallback_KeyValue KeyValueCallback;
 
+/**
+ * FFI-safe Option.
+ *
+ * This type is not really meant for general use, but rather as a last-resort conversion for type
+ * wrapping.
+ *
+ * Typical workflow would include temporarily converting into/from COption. However, most of the
+ * commonly used methods of `Option` are available directly, for instance:
+ *
+ * ```
+ * use cglue::option::COption;
+ *
+ * let opt = COption::Some(2u8);
+ *
+ * assert_eq!(opt.map(|v| v * 2), COption::Some(4));
+ * assert_eq!(opt.and_then(|v| v.checked_sub(3).into()), COption::None);
+ * assert_eq!(opt.ok_or("none").unwrap(), 2);
+ * assert_eq!(opt.into_iter().sum::<u8>(), 2);
+ * ```
+ *
+ * The `?` operator is available by converting into `Option`:
+ *
+ * ```
+ * use cglue::option::COption;
+ *
+ * fn add(a: COption<u8>, b: COption<u8>) -> Option<u8> {
+ *     Option::<u8>::from(a)?.checked_add(Option::<u8>::from(b)?)
+ * }
+ *
+ * assert_eq!(add(COption::Some(1), COption::Some(2)), Some(3));
+ * assert_eq!(add(COption::Some(1), COption::None), None);
+ * ```
+ */
+typedef enum COption_usize_Tag {
+    None_usize,
+    Some_usize,
+} COption_usize_Tag;
+
+typedef struct COption_usize {
+    COption_usize_Tag tag;
+    union {
+        struct {
+            uintptr_t some;
+        };
+    };
+} COption_usize;
+
 /**
  * FFI compatible iterator.
  *
@@ -191,6 +305,11 @@ typedef OpaqueCallback_KeyValue KeyValueCallback;
  *
  * `CIterator<T>` implements `Iterator<Item = T>`.
  *
+ * It also implements `DoubleEndedIterator` and `ExactSizeIterator`, however, these are only
+ * functional if the underlying iterator provided the necessary functions. Calling `next_back`
+ * on an iterator that is not double-ended panics, and so does calling `len` on an iterator,
+ * whose size hint is not exact.
+ *
  * # Examples
  *
  * Using [`AsCIterator`](AsCIterator) helper:
@@ -220,10 +339,24 @@ typedef OpaqueCallback_KeyValue KeyValueCallback;
  *
  * assert_eq!(sum_all((&mut iter).into()), 385);
  * ```
+ *
+ * Double-ended iteration:
+ *
+ * ```
+ * use cglue::iter::CIterator;
+ *
+ * let mut iter = 0..4;
+ * let citer = CIterator::new_double_ended(&mut iter);
+ *
+ * assert_eq!(citer.len(), 4);
+ * assert_eq!(citer.rev().collect::<Vec<_>>(), [3, 2, 1, 0]);
+ * ```
  */
 typedef struct CIterator_i32 {
     void *iter;
     int32_t (*func)(void*, int32_t *out);
+    int32_t (*next_back)(void*, int32_t *out);
+    uintptr_t (*size_hint)(const void*, struct COption_usize *upper);
 } CIterator_i32;
 
 /**
@@ -248,17 +381,17 @@ typedef struct KeyValueStoreVtbl_FeaturesGroupContainer_CBox_c_void_____CArc_c_v
  * Trait group potentially implementing `MainFeature < > + :: cglue :: ext :: core :: clone :: Clone < > + KeyValueDumper < > + KeyValueStore < >` traits.
  *
  * Optional traits are not implemented here, however. There are numerous conversion
- * functions available for safely retrieving a concrete collection of traits.
+ * macros available for safely retrieving a concrete collection of traits.
  *
- * `check_impl_` functions allow to check if the object implements the wanted traits.
+ * `check!` macro allows to check if the object implements the wanted traits.
  *
- * `into_impl_` functions consume the object and produce a new final structure that
- * keeps only the required information.
+ * `into!`, and `try_into!` macros consume the object and produce a new final
+ * structure that keeps only the required information.
  *
- * `cast_impl_` functions merely check and transform the object into a type that can
+ * `cast!` macro merely checks and transforms the object into a type that can
  *be transformed back into `FeaturesGroup` without losing data.
  *
- * `as_ref_`, and `as_mut_` functions obtain references to safe objects, but do not
+ * `as_ref!`, and `as_mut!` macros obtain references to safe objects, but do not
  * perform any memory transformations either. They are the safest to use, because
  * there is no risk of accidentally consuming the whole object.
  */
@@ -272,6 +405,28 @@ typedef struct FeaturesGroup_CBox_c_void_____CArc_c_void {
 
 // Typedef for default container and context type
 typedef struct FeaturesGroup_CBox_c_void_____CArc_c_void FeaturesGroup;
+/**
+ * Function table of an FFI-safe Arc.
+ *
+ * All operations beyond simple clone and drop go through this table, so that they are always
+ * performed by the module that created the arc.
+ */
+typedef struct CArcVtbl_c_void {
+    const void *(*clone)(const void*);
+    void (*drop)(const void*);
+    uintptr_t (*strong_count)(const void*);
+    uintptr_t (*weak_count)(const void*);
+    void *(*get_mut)(const void*);
+    bool (*try_unwrap)(const void*, void*);
+    const void *(*downgrade)(const void*);
+    const void *(*upgrade)(const void*);
+    const void *(*weak_clone)(const void*);
+    void (*weak_drop)(const void*);
+    uintptr_t (*weak_strong_count)(const void*);
+    uintptr_t (*weak_weak_count)(const void*);
+    const uint8_t *(*module)(void);
+} CArcVtbl_c_void;
+
 /**
  * FFI-Safe Arc
  *
@@ -325,17 +480,17 @@ typedef struct KeyValueStoreVtbl_FeaturesGroupContainer_____c_void__CArc_c_void
  * Trait group potentially implementing `MainFeature < > + :: cglue :: ext :: core :: clone :: Clone < > + KeyValueDumper < > + KeyValueStore < >` traits.
  *
  * Optional traits are not implemented here, however. There are numerous conversion
- * functions available for safely retrieving a concrete collection of traits.
+ * macros available for safely retrieving a concrete collection of traits.
  *
- * `check_impl_` functions allow to check if the object implements the wanted traits.
+ * `check!` macro allows to check if the object implements the wanted traits.
  *
- * `into_impl_` functions consume the object and produce a new final structure that
- * keeps only the required information.
+ * `into!`, and `try_into!` macros consume the object and produce a new final
+ * structure that keeps only the required information.
  *
- * `cast_impl_` functions merely check and transform the object into a type that can
+ * `cast!` macro merely checks and transforms the object into a type that can
  *be transformed back into `FeaturesGroup` without losing data.
  *
- * `as_ref_`, and `as_mut_` functions obtain references to safe objects, but do not
+ * `as_ref!`, and `as_mut!` macros obtain references to safe objects, but do not
  * perform any memory transformations either. They are the safest to use, because
  * there is no risk of accidentally consuming the whole object.
  */
@@ -454,7 +609,7 @@ void ctx_arc_drop(CArc_c_void *self) {
     if (self->drop_fn && self->instance) self->drop_fn(self->instance);
 }
 void cont_box_drop(CBox_c_void *self) {
-    if (self->drop_fn && self->instance) self->drop_fn(self->instance);
+    if (self->drop_fn && self->instance) self->drop_fn(self->instance, self->alloc);
 }
 
 static inline struct FeaturesGroup_CBox_c_void_____CArc_c_void borrow_features(void *self)  {
@@ -88,6 +88,29 @@ struct alignas(alignof(T)) RustMaybeUninit {
  */
 using ReprCStr = const char*;
 
+/**
+ * Function table of an FFI-safe Arc.
+ *
+ * All operations beyond simple clone and drop go through this table, so that they are always
+ * performed by the module that created the arc.
+ */
+template<typename T>
+struct CArcVtbl {
+    const T *(*clone)(const T*);
+    void (*drop)(const T*);
+    uintptr_t (*strong_count)(const T*);
+    uintptr_t (*weak_count)(const T*);
+    T *(*get_mut)(const T*);
+    bool (*try_unwrap)(const T*, T*);
+    const T *(*downgrade)(const T*);
+    const T *(*upgrade)(const T*);
+    const T *(*weak_clone)(const T*);
+    void (*weak_drop)(const T*);
+    uintptr_t (*weak_strong_count)(const T*);
+    uintptr_t (*weak_weak_count)(const T*);
+    const uint8_t *(*module)(void);
+};
+
 /**
  * FFI-Safe Arc
  *
@@ -98,12 +121,14 @@ struct CArc {
     const T *instance;
     const T *(*clone_fn)(const T*);
     void (*drop_fn)(const T*);
+    const CArcVtbl<T> *vtbl;
 
     inline CArc clone() const noexcept {
         CArc ret;
         ret.instance = clone_fn(instance);
         ret.clone_fn = clone_fn;
         ret.drop_fn = drop_fn;
+        ret.vtbl = vtbl;
         return ret;
     }
 
@@ -117,35 +142,85 @@ struct CArc {
         instance = nullptr;
         clone_fn = nullptr;
         drop_fn = nullptr;
+        vtbl = nullptr;
     }
 };
 
+/**
+ * FFI-safe memory layout.
+ *
+ * This is an equivalent of [`Layout`], with the same invariants - `align` must be a non-zero
+ * power of 2, and `size` rounded up to `align` must not overflow `isize`.
+ */
+struct CLayout {
+    uintptr_t size;
+    uintptr_t align;
+};
+
+/**
+ * FFI-safe allocator.
+ *
+ * This is a function table with an opaque `ctx` pointer, that gets passed to every function.
+ * Containers store a `'static` reference to the allocator that was used to create them, and
+ * use it to grow and free their memory.
+ *
+ * The functions follow semantics of [`GlobalAlloc`](std::alloc::GlobalAlloc). Namely, they
+ * are never called with zero-sized layouts, `alloc` and `realloc` return null on failure, and
+ * `realloc` keeps the alignment of the passed layout.
+ *
+ * # Safety
+ *
+ * Since the allocator is shared between all containers created with it, the functions must
+ * be safe to call from multiple threads at once.
+ */
+struct CAllocator {
+    /**
+     * Opaque allocator state, passed to all functions.
+     */
+    const void *ctx;
+    /**
+     * Allocate a block of memory with given layout.
+     */
+    uint8_t *(*alloc)(const void *ctx, CLayout layout);
+    /**
+     * Resize a previously allocated block of memory to `new_size` bytes.
+     */
+    uint8_t *(*realloc)(const void *ctx, uint8_t *ptr, CLayout layout, uintptr_t new_size);
+    /**
+     * Free a previously allocated block of memory.
+     */
+    void (*dealloc)(const void *ctx, uint8_t *ptr, CLayout layout);
+};
+
 /**
  * FFI-safe box
  *
  * This box has a static self reference, alongside a custom drop function.
  *
  * The drop function can be called from anywhere, it will free on correct allocator internally.
+ * Boxes created with [`CBox::new_in`] also record the [`CAllocator`] they were allocated on.
  */
 template<typename T>
 struct CBox {
     T *instance;
-    void (*drop_fn)(T*);
+    void (*drop_fn)(T*, const CAllocator*);
+    const CAllocator *alloc;
 
     CBox() = default;
-    CBox(T *instance) : instance(instance), drop_fn(nullptr) {}
-    CBox(T *instance, void (*drop_fn)(T *)) : instance(instance), drop_fn(drop_fn) {}
+    CBox(T *instance) : instance(instance), drop_fn(nullptr), alloc(nullptr) {}
+    CBox(T *instance, void (*drop_fn)(T *, const CAllocator *)) : instance(instance), drop_fn(drop_fn), alloc(nullptr) {}
     template<typename U = T, class = typename std::enable_if<std::is_same<U, T>::value>::type, class = typename std::enable_if<!std::is_same<U, void>::value>::type>
-    CBox(U &&instance) : instance(new U(instance)), drop_fn(&CBox::delete_fn) {}
+    CBox(U &&instance) : instance(new U(instance)), drop_fn(&CBox::delete_fn), alloc(nullptr) {}
 
-    static void delete_fn(T *v) {
+    static void delete_fn(T *v, const CAllocator *) {
         delete v;
     }
 
     inline operator CBox<void> () const {
         CBox<void> ret;
         ret.instance = (void*)instance;
-        ret.drop_fn = (void(*)(void *))drop_fn;
+        ret.drop_fn = (void(*)(void *, const CAllocator *))drop_fn;
+        ret.alloc = alloc;
         return ret;
     }
 
@@ -153,18 +228,20 @@ struct CBox {
         CBox ret;
         ret.instance = new T;
         ret.drop_fn = &CBox::delete_fn;
+        ret.alloc = nullptr;
         return ret;
     }
 
     inline void drop() && noexcept {
         if (drop_fn && instance)
-            drop_fn(instance);
+            drop_fn(instance, alloc);
         forget();
     }
 
     inline void forget() noexcept {
         instance = nullptr;
         drop_fn = nullptr;
+        alloc = nullptr;
     }
 
     inline T *operator->() {
@@ -356,6 +433,56 @@ using OpaqueCallback = Callback<void, T>;
 
 using KeyValueCallback = OpaqueCallback<KeyValue>;
 
+/**
+ * FFI-safe Option.
+ *
+ * This type is not really meant for general use, but rather as a last-resort conversion for type
+ * wrapping.
+ *
+ * Typical workflow would include temporarily converting into/from COption. However, most of the
+ * commonly used methods of `Option` are available directly, for instance:
+ *
+ * ```
+ * use cglue::option::COption;
+ *
+ * let opt = COption::Some(2u8);
+ *
+ * assert_eq!(opt.map(|v| v * 2), COption::Some(4));
+ * assert_eq!(opt.and_then(|v| v.checked_sub(3).into()), COption::None);
+ * assert_eq!(opt.ok_or("none").unwrap(), 2);
+ * assert_eq!(opt.into_iter().sum::<u8>(), 2);
+ * ```
+ *
+ * The `?` operator is available by converting into `Option`:
+ *
+ * ```
+ * use cglue::option::COption;
+ *
+ * fn add(a: COption<u8>, b: COption<u8>) -> Option<u8> {
+ *     Option::<u8>::from(a)?.checked_add(Option::<u8>::from(b)?)
+ * }
+ *
+ * assert_eq!(add(COption::Some(1), COption::Some(2)), Some(3));
+ * assert_eq!(add(COption::Some(1), COption::None), None);
+ * ```
+ */
+template<typename T>
+struct COption {
+    enum class Tag {
+        None,
+        Some,
+    };
+
+    struct Some_Body {
+        T _0;
+    };
+
+    Tag tag;
+    union {
+        Some_Body some;
+    };
+};
+
 /**
  * FFI compatible iterator.
  *
@@ -363,6 +490,11 @@ using KeyValueCallback = OpaqueCallback<KeyValue>;
  *
  * `CIterator<T>` implements `Iterator<Item = T>`.
  *
+ * It also implements `DoubleEndedIterator` and `ExactSizeIterator`, however, these are only
+ * functional if the underlying iterator provided the necessary functions. Calling `next_back`
+ * on an iterator that is not double-ended panics, and so does calling `len` on an iterator,
+ * whose size hint is not exact.
+ *
  * # Examples
  *
  * Using [`AsCIterator`](AsCIterator) helper:
@@ -392,11 +524,25 @@ using KeyValueCallback = OpaqueCallback<KeyValue>;
  *
  * assert_eq!(sum_all((&mut iter).into()), 385);
  * ```
+ *
+ * Double-ended iteration:
+ *
+ * ```
+ * use cglue::iter::CIterator;
+ *
+ * let mut iter = 0..4;
+ * let citer = CIterator::new_double_ended(&mut iter);
+ *
+ * assert_eq!(citer.len(), 4);
+ * assert_eq!(citer.rev().collect::<Vec<_>>(), [3, 2, 1, 0]);
+ * ```
  */
 template<typename T>
 struct CIterator {
     void *iter;
-    int32_t (*func)(void*, MaybeUninit<T> *out);
+    int32_t (*func)(void*, T *out);
+    int32_t (*next_back)(void*, T *out);
+    uintptr_t (*size_hint)(const void*, COption<uintptr_t> *upper);
 
     class iterator : std::iterator<std::input_iterator_tag, T> {
         CIterator<T> *iter;
@@ -473,6 +619,8 @@ struct CPPIterator {
     {
         iter.iter = &iter - offsetof(CPPIterator<Container>, iter);
         iter.func = &CPPIterator::next;
+        iter.next_back = nullptr;
+        iter.size_hint = nullptr;
     }
 
     CPPIterator(CPPIterator &&o) {
@@ -540,17 +688,17 @@ constexpr KeyValueStoreVtblImpl() :
  * Trait group potentially implementing `MainFeature < > + :: cglue :: ext :: core :: clone :: Clone < > + KeyValueDumper < > + KeyValueStore < >` traits.
  *
  * Optional traits are not implemented here, however. There are numerous conversion
- * functions available for safely retrieving a concrete collection of traits.
+ * macros available for safely retrieving a concrete collection of traits.
  *
- * `check_impl_` functions allow to check if the object implements the wanted traits.
+ * `check!` macro allows to check if the object implements the wanted traits.
  *
- * `into_impl_` functions consume the object and produce a new final structure that
- * keeps only the required information.
+ * `into!`, and `try_into!` macros consume the object and produce a new final
+ * structure that keeps only the required information.
  *
- * `cast_impl_` functions merely check and transform the object into a type that can
+ * `cast!` macro merely checks and transforms the object into a type that can
  *be transformed back into `FeaturesGroup` without losing data.
  *
- * `as_ref_`, and `as_mut_` functions obtain references to safe objects, but do not
+ * `as_ref!`, and `as_mut!` macros obtain references to safe objects, but do not
  * perform any memory transformations either. They are the safest to use, because
  * there is no risk of accidentally consuming the whole object.
  */